@@ -0,0 +1,708 @@
+use hmac::{Hmac, Mac};
+use nom::multi::many0;
+use pcap_parser::{LegacyPcapBlock, Linktype, PcapBlockOwned, ToVec};
+use pnet::packet::ethernet::{EtherType, EtherTypes};
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
+use pnet::packet::ipv4::{self, Ipv4Packet, MutableIpv4Packet};
+use pnet::packet::ipv6::{Ipv6Packet, MutableIpv6Packet};
+use pnet::packet::tcp::{self, MutableTcpPacket, TcpPacket};
+use pnet::packet::udp::{self, MutableUdpPacket};
+use pnet::packet::Packet;
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::File;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::Path;
+
+use crate::encoders::pcap_builder::GCP_PORT;
+use crate::encoders::tlv_encoder::{encode_tlvs, TlvNode};
+use crate::parsers::dictionary::dictionary;
+use crate::parsers::gcp_parser::{rcp_offset, split_messages, GcpHeader, MessageId};
+use crate::parsers::pcap_reader::{read_pcap, PcapReadError};
+use crate::parsers::reassembly::Reassembly;
+use crate::parsers::tlv_def::{DataType, TlvDef};
+use crate::parsers::tlv_parser::parse_tlvs;
+use crate::reports::{ReportFormat, ReportTable};
+
+/// RCP sequence TLV, holding the R-PHY TLVs of the dictionary.
+const SEQUENCE: u8 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// Binary identifier. 6 byte ones are mapped as MAC addresses.
+    Identifier,
+    Text,
+    /// Geographic coordinate, the digits are zeroed.
+    Location,
+}
+
+/// Identifying TLVs besides the IP and MAC addresses, which are all rewritten.
+/// CcapCoreOwner holds a CoreId and maps the same way.
+const FIELDS: &[(&str, Field)] = &[
+    ("CoreId", Field::Identifier),
+    ("CcapCoreOwner", Field::Identifier),
+    ("SerialNumber", Field::Text),
+    ("UsBurstReceiverSerialNumber", Field::Text),
+    ("DeviceAlias", Field::Text),
+    ("DeviceLocationDescription", Field::Text),
+    ("GeoLocationLatitude", Field::Location),
+    ("GeoLocationLongitude", Field::Location),
+];
+
+#[derive(Debug)]
+pub enum AnonymizeError {
+    Io(std::io::Error),
+    Pcap(String),
+}
+
+impl fmt::Display for AnonymizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnonymizeError::Io(e) => write!(f, "{e}"),
+            AnonymizeError::Pcap(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<PcapReadError> for AnonymizeError {
+    fn from(e: PcapReadError) -> Self {
+        match e {
+            PcapReadError::Io(e) => AnonymizeError::Io(e),
+            PcapReadError::Pcap(e) => AnonymizeError::Pcap(format!("{e:?}")),
+        }
+    }
+}
+
+/// End of a frame holding the start of a GCP message continued in later frames.
+#[derive(Debug, Clone)]
+struct Cut {
+    frame: usize,
+    /// Bytes of the message at the end of the frame.
+    len: usize,
+    /// Length of the TCP segment ending the frame.
+    segment_len: usize,
+    /// Anonymized source and destination of the segment, for its checksum.
+    sockets: [SocketAddr; 2],
+}
+
+/// New end of a frame already anonymized: the anonymized start of a GCP message that was
+/// completed by a later frame, or zeros if it never was.
+#[derive(Debug, Clone)]
+pub struct Patch {
+    cut: Cut,
+    bytes: Vec<u8>,
+}
+
+impl Patch {
+    /// Number of the frame to patch, counting from 0.
+    pub fn frame(&self) -> usize {
+        self.cut.frame
+    }
+
+    /// Rewrites the end of the anonymized frame and its TCP checksum.
+    pub fn apply(&self, frame: &mut [u8]) {
+        let end = frame.len();
+        frame[end - self.bytes.len()..].copy_from_slice(&self.bytes);
+        let [src, dst] = self.cut.sockets;
+        set_tcp_checksum(&mut frame[end - self.cut.segment_len..], src.ip(), dst.ip());
+    }
+}
+
+fn set_tcp_checksum(segment: &mut [u8], src: IpAddr, dst: IpAddr) {
+    if let Some(mut tcp) = MutableTcpPacket::new(segment) {
+        let checksum = match (src, dst) {
+            (IpAddr::V4(s), IpAddr::V4(d)) => tcp::ipv4_checksum(&tcp.to_immutable(), &s, &d),
+            (IpAddr::V6(s), IpAddr::V6(d)) => tcp::ipv6_checksum(&tcp.to_immutable(), &s, &d),
+            _ => unreachable!(),
+        };
+        tcp.set_checksum(checksum);
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Rewrites identifying values with a keyed hash (HMAC-SHA256), so the same value maps
+/// to the same replacement in every frame and every capture anonymized with the same key.
+/// Replacements keep the length of the original, TCP sequence numbers stay valid.
+///
+/// A GCP message split over TCP segments is anonymized whole, with the frame completing it.
+/// The frames holding its start are then rewritten through the [`Patch`]es of
+/// [`Anonymizer::take_patches`].
+pub struct Anonymizer {
+    key: Vec<u8>,
+    /// Replacements made, keyed by kind and original value.
+    mapping: BTreeMap<(&'static str, String), String>,
+    reassembly: Reassembly,
+    /// Frames holding the start of the message kept by `reassembly`, for every TCP direction.
+    cuts: HashMap<[SocketAddr; 2], Vec<Cut>>,
+    patches: Vec<Patch>,
+    /// Frames anonymized since the last `finish`.
+    frames: usize,
+    blanked: usize,
+}
+
+impl Anonymizer {
+    pub fn new(key: &[u8]) -> Self {
+        Anonymizer {
+            key: key.to_vec(),
+            mapping: BTreeMap::new(),
+            reassembly: Reassembly::default(),
+            cuts: HashMap::new(),
+            patches: Vec::new(),
+            frames: 0,
+            blanked: 0,
+        }
+    }
+
+    /// `len` pseudo-random bytes for `value`, the domain separating the kinds of values.
+    fn digest(&self, domain: &str, value: &[u8], len: usize) -> Vec<u8> {
+        let mut out = Vec::with_capacity(len);
+        let mut counter = 0u32;
+
+        while out.len() < len {
+            let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("any key size");
+            mac.update(domain.as_bytes());
+            mac.update(&counter.to_be_bytes());
+            mac.update(value);
+            out.extend_from_slice(&mac.finalize().into_bytes());
+            counter += 1;
+        }
+        out.truncate(len);
+
+        out
+    }
+
+    fn record(&mut self, kind: &'static str, original: String, replacement: String) {
+        self.mapping.insert((kind, original), replacement);
+    }
+
+    pub fn ip(&mut self, ip: IpAddr) -> IpAddr {
+        if ip.is_unspecified() || ip.is_loopback() {
+            return ip;
+        }
+
+        let new = match ip {
+            IpAddr::V4(v4) => {
+                let h = self.digest("ipv4", &v4.octets(), 3);
+                // Multicast stays multicast, e.g. the StaticPwConfig group addresses.
+                let first = if v4.is_multicast() { 239 } else { 10 };
+                IpAddr::V4(Ipv4Addr::new(first, h[0], h[1], h[2]))
+            }
+            IpAddr::V6(v6) => {
+                let o = v6.octets();
+                // Multicast keeps flags and scope, link-local its prefix,
+                // anything else goes to the documentation prefix 2001:db8::/32.
+                let prefix: &[u8] = if v6.is_multicast() {
+                    &o[..2]
+                } else if o[0] == 0xfe && o[1] & 0xc0 == 0x80 {
+                    &o[..8]
+                } else {
+                    &[0x20, 0x01, 0x0d, 0xb8]
+                };
+                let mut b = prefix.to_vec();
+                b.extend(self.digest("ipv6", &o, 16 - prefix.len()));
+                let b: [u8; 16] = b.try_into().unwrap();
+                IpAddr::V6(Ipv6Addr::from(b))
+            }
+        };
+        self.record("ip", ip.to_string(), new.to_string());
+
+        new
+    }
+
+    pub fn mac(&mut self, mac: &[u8]) -> Vec<u8> {
+        // Broadcast and multicast addresses carry no identity.
+        if mac.len() != 6 || mac[0] & 0x01 != 0 || mac.iter().all(|b| *b == 0) {
+            return mac.to_vec();
+        }
+
+        // Locally administered unicast.
+        let mut new = self.digest("mac", mac, 6);
+        new[0] = (new[0] & 0xfc) | 0x02;
+        self.record("mac", to_hex(mac), to_hex(&new));
+
+        new
+    }
+
+    fn field(&mut self, field: Field, val: &[u8]) -> Vec<u8> {
+        let (kind, new) = match field {
+            Field::Identifier if val.len() == 6 => return self.mac(val),
+            Field::Identifier => ("identifier", self.digest("identifier", val, val.len())),
+            Field::Text => {
+                let hex = to_hex(&self.digest("text", val, val.len().div_ceil(2)));
+                ("text", hex.as_bytes()[..val.len()].to_vec())
+            }
+            Field::Location => (
+                "location",
+                val.iter()
+                    .map(|c| if c.is_ascii_digit() { b'0' } else { *c })
+                    .collect(),
+            ),
+        };
+        match field {
+            Field::Identifier => self.record(kind, to_hex(val), to_hex(&new)),
+            _ => self.record(
+                kind,
+                String::from_utf8_lossy(val).to_string(),
+                String::from_utf8_lossy(&new).to_string(),
+            ),
+        }
+
+        new
+    }
+
+    /// IP address TLV value, 4 or 16 bytes.
+    fn ip_bytes(&mut self, val: &[u8]) -> Vec<u8> {
+        let ip = match val.len() {
+            4 => IpAddr::from(<[u8; 4]>::try_from(val).unwrap()),
+            16 => IpAddr::from(<[u8; 16]>::try_from(val).unwrap()),
+            _ => return val.to_vec(),
+        };
+
+        match self.ip(ip) {
+            IpAddr::V4(ip) => ip.octets().to_vec(),
+            IpAddr::V6(ip) => ip.octets().to_vec(),
+        }
+    }
+
+    fn value(&mut self, def: &TlvDef, val: &[u8]) -> Vec<u8> {
+        if let Some((_, field)) = FIELDS.iter().find(|(n, _)| *n == def.name) {
+            return self.field(*field, val);
+        }
+
+        match def.data_type {
+            DataType::IpAddress => self.ip_bytes(val),
+            DataType::MacAddress => self.mac(val),
+            _ => val.to_vec(),
+        }
+    }
+
+    /// Anonymizes a stream of TLVs against `defs`. Returns None if it does not parse,
+    /// the caller then keeps the original bytes.
+    fn tlvs(&mut self, defs: &[TlvDef], input: &[u8]) -> Option<Vec<TlvNode>> {
+        let (rest, tlvs) = many0(parse_tlvs)(input).ok()?;
+        if !rest.is_empty() {
+            return None;
+        }
+
+        let nodes = tlvs
+            .iter()
+            .map(|t| match defs.iter().find(|d| d.typ == t.typ) {
+                Some(def) if def.data_type == DataType::Complex => {
+                    match self.tlvs(&def.sub, t.val) {
+                        Some(sub) => TlvNode::complex(t.typ, sub),
+                        None => TlvNode::from(t),
+                    }
+                }
+                Some(def) => TlvNode::bytes(t.typ, &self.value(def, t.val)),
+                None => TlvNode::from(t),
+            })
+            .collect();
+
+        Some(nodes)
+    }
+
+    /// Anonymizes an RCP message, keeping its type.
+    pub fn rcp(&mut self, input: &[u8]) -> Vec<u8> {
+        let anonymized = (|| {
+            let (&message_type, rest) = input.split_first()?;
+            let (_, sequences) = many0(parse_tlvs)(rest.get(2..)?).ok()?;
+            let sequences = sequences
+                .iter()
+                .map(|s| match (s.typ, self.tlvs(&dictionary().tlv, s.val)) {
+                    (SEQUENCE, Some(tlvs)) => TlvNode::complex(s.typ, tlvs),
+                    _ => TlvNode::from(s),
+                })
+                .collect::<Vec<_>>();
+            let body = encode_tlvs(&sequences).ok()?;
+
+            let mut out = vec![message_type];
+            out.extend_from_slice(&u16::try_from(body.len()).ok()?.to_be_bytes());
+            out.extend_from_slice(&body);
+            Some(out)
+        })();
+
+        anonymized.unwrap_or_else(|| input.to_vec())
+    }
+
+    /// Anonymizes one GCP message, header included, and recomputes its lengths.
+    fn gcp_message(&mut self, msg: &[u8]) -> Vec<u8> {
+        let body = &msg[10..];
+        let message_id = MessageId(msg[7]);
+
+        let body = match rcp_offset(message_id, body) {
+            Some(off) => [&body[..off], &self.rcp(&body[off..])[..]].concat(),
+            None => body.to_vec(),
+        };
+
+        let header = GcpHeader::for_message(
+            u16::from_be_bytes([msg[0], msg[1]]),
+            msg[6],
+            message_id,
+            body.len(),
+        );
+        match header {
+            Ok(mut header) => {
+                header.protocol_identifier = u16::from_be_bytes([msg[2], msg[3]]);
+                let mut out = Vec::with_capacity(10 + body.len());
+                header.encode_into(&mut out);
+                out.extend_from_slice(&body);
+                out
+            }
+            Err(_) => msg.to_vec(),
+        }
+    }
+
+    /// Zeros the ends of frames holding the start of a message that was not completed.
+    fn blank(&mut self, cuts: Vec<Cut>) {
+        for cut in cuts {
+            self.blanked += cut.len;
+            let bytes = vec![0; cut.len];
+            self.patches.push(Patch { cut, bytes });
+        }
+    }
+
+    /// Anonymizes the GCP messages of a TCP segment payload, sent between the anonymized
+    /// `sockets`. A message started by earlier segments of the direction is anonymized
+    /// whole, its start is patched into their frames. Returns the payload and the number of
+    /// bytes at its end starting a message continued in the next segment, kept as is for now.
+    fn gcp(&mut self, sockets: [SocketAddr; 2], seq: u32, payload: &[u8]) -> (Vec<u8>, usize) {
+        let stream = self.reassembly.stream(sockets, seq, payload);
+        let carried = stream.len() - payload.len();
+        let mut cuts = self.cuts.remove(&sockets).unwrap_or_default();
+        if carried == 0 {
+            // The segment continuing them was not captured.
+            self.blank(std::mem::take(&mut cuts));
+        }
+
+        let (messages, rest) = split_messages(&stream);
+        let mut out = Vec::with_capacity(stream.len());
+        for (i, msg) in messages.iter().enumerate() {
+            let mut new = self.gcp_message(msg);
+            // The start of the first one is written back in place, in the earlier frames.
+            if i == 0 && carried > 0 && new.len() != msg.len() {
+                self.blanked += msg.len();
+                new = vec![0; msg.len()];
+            }
+            out.extend(new);
+        }
+        out.extend_from_slice(rest);
+
+        // The carried bytes go back to the frames they came from.
+        let own = out.split_off(carried);
+        if !messages.is_empty() {
+            let mut head = &out[..];
+            for cut in cuts.drain(..) {
+                let (bytes, tail) = head.split_at(cut.len);
+                head = tail;
+                self.patches.push(Patch {
+                    cut,
+                    bytes: bytes.to_vec(),
+                });
+            }
+        }
+
+        let next = seq.wrapping_add(payload.len() as u32);
+        let kept = match self.reassembly.keep(sockets, next, rest) {
+            // Bytes of this segment, the rest of the stream if no message was completed.
+            true => rest.len().min(payload.len()),
+            false => {
+                self.blank(std::mem::take(&mut cuts));
+                0
+            }
+        };
+        if !cuts.is_empty() {
+            self.cuts.insert(sockets, cuts);
+        }
+
+        (own, kept)
+    }
+
+    /// TCP segment with the GCP payload anonymized, sent between the anonymized addresses.
+    /// Checksum left to the caller.
+    fn tcp(&mut self, segment: &[u8], src: IpAddr, dst: IpAddr) -> Vec<u8> {
+        let tcp = match TcpPacket::new(segment) {
+            Some(tcp) => tcp,
+            None => return segment.to_vec(),
+        };
+        let header_len = (usize::from(tcp.get_data_offset()) * 4).min(segment.len());
+        let is_gcp = tcp.get_source() == GCP_PORT || tcp.get_destination() == GCP_PORT;
+
+        if !is_gcp || tcp.payload().is_empty() {
+            return segment.to_vec();
+        }
+
+        let sockets = [
+            SocketAddr::new(src, tcp.get_source()),
+            SocketAddr::new(dst, tcp.get_destination()),
+        ];
+        let (payload, kept) = self.gcp(sockets, tcp.get_sequence(), tcp.payload());
+        let out = [&segment[..header_len], &payload[..]].concat();
+        if kept > 0 {
+            self.cuts.entry(sockets).or_default().push(Cut {
+                frame: self.frames,
+                len: kept,
+                segment_len: out.len(),
+                sockets,
+            });
+        }
+
+        out
+    }
+
+    /// Patches for the frames anonymized so far, to apply before writing them.
+    pub fn take_patches(&mut self) -> Vec<Patch> {
+        std::mem::take(&mut self.patches)
+    }
+
+    /// Ends a capture: the starts of messages still waiting for their end are zeroed,
+    /// see [`Anonymizer::take_patches`], and frames are counted from 0 again.
+    pub fn finish(&mut self) {
+        let cuts: Vec<Cut> = self.cuts.drain().flat_map(|(_, cuts)| cuts).collect();
+        self.blank(cuts);
+        self.reassembly = Reassembly::default();
+        self.frames = 0;
+    }
+
+    /// Bytes of GCP messages zeroed because they could not be anonymized whole.
+    pub fn blanked(&self) -> usize {
+        self.blanked
+    }
+
+    /// Anonymizes a frame of a capture with the given link type: link-layer and IP addresses,
+    /// and the GCP messages it carries. IP lengths and checksums are recomputed.
+    /// Frames of other link types are kept as is.
+    pub fn frame(&mut self, linktype: Linktype, frame: &[u8]) -> Vec<u8> {
+        let out = self.link(linktype, frame);
+        self.frames += 1;
+
+        out
+    }
+
+    fn link(&mut self, linktype: Linktype, frame: &[u8]) -> Vec<u8> {
+        match linktype {
+            Linktype::ETHERNET if frame.len() >= 14 => {
+                // 802.1Q and 802.1ad tags are kept, the packet after them is anonymized.
+                let mut tags = 12;
+                while frame.len() >= tags + 6
+                    && matches!(
+                        EtherType(u16::from_be_bytes([frame[tags], frame[tags + 1]])),
+                        EtherTypes::Vlan | EtherTypes::QinQ
+                    )
+                {
+                    tags += 4;
+                }
+                let ethertype = u16::from_be_bytes([frame[tags], frame[tags + 1]]);
+                [
+                    &self.mac(&frame[0..6])[..],
+                    &self.mac(&frame[6..12])[..],
+                    &frame[12..tags + 2],
+                    &self.l3(EtherType(ethertype), &frame[tags + 2..])[..],
+                ]
+                .concat()
+            }
+            // Linux cooked capture: packet type, address type and length, address, protocol.
+            Linktype::LINUX_SLL if frame.len() >= 16 => {
+                let address = match u16::from_be_bytes([frame[4], frame[5]]) {
+                    6 => [&self.mac(&frame[6..12])[..], &frame[12..14]].concat(),
+                    _ => frame[6..14].to_vec(),
+                };
+                let ethertype = u16::from_be_bytes([frame[14], frame[15]]);
+                [
+                    &frame[..6],
+                    &address[..],
+                    &frame[14..16],
+                    &self.l3(EtherType(ethertype), &frame[16..])[..],
+                ]
+                .concat()
+            }
+            // BSD loopback: 4 byte address family, in the byte order of the host.
+            Linktype::NULL | Linktype::LOOP if frame.len() >= 4 => {
+                [&frame[..4], &self.ip_packet(&frame[4..])[..]].concat()
+            }
+            Linktype::RAW | Linktype::IPV4 | Linktype::IPV6 => self.ip_packet(frame),
+            _ => frame.to_vec(),
+        }
+    }
+
+    fn l3(&mut self, ethertype: EtherType, packet: &[u8]) -> Vec<u8> {
+        match ethertype {
+            EtherTypes::Ipv4 => self.ipv4(packet),
+            EtherTypes::Ipv6 => self.ipv6(packet),
+            _ => packet.to_vec(),
+        }
+    }
+
+    /// IPv4 or IPv6 packet, told apart by the version.
+    fn ip_packet(&mut self, packet: &[u8]) -> Vec<u8> {
+        match packet.first().map(|b| b >> 4) {
+            Some(4) => self.ipv4(packet),
+            Some(6) => self.ipv6(packet),
+            _ => packet.to_vec(),
+        }
+    }
+
+    /// Transport segment of `protocol` with the GCP payload anonymized and the TCP or UDP
+    /// checksum recomputed for the new addresses.
+    fn transport(
+        &mut self,
+        protocol: IpNextHeaderProtocol,
+        segment: &[u8],
+        src: IpAddr,
+        dst: IpAddr,
+    ) -> Vec<u8> {
+        match protocol {
+            IpNextHeaderProtocols::Tcp => {
+                let mut out = self.tcp(segment, src, dst);
+                set_tcp_checksum(&mut out, src, dst);
+                out
+            }
+            IpNextHeaderProtocols::Udp => {
+                let mut out = segment.to_vec();
+                if let Some(mut udp) = MutableUdpPacket::new(&mut out) {
+                    // A zero checksum is not computed, IPv4 only.
+                    let checksum = match (src, dst) {
+                        (IpAddr::V4(_), _) if udp.get_checksum() == 0 => 0,
+                        (IpAddr::V4(s), IpAddr::V4(d)) => {
+                            udp::ipv4_checksum(&udp.to_immutable(), &s, &d)
+                        }
+                        (IpAddr::V6(s), IpAddr::V6(d)) => {
+                            udp::ipv6_checksum(&udp.to_immutable(), &s, &d)
+                        }
+                        _ => unreachable!(),
+                    };
+                    udp.set_checksum(checksum);
+                }
+                out
+            }
+            _ => segment.to_vec(),
+        }
+    }
+
+    fn ipv4(&mut self, packet: &[u8]) -> Vec<u8> {
+        let ip = match Ipv4Packet::new(packet) {
+            Some(ip) if packet.len() >= usize::from(ip.get_header_length()) * 4 => ip,
+            _ => return packet.to_vec(),
+        };
+        let header_len = usize::from(ip.get_header_length()) * 4;
+        let (src, dst) = match (
+            self.ip(ip.get_source().into()),
+            self.ip(ip.get_destination().into()),
+        ) {
+            (IpAddr::V4(s), IpAddr::V4(d)) => (s, d),
+            _ => unreachable!(),
+        };
+
+        let payload = self.transport(
+            ip.get_next_level_protocol(),
+            ip.payload(),
+            src.into(),
+            dst.into(),
+        );
+
+        let mut out = [&packet[..header_len], &payload[..]].concat();
+        let mut ip = MutableIpv4Packet::new(&mut out).unwrap();
+        ip.set_source(src);
+        ip.set_destination(dst);
+        ip.set_total_length((header_len + payload.len()) as u16);
+        let checksum = ipv4::checksum(&ip.to_immutable());
+        ip.set_checksum(checksum);
+
+        out
+    }
+
+    fn ipv6(&mut self, packet: &[u8]) -> Vec<u8> {
+        let ip = match Ipv6Packet::new(packet) {
+            Some(ip) => ip,
+            None => return packet.to_vec(),
+        };
+        let (src, dst) = match (
+            self.ip(ip.get_source().into()),
+            self.ip(ip.get_destination().into()),
+        ) {
+            (IpAddr::V6(s), IpAddr::V6(d)) => (s, d),
+            _ => unreachable!(),
+        };
+
+        let payload = self.transport(ip.get_next_header(), ip.payload(), src.into(), dst.into());
+
+        let mut out = [&packet[..40], &payload[..]].concat();
+        let mut ip = MutableIpv6Packet::new(&mut out).unwrap();
+        ip.set_source(src);
+        ip.set_destination(dst);
+        ip.set_payload_length(payload.len() as u16);
+
+        out
+    }
+
+    /// Replacements made so far as CSV: kind, original, anonymized.
+    pub fn mapping_csv(&self) -> String {
+        let mut table = ReportTable::new(&["kind", "original", "anonymized"]);
+        for ((kind, original), new) in self.mapping.iter() {
+            table.push(vec![
+                Value::from(*kind),
+                Value::from(original.as_str()),
+                Value::from(new.as_str()),
+            ]);
+        }
+
+        table.render(ReportFormat::Csv)
+    }
+}
+
+/// Reads the capture at `input` and writes the anonymized capture to `output`, keeping its
+/// header, so its link type and timestamp precision, and the original length of the frames.
+/// A last frame cut short is dropped. Returns the number of frames written.
+pub fn anonymize_file(
+    anonymizer: &mut Anonymizer,
+    input: &Path,
+    output: &Path,
+) -> Result<usize, AnonymizeError> {
+    let file = File::open(input).map_err(AnonymizeError::Io)?;
+    let mut header = Vec::new();
+    let mut linktype = Linktype::ETHERNET;
+    // Kept until the end of the capture, a later frame may patch them.
+    let mut records = Vec::new();
+
+    read_pcap(file, false, |block| match block {
+        PcapBlockOwned::LegacyHeader(mut hdr) => {
+            linktype = hdr.network;
+            // Records are written little-endian.
+            if hdr.is_bigendian() {
+                hdr.magic_number = hdr.magic_number.swap_bytes();
+            }
+            header = hdr.to_vec_raw().unwrap();
+        }
+        PcapBlockOwned::Legacy(b) => {
+            let data = &b.data[..(b.caplen as usize).min(b.data.len())];
+            let data = anonymizer.frame(linktype, data);
+            records.push((b.ts_sec, b.ts_usec, b.origlen, data));
+        }
+        PcapBlockOwned::NG(_) => unreachable!(),
+    })?;
+
+    anonymizer.finish();
+    for patch in anonymizer.take_patches() {
+        patch.apply(&mut records[patch.frame()].3);
+    }
+
+    let mut out = header;
+    for (ts_sec, ts_usec, origlen, data) in records.iter() {
+        let block = LegacyPcapBlock {
+            ts_sec: *ts_sec,
+            ts_usec: *ts_usec,
+            caplen: data.len() as u32,
+            // Lengths are kept, unless the frame was not captured whole.
+            origlen: (*origlen).max(data.len() as u32),
+            data,
+        };
+        out.extend(block.to_vec_raw().unwrap());
+    }
+    std::fs::write(output, out).map_err(AnonymizeError::Io)?;
+
+    Ok(records.len())
+}
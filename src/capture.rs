@@ -0,0 +1,223 @@
+use pcap_parser::{LegacyPcapBlock, Linktype, PcapHeader};
+use pnet::datalink::{self, Channel, DataLinkReceiver};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::parsers::gcp_parser::{FrameHandler, ParseOptions};
+use crate::parsers::pcap_reader::SNAP_LEN;
+
+/// How long a frame is kept to be matched with its copy, see [`Duplicates`].
+const DUPLICATE_WINDOW: Duration = Duration::from_millis(10);
+
+#[derive(Debug)]
+pub enum CaptureError {
+    UnknownInterface(String),
+    Open(io::Error),
+    UnsupportedChannel,
+    Read(io::Error),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::UnknownInterface(name) => write!(f, "unknown interface '{name}'"),
+            CaptureError::Open(e) => write!(f, "opening capture: {e}"),
+            CaptureError::UnsupportedChannel => write!(f, "interface is not an Ethernet channel"),
+            CaptureError::Read(e) => write!(f, "reading frame: {e}"),
+        }
+    }
+}
+
+/// Frames already seen, to drop the copies of a frame captured twice, e.g. once sent and
+/// once received on the loopback interface. Each frame matches a single copy, and only
+/// within [`DUPLICATE_WINDOW`], so identical frames sent later, like retransmissions, are kept.
+#[derive(Debug, Default)]
+struct Duplicates {
+    recent: VecDeque<(Instant, Vec<u8>)>,
+}
+
+impl Duplicates {
+    fn is_duplicate(&mut self, now: Instant, data: &[u8]) -> bool {
+        while let Some((seen, _)) = self.recent.front() {
+            if now.duration_since(*seen) <= DUPLICATE_WINDOW {
+                break;
+            }
+            self.recent.pop_front();
+        }
+
+        match self.recent.iter().position(|(_, d)| d == data) {
+            Some(i) => {
+                self.recent.remove(i);
+                true
+            }
+            None => {
+                self.recent.push_back((now, data.to_vec()));
+                false
+            }
+        }
+    }
+}
+
+/// Where live frames come from, an interface or, in tests, frames given in advance.
+trait FrameSource {
+    fn next_frame(&mut self) -> io::Result<&[u8]>;
+}
+
+impl FrameSource for Box<dyn DataLinkReceiver> {
+    fn next_frame(&mut self) -> io::Result<&[u8]> {
+        self.next()
+    }
+}
+
+/// Decodes the frames received on `interface` as they arrive, until an error occurs.
+/// The filter of `options` applies as for a file, e.g. its ports to keep only GCP.
+/// With `dedupe`, the second copy of a frame captured twice is dropped.
+/// Needs the capture privileges of a raw socket, CAP_NET_RAW on Linux.
+pub fn capture(interface: &str, options: &ParseOptions, dedupe: bool) -> Result<(), CaptureError> {
+    let iface = datalink::interfaces()
+        .into_iter()
+        .find(|i| i.name == interface)
+        .ok_or_else(|| CaptureError::UnknownInterface(interface.to_string()))?;
+
+    let config = datalink::Config {
+        read_buffer_size: SNAP_LEN,
+        ..Default::default()
+    };
+    // Promiscuous mode is refused by some interfaces and sandboxes, the traffic to and
+    // from the host is still captured without it.
+    let channel = datalink::channel(&iface, config).or_else(|_| {
+        let config = datalink::Config {
+            promiscuous: false,
+            ..config
+        };
+        datalink::channel(&iface, config)
+    });
+    let mut rx = match channel.map_err(CaptureError::Open)? {
+        Channel::Ethernet(_, rx) => rx,
+        _ => return Err(CaptureError::UnsupportedChannel),
+    };
+
+    let mut handler = FrameHandler::new(options);
+    handler.header(PcapHeader {
+        snaplen: SNAP_LEN as u32,
+        network: Linktype::ETHERNET,
+        ..PcapHeader::new()
+    });
+
+    handle_frames(&mut rx, &mut handler, dedupe)
+}
+
+/// Hands every frame of `source` to `handler`, time stamped as it arrives, until an error occurs.
+fn handle_frames<S: FrameSource>(
+    source: &mut S,
+    handler: &mut FrameHandler,
+    dedupe: bool,
+) -> Result<(), CaptureError> {
+    let mut duplicates = Duplicates::default();
+
+    loop {
+        let data = source.next_frame().map_err(CaptureError::Read)?;
+        if dedupe && duplicates.is_duplicate(Instant::now(), data) {
+            continue;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let block = LegacyPcapBlock {
+            ts_sec: now.as_secs() as u32,
+            ts_usec: now.subsec_micros(),
+            caplen: data.len() as u32,
+            origlen: data.len() as u32,
+            data,
+        };
+        handler.frame(&block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoders::pcap_builder::{tcp_frame, TcpSegment, GCP_PORT};
+    use crate::parsers::filter::FrameFilter;
+    use pnet::packet::tcp::TcpFlags;
+
+    /// Frames received one after the other, then a read error once they are all taken.
+    struct Frames {
+        frames: VecDeque<Vec<u8>>,
+        current: Vec<u8>,
+    }
+
+    impl FrameSource for Frames {
+        fn next_frame(&mut self) -> io::Result<&[u8]> {
+            self.current = self
+                .frames
+                .pop_front()
+                .ok_or(io::ErrorKind::UnexpectedEof)?;
+            Ok(&self.current)
+        }
+    }
+
+    #[test]
+    fn drops_one_copy_within_the_window() {
+        let mut duplicates = Duplicates::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert!(!duplicates.is_duplicate(at(0), b"a"));
+        assert!(!duplicates.is_duplicate(at(0), b"b"));
+        // Copies interleaved with other frames.
+        assert!(duplicates.is_duplicate(at(1), b"a"));
+        assert!(duplicates.is_duplicate(at(1), b"b"));
+        // A third identical frame is a new one.
+        assert!(!duplicates.is_duplicate(at(2), b"a"));
+        // Identical frames further apart are kept, e.g. a retransmission.
+        assert!(!duplicates.is_duplicate(at(200), b"a"));
+    }
+
+    #[test]
+    fn filters_and_writes_the_frames_received() {
+        let segment = |port: u16| {
+            tcp_frame(&TcpSegment {
+                src: "10.0.0.2:40000".parse().unwrap(),
+                dst: format!("10.0.0.1:{port}").parse().unwrap(),
+                seq: 1,
+                ack: 1,
+                flags: TcpFlags::ACK,
+                payload: &[],
+            })
+        };
+        let (gcp, http) = (segment(GCP_PORT), segment(80));
+        let file = format!("pcap_rparser-{}-live.pcap", std::process::id());
+        let path = std::env::temp_dir().join(file);
+        let options = ParseOptions {
+            filter: FrameFilter {
+                ports: vec![GCP_PORT],
+                ..FrameFilter::default()
+            },
+            write: Some(path.clone()),
+            ..ParseOptions::default()
+        };
+        let mut handler = FrameHandler::new(&options);
+        handler.header(PcapHeader {
+            network: Linktype::ETHERNET,
+            ..PcapHeader::new()
+        });
+
+        // The copy is dropped, the frame to port 80 filtered out, the last frame kept.
+        let mut source = Frames {
+            frames: VecDeque::from([gcp.clone(), gcp.clone(), http, gcp.clone()]),
+            current: Vec::new(),
+        };
+        let end = handle_frames(&mut source, &mut handler, true);
+        assert!(
+            matches!(end, Err(CaptureError::Read(e)) if e.kind() == io::ErrorKind::UnexpectedEof)
+        );
+
+        let written = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written.len(), 24 + 2 * (16 + gcp.len()));
+        assert!(written.ends_with(&gcp));
+    }
+}
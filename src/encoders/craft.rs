@@ -0,0 +1,581 @@
+use eui48::MacAddress;
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::fmt;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::path::Path;
+
+use crate::encoders::gcp_encoder::{Edr, Eds, GcpMessage, Mwr};
+use crate::encoders::pcap_builder::{CaptureBuilder, Direction, TcpFlow, GCP_PORT};
+use crate::encoders::rcp_encoder::{RcpMessage, Sequence};
+use crate::encoders::tlv_encoder::{EncodeError, TlvNode};
+use crate::parsers::date_and_time::{DateAndTime, DateAndTimeError};
+use crate::parsers::dictionary::dictionary;
+use crate::parsers::gcp_parser::MessageId;
+use crate::parsers::rcp_parser::{Operation, RcpMessageType};
+use crate::parsers::tlv_def::{DataType, EnumValue, TlvDef};
+use crate::parsers::vendor::CABLELABS_VENDOR_ID;
+
+#[derive(Debug)]
+pub enum CraftError {
+    Io(std::io::Error),
+    Yaml(serde_yaml::Error),
+    Encode(EncodeError),
+    UnknownMessage(String),
+    UnknownTlv(String, String),
+    InvalidValue(String, String),
+}
+
+impl fmt::Display for CraftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CraftError::Io(e) => write!(f, "{e}"),
+            CraftError::Yaml(e) => write!(f, "{e}"),
+            CraftError::Encode(e) => write!(f, "{e}"),
+            CraftError::UnknownMessage(m) => write!(f, "unknown message '{m}'"),
+            CraftError::UnknownTlv(parent, name) => write!(f, "unknown TLV '{name}' in {parent}"),
+            CraftError::InvalidValue(name, reason) => write!(f, "{name}: {reason}"),
+        }
+    }
+}
+
+impl From<EncodeError> for CraftError {
+    fn from(e: EncodeError) -> Self {
+        CraftError::Encode(e)
+    }
+}
+
+fn invalid(name: &str, reason: &str) -> CraftError {
+    CraftError::InvalidValue(name.to_string(), reason.to_string())
+}
+
+fn default_vendor_id() -> u32 {
+    CABLELABS_VENDOR_ID
+}
+
+fn default_src() -> IpAddr {
+    Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1).into()
+}
+
+fn default_dst() -> IpAddr {
+    Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2).into()
+}
+
+fn default_src_port() -> u16 {
+    GCP_PORT
+}
+
+fn default_dst_port() -> u16 {
+    40000
+}
+
+/// Addresses of the synthetic capture. Defaults to the Core (port 8190) sending to the RPD.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PcapDescription {
+    #[serde(default = "default_src")]
+    pub src: IpAddr,
+    #[serde(default = "default_dst")]
+    pub dst: IpAddr,
+    #[serde(default = "default_src_port")]
+    pub src_port: u16,
+    #[serde(default = "default_dst_port")]
+    pub dst_port: u16,
+}
+
+impl Default for PcapDescription {
+    fn default() -> Self {
+        PcapDescription {
+            src: default_src(),
+            dst: default_dst(),
+            src_port: default_src_port(),
+            dst_port: default_dst_port(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SequenceDescription {
+    pub sequence_number: u16,
+    pub operation: String,
+    /// TLVs by name, as a mapping or a list of single entry mappings to repeat a TLV.
+    #[serde(default)]
+    pub tlvs: Value,
+}
+
+/// Human-readable description of a GCP message, see `examples/craft/rex-write-rfchannel.yaml`.
+/// Fields not used by the message type are ignored.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CraftDescription {
+    /// GCP message, e.g. EdsRequest, NotifyRequest or DmResponse.
+    pub message: String,
+    #[serde(default)]
+    pub transaction_id: u16,
+    #[serde(default)]
+    pub unit_id: u8,
+    #[serde(default)]
+    pub mode: u8,
+    #[serde(default)]
+    pub port: u16,
+    #[serde(default)]
+    pub channel: u16,
+    #[serde(default = "default_vendor_id")]
+    pub vendor_id: u32,
+    #[serde(default)]
+    pub vendor_index: u8,
+    #[serde(default)]
+    pub status: u8,
+    #[serde(default)]
+    pub event_code: u32,
+    #[serde(default)]
+    pub return_code: u8,
+    #[serde(default)]
+    pub command: u8,
+    #[serde(default)]
+    pub address: u32,
+    #[serde(default)]
+    pub and_mask: u16,
+    #[serde(default)]
+    pub or_mask: u16,
+    /// RCP message type: IRA, REX or NTF. Defaults to NTF for Notify, REX otherwise.
+    #[serde(default)]
+    pub rcp: Option<String>,
+    #[serde(default)]
+    pub sequences: Vec<SequenceDescription>,
+    /// Hex data used as is instead of an RCP message.
+    #[serde(default)]
+    pub data: Option<String>,
+    #[serde(default)]
+    pub pcap: PcapDescription,
+}
+
+impl CraftDescription {
+    /// Reads a YAML or JSON description.
+    pub fn from_file(path: &Path) -> Result<Self, CraftError> {
+        let text = std::fs::read_to_string(path).map_err(CraftError::Io)?;
+
+        serde_yaml::from_str(&text).map_err(CraftError::Yaml)
+    }
+
+    fn data(&self, default_rcp: RcpMessageType) -> Result<Vec<u8>, CraftError> {
+        if let Some(hex) = &self.data {
+            return parse_hex(hex).ok_or_else(|| invalid("data", "invalid hex string"));
+        }
+
+        let message_type = match &self.rcp {
+            Some(name) => (1..=3)
+                .map(RcpMessageType)
+                .find(|t| t.to_string() == *name)
+                .ok_or_else(|| invalid("rcp", "expected IRA, REX or NTF"))?,
+            None => default_rcp,
+        };
+        let sequences = self
+            .sequences
+            .iter()
+            .map(|s| {
+                let operation = parse_operation(&s.operation)?;
+                let tlvs = encode_tlvs(&dictionary().tlv, "Sequence", &s.tlvs)?;
+                Ok(Sequence::new(s.sequence_number, operation, tlvs))
+            })
+            .collect::<Result<Vec<_>, CraftError>>()?;
+        let rcp = RcpMessage {
+            message_type,
+            sequences,
+        };
+
+        Ok(rcp.encode()?)
+    }
+
+    pub fn to_message(&self) -> Result<GcpMessage, CraftError> {
+        let transaction_id = self.transaction_id;
+        let eds = || -> Result<Eds, CraftError> {
+            Ok(Eds {
+                transaction_id,
+                mode: self.mode,
+                port: self.port,
+                channel: self.channel,
+                vendor_id: self.vendor_id,
+                vendor_index: self.vendor_index,
+                data: self.data(RcpMessageType::REX)?,
+            })
+        };
+        let edr = || -> Result<Edr, CraftError> {
+            Ok(Edr {
+                transaction_id,
+                mode: self.mode,
+                port: self.port,
+                channel: self.channel,
+                address: self.address,
+                data: match &self.data {
+                    Some(hex) => parse_hex(hex).ok_or_else(|| invalid("data", "invalid hex"))?,
+                    None => Vec::new(),
+                },
+            })
+        };
+        let mwr = Mwr {
+            transaction_id,
+            mode: self.mode,
+            port: self.port,
+            channel: self.channel,
+            address: self.address,
+            and_mask: self.and_mask,
+            or_mask: self.or_mask,
+        };
+        let return_code = self.return_code;
+
+        let id = self
+            .message
+            .parse()
+            .map_err(|_| CraftError::UnknownMessage(self.message.clone()))?;
+        let message = match id {
+            MessageId::Req_Notify => GcpMessage::NotifyRequest {
+                transaction_id,
+                mode: self.mode,
+                status: self.status,
+                event_code: self.event_code,
+                event_data: self.data(RcpMessageType::NTF)?,
+            },
+            MessageId::Rsp_Notify => GcpMessage::NotifyResponse {
+                transaction_id,
+                mode: self.mode,
+                event_code: self.event_code,
+            },
+            MessageId::Rsp_Notify_Error => GcpMessage::NotifyError {
+                transaction_id,
+                return_code,
+            },
+            MessageId::Req_DM => GcpMessage::DmRequest {
+                transaction_id,
+                mode: self.mode,
+                port: self.port,
+                channel: self.channel,
+                command: self.command,
+            },
+            MessageId::Rsp_DM => GcpMessage::DmResponse {
+                transaction_id,
+                mode: self.mode,
+                return_code,
+            },
+            MessageId::Rsp_DM_Error => GcpMessage::DmError {
+                transaction_id,
+                return_code,
+            },
+            MessageId::Req_EDS => GcpMessage::EdsRequest(eds()?),
+            MessageId::Rsp_EDS => GcpMessage::EdsResponse(eds()?),
+            MessageId::Rsp_EDS_Error => GcpMessage::EdsError {
+                transaction_id,
+                return_code,
+            },
+            MessageId::Req_EDR => GcpMessage::EdrRequest(edr()?),
+            MessageId::Rsp_EDR => GcpMessage::EdrResponse(edr()?),
+            MessageId::Rsp_EDR_Error => GcpMessage::EdrError {
+                transaction_id,
+                return_code,
+            },
+            MessageId::Req_MWR => GcpMessage::MwrRequest(mwr),
+            MessageId::Rsp_MWR => GcpMessage::MwrResponse(mwr),
+            MessageId::Rsp_MWR_Error => GcpMessage::MwrError {
+                transaction_id,
+                return_code,
+            },
+            _ => return Err(CraftError::UnknownMessage(self.message.clone())),
+        };
+
+        Ok(message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CraftFormat {
+    Hex,
+    Raw,
+    Pcap,
+}
+
+/// Encodes the described message as hex text, raw GCP bytes or a single frame capture.
+pub fn craft(desc: &CraftDescription, format: CraftFormat) -> Result<Vec<u8>, CraftError> {
+    let bytes = desc.to_message()?.encode(desc.unit_id)?;
+
+    let out = match format {
+        CraftFormat::Hex => {
+            let mut hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+            hex.push('\n');
+            hex.into_bytes()
+        }
+        CraftFormat::Raw => bytes,
+        CraftFormat::Pcap => {
+            let p = &desc.pcap;
+            let mut flow = TcpFlow::new(
+                SocketAddr::new(p.src, p.src_port),
+                SocketAddr::new(p.dst, p.dst_port),
+            );
+            CaptureBuilder::new()
+                .send(&mut flow, Direction::FromClient, &bytes)
+                .build()
+        }
+    };
+
+    Ok(out)
+}
+
+fn parse_operation(name: &str) -> Result<Operation, CraftError> {
+    (1..=8)
+        .map(Operation)
+        .find(|o| o.to_string() == name)
+        .ok_or_else(|| invalid("operation", &format!("unknown operation '{name}'")))
+}
+
+/// Hex string, spaces and ':' between the bytes are allowed.
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace() && *c != b':')
+        .collect();
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+
+    digits
+        .chunks(2)
+        .map(|c| u8::from_str_radix(std::str::from_utf8(c).ok()?, 16).ok())
+        .collect()
+}
+
+/// Encodes the TLVs listed in `value` against `defs`. TLVs are named as in the dictionary,
+/// or given by type number. A `{ hex: "..." }` value is encoded as is.
+fn encode_tlvs(defs: &[TlvDef], parent: &str, value: &Value) -> Result<Vec<TlvNode>, CraftError> {
+    let mut tlvs = Vec::new();
+
+    match value {
+        Value::Null => (),
+        Value::Mapping(map) => {
+            for (k, v) in map.iter() {
+                tlvs.push(encode_entry(defs, parent, k, v)?);
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items.iter() {
+                tlvs.extend(encode_tlvs(defs, parent, item)?);
+            }
+        }
+        _ => return Err(invalid(parent, "expected a mapping or a list of TLVs")),
+    }
+
+    Ok(tlvs)
+}
+
+fn raw_hex(value: &Value) -> Option<&str> {
+    match value {
+        Value::Mapping(map) if map.len() == 1 => map.get(&Value::from("hex"))?.as_str(),
+        _ => None,
+    }
+}
+
+fn encode_entry(
+    defs: &[TlvDef],
+    parent: &str,
+    key: &Value,
+    value: &Value,
+) -> Result<TlvNode, CraftError> {
+    // JSON keys are always strings, so "200" is a type number too.
+    let number = match key {
+        Value::String(name) => name.parse::<u64>().ok(),
+        _ => key.as_u64(),
+    };
+    let def = match (key, number) {
+        (_, Some(n)) => defs.iter().find(|d| u64::from(d.typ) == n),
+        (Value::String(name), None) => defs.iter().find(|d| d.name == *name),
+        _ => None,
+    };
+    let typ = match (def, number) {
+        (Some(def), _) => def.typ,
+        (None, Some(n)) => u8::try_from(n).map_err(|_| invalid(parent, "type out of range"))?,
+        (None, None) => {
+            let name = key.as_str().unwrap_or_default().to_string();
+            return Err(CraftError::UnknownTlv(parent.to_string(), name));
+        }
+    };
+
+    if let Some(hex) = raw_hex(value) {
+        let bytes = parse_hex(hex).ok_or_else(|| invalid(parent, "invalid hex string"))?;
+        return Ok(TlvNode::bytes(typ, &bytes));
+    }
+
+    match def {
+        Some(def) => encode_value(def, value),
+        // TLVs missing from the dictionary take sub-TLVs by type, or hex.
+        None => match value {
+            Value::String(hex) => parse_hex(hex)
+                .map(|b| TlvNode::bytes(typ, &b))
+                .ok_or_else(|| invalid(parent, "invalid hex string")),
+            _ => Ok(TlvNode::complex(typ, encode_tlvs(&[], parent, value)?)),
+        },
+    }
+}
+
+fn number<T: TryFrom<i64>>(def: &TlvDef, value: &Value) -> Result<T, CraftError> {
+    value
+        .as_i64()
+        .and_then(|n| T::try_from(n).ok())
+        .ok_or_else(|| invalid(&def.name, "expected a number in range"))
+}
+
+fn encode_value(def: &TlvDef, value: &Value) -> Result<TlvNode, CraftError> {
+    let typ = def.typ;
+    let text = || {
+        value
+            .as_str()
+            .ok_or_else(|| invalid(&def.name, "expected a string"))
+    };
+
+    let tlv = match def.data_type {
+        DataType::UnsignedByte => TlvNode::u8(typ, number(def, value)?),
+        DataType::UnsignedShort => TlvNode::u16(typ, number(def, value)?),
+        DataType::Short => TlvNode::bytes(typ, &number::<i16>(def, value)?.to_be_bytes()),
+        DataType::UnsignedInt => TlvNode::u32(typ, number(def, value)?),
+        DataType::UnsignedLong => {
+            let n = value
+                .as_u64()
+                .ok_or_else(|| invalid(&def.name, "expected a number"))?;
+            TlvNode::u64(typ, n)
+        }
+        DataType::Bool => {
+            let b = value
+                .as_bool()
+                .ok_or_else(|| invalid(&def.name, "expected true or false"))?;
+            TlvNode::bool(typ, b)
+        }
+        DataType::IpAddress => {
+            let ip: IpAddr = text()?
+                .parse()
+                .map_err(|_| invalid(&def.name, "invalid IP address"))?;
+            TlvNode::ip(typ, ip)
+        }
+        DataType::MacAddress => {
+            let mac = MacAddress::parse_str(text()?)
+                .map_err(|_| invalid(&def.name, "invalid MAC address"))?;
+            TlvNode::bytes(typ, mac.as_bytes())
+        }
+        DataType::HexBinary => {
+            let bytes = parse_hex(text()?).ok_or_else(|| invalid(&def.name, "invalid hex"))?;
+            TlvNode::bytes(typ, &bytes)
+        }
+        DataType::String => TlvNode::string(typ, text()?),
+        DataType::DateAndTime => {
+            let date: DateAndTime = text()?
+                .parse()
+                .map_err(|e: DateAndTimeError| invalid(&def.name, &e.to_string()))?;
+            TlvNode::bytes(typ, &date.to_bytes())
+        }
+        DataType::Enum => {
+            let n = match value.as_str() {
+                Some(name) => enum_value(def, name)?,
+                None => number(def, value)?,
+            };
+            sized(def, n)?
+        }
+        DataType::Bits => encode_bits(def, value)?,
+        DataType::Complex => TlvNode::complex(typ, encode_tlvs(&def.sub, &def.name, value)?),
+    };
+
+    Ok(tlv)
+}
+
+fn enum_value(def: &TlvDef, name: &str) -> Result<u32, CraftError> {
+    def.values
+        .iter()
+        .find(|(_, v)| match v {
+            EnumValue::Name(n) => n == name,
+            EnumValue::Versioned { name: n, .. } => n.as_deref() == Some(name),
+        })
+        .and_then(|(k, _)| k.parse().ok())
+        .ok_or_else(|| invalid(&def.name, &format!("unknown value '{name}'")))
+}
+
+/// Bits TLVs take a number or the list of the flags set.
+fn encode_bits(def: &TlvDef, value: &Value) -> Result<TlvNode, CraftError> {
+    let bits: u32 = match value {
+        Value::Sequence(names) => names.iter().try_fold(0, |bits, n| {
+            let flag = def
+                .flags
+                .iter()
+                .find(|f| Some(f.name.as_str()) == n.as_str())
+                .ok_or_else(|| invalid(&def.name, &format!("unknown flag {n:?}")))?;
+            Ok::<u32, CraftError>(bits | (1 << flag.bit))
+        })?,
+        _ => number(def, value)?,
+    };
+
+    sized(def, bits)
+}
+
+/// Encodes an Enum or Bits value at the size of its definition.
+fn sized(def: &TlvDef, n: u32) -> Result<TlvNode, CraftError> {
+    let size = def.size.unwrap_or(4);
+    if size < 4 && n >> (size * 8) != 0 {
+        return Err(invalid(
+            &def.name,
+            &format!("{n} does not fit in {size} byte(s)"),
+        ));
+    }
+
+    Ok(TlvNode::bytes(def.typ, &n.to_be_bytes()[4 - size..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Definition of a sub-TLV of the built-in dictionary, by type path.
+    fn def(path: &[u8]) -> &'static TlvDef {
+        let mut def = dictionary().find(path[0]).unwrap();
+        for typ in &path[1..] {
+            def = def.sub.iter().find(|d| d.typ == *typ).unwrap();
+        }
+        def
+    }
+
+    #[test]
+    fn enums_use_the_size_of_the_definition() {
+        let pw_subtype = def(&[58, 3, 5]);
+        let tlv = encode_value(pw_subtype, &Value::from(1)).unwrap();
+        assert_eq!(tlv, TlvNode::bytes(5, &[0, 1]));
+
+        let operation = def(&[11]);
+        let tlv = encode_value(operation, &Value::from("Write")).unwrap();
+        assert_eq!(tlv, TlvNode::bytes(11, &[2]));
+        assert!(encode_value(operation, &Value::from(256)).is_err());
+    }
+
+    #[test]
+    fn bits_use_the_size_of_the_definition() {
+        let core_function = def(&[60, 10]);
+        let flags = Value::Sequence(vec![Value::from("Principal"), Value::from("DOCSIS")]);
+        let tlv = encode_value(core_function, &flags).unwrap();
+        assert_eq!(tlv, TlvNode::bytes(10, &[0, 3]));
+        assert!(encode_value(core_function, &Value::from(0x1_0000)).is_err());
+    }
+
+    #[test]
+    fn messages_are_named_like_on_the_command_line() {
+        let message = |name: &str| {
+            let desc: CraftDescription =
+                serde_yaml::from_str(&format!("message: {name}\nreturn_code: 2")).unwrap();
+            desc.to_message()
+        };
+        let error = GcpMessage::DmError {
+            transaction_id: 0,
+            return_code: 2,
+        };
+        assert_eq!(message("DmError").unwrap(), error);
+        assert_eq!(message("dmerror").unwrap(), error);
+        assert_eq!(message("133").unwrap(), error);
+        assert!(matches!(message("DmReply"), Err(CraftError::UnknownMessage(m)) if m == "DmReply"));
+        assert!(matches!(message("1"), Err(CraftError::UnknownMessage(_))));
+
+        let bytes = message("DmError").unwrap().encode(0).unwrap();
+        assert_eq!(bytes.len(), bytes.capacity());
+    }
+}
@@ -0,0 +1,371 @@
+use crate::encoders::tlv_encoder::{to_len, EncodeError};
+use crate::parsers::gcp_parser::{GcpHeader, MessageId};
+
+/// Protocol Identifier of the GCP TCP header.
+pub const GCP_PROTOCOL_ID: u16 = 1;
+
+/// Length of the GCP TCP header, up to and including the Message Length.
+pub const GCP_HEADER_LEN: usize = 10;
+
+impl GcpHeader {
+    /// Header of a GCP message whose body, starting with the Transaction ID, is `body_len` bytes.
+    pub fn for_message(
+        transaction_identifier: u16,
+        unit_identifier: u8,
+        message_id: MessageId,
+        body_len: usize,
+    ) -> Result<Self, EncodeError> {
+        Ok(GcpHeader {
+            transaction_identifier,
+            protocol_identifier: GCP_PROTOCOL_ID,
+            // Unit ID, Message ID and Message Length are covered by the length too.
+            length: to_len(body_len + 4)?,
+            unit_identifier,
+            message_id,
+            message_length: to_len(body_len)?,
+        })
+    }
+
+    pub fn encode_into(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.transaction_identifier.to_be_bytes());
+        out.extend_from_slice(&self.protocol_identifier.to_be_bytes());
+        out.extend_from_slice(&self.length.to_be_bytes());
+        out.push(self.unit_identifier);
+        out.push(self.message_id.0);
+        out.extend_from_slice(&self.message_length.to_be_bytes());
+    }
+}
+
+/// Fields shared by the EDS request and response. `data` is usually an encoded RCP message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Eds {
+    pub transaction_id: u16,
+    pub mode: u8,
+    pub port: u16,
+    pub channel: u16,
+    pub vendor_id: u32,
+    pub vendor_index: u8,
+    pub data: Vec<u8>,
+}
+
+/// Fields shared by the EDR request and response. The data length is computed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edr {
+    pub transaction_id: u16,
+    pub mode: u8,
+    pub port: u16,
+    pub channel: u16,
+    pub address: u32,
+    pub data: Vec<u8>,
+}
+
+/// Fields shared by the MWR request and response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mwr {
+    pub transaction_id: u16,
+    pub mode: u8,
+    pub port: u16,
+    pub channel: u16,
+    pub address: u32,
+    pub and_mask: u16,
+    pub or_mask: u16,
+}
+
+/// GCP message bodies, one per [`MessageId`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GcpMessage {
+    NotifyRequest {
+        transaction_id: u16,
+        mode: u8,
+        status: u8,
+        event_code: u32,
+        event_data: Vec<u8>,
+    },
+    NotifyResponse {
+        transaction_id: u16,
+        mode: u8,
+        event_code: u32,
+    },
+    NotifyError {
+        transaction_id: u16,
+        return_code: u8,
+    },
+    DmRequest {
+        transaction_id: u16,
+        mode: u8,
+        port: u16,
+        channel: u16,
+        command: u8,
+    },
+    DmResponse {
+        transaction_id: u16,
+        mode: u8,
+        return_code: u8,
+    },
+    DmError {
+        transaction_id: u16,
+        return_code: u8,
+    },
+    EdsRequest(Eds),
+    EdsResponse(Eds),
+    EdsError {
+        transaction_id: u16,
+        return_code: u8,
+    },
+    EdrRequest(Edr),
+    EdrResponse(Edr),
+    EdrError {
+        transaction_id: u16,
+        return_code: u8,
+    },
+    MwrRequest(Mwr),
+    MwrResponse(Mwr),
+    MwrError {
+        transaction_id: u16,
+        return_code: u8,
+    },
+}
+
+impl GcpMessage {
+    pub fn message_id(&self) -> MessageId {
+        match self {
+            GcpMessage::NotifyRequest { .. } => MessageId::Req_Notify,
+            GcpMessage::NotifyResponse { .. } => MessageId::Rsp_Notify,
+            GcpMessage::NotifyError { .. } => MessageId::Rsp_Notify_Error,
+            GcpMessage::DmRequest { .. } => MessageId::Req_DM,
+            GcpMessage::DmResponse { .. } => MessageId::Rsp_DM,
+            GcpMessage::DmError { .. } => MessageId::Rsp_DM_Error,
+            GcpMessage::EdsRequest(_) => MessageId::Req_EDS,
+            GcpMessage::EdsResponse(_) => MessageId::Rsp_EDS,
+            GcpMessage::EdsError { .. } => MessageId::Rsp_EDS_Error,
+            GcpMessage::EdrRequest(_) => MessageId::Req_EDR,
+            GcpMessage::EdrResponse(_) => MessageId::Rsp_EDR,
+            GcpMessage::EdrError { .. } => MessageId::Rsp_EDR_Error,
+            GcpMessage::MwrRequest(_) => MessageId::Req_MWR,
+            GcpMessage::MwrResponse(_) => MessageId::Rsp_MWR,
+            GcpMessage::MwrError { .. } => MessageId::Rsp_MWR_Error,
+        }
+    }
+
+    pub fn transaction_id(&self) -> u16 {
+        match self {
+            GcpMessage::NotifyRequest { transaction_id, .. }
+            | GcpMessage::NotifyResponse { transaction_id, .. }
+            | GcpMessage::NotifyError { transaction_id, .. }
+            | GcpMessage::DmRequest { transaction_id, .. }
+            | GcpMessage::DmResponse { transaction_id, .. }
+            | GcpMessage::DmError { transaction_id, .. }
+            | GcpMessage::EdsError { transaction_id, .. }
+            | GcpMessage::EdrError { transaction_id, .. }
+            | GcpMessage::MwrError { transaction_id, .. } => *transaction_id,
+            GcpMessage::EdsRequest(eds) | GcpMessage::EdsResponse(eds) => eds.transaction_id,
+            GcpMessage::EdrRequest(edr) | GcpMessage::EdrResponse(edr) => edr.transaction_id,
+            GcpMessage::MwrRequest(mwr) | GcpMessage::MwrResponse(mwr) => mwr.transaction_id,
+        }
+    }
+
+    /// Message body, from the Transaction ID on.
+    pub fn encode_body(&self) -> Result<Vec<u8>, EncodeError> {
+        let mut out = self.transaction_id().to_be_bytes().to_vec();
+
+        match self {
+            GcpMessage::NotifyRequest {
+                mode,
+                status,
+                event_code,
+                event_data,
+                ..
+            } => {
+                out.push(*mode);
+                out.push(*status);
+                out.extend_from_slice(&event_code.to_be_bytes());
+                out.extend_from_slice(event_data);
+            }
+            GcpMessage::NotifyResponse {
+                mode, event_code, ..
+            } => {
+                out.push(*mode);
+                out.extend_from_slice(&event_code.to_be_bytes());
+            }
+            GcpMessage::DmRequest {
+                mode,
+                port,
+                channel,
+                command,
+                ..
+            } => {
+                out.push(*mode);
+                out.extend_from_slice(&port.to_be_bytes());
+                out.extend_from_slice(&channel.to_be_bytes());
+                out.push(*command);
+            }
+            GcpMessage::DmResponse {
+                mode, return_code, ..
+            } => {
+                out.push(*mode);
+                out.push(*return_code);
+            }
+            GcpMessage::NotifyError { return_code, .. }
+            | GcpMessage::DmError { return_code, .. }
+            | GcpMessage::EdsError { return_code, .. }
+            | GcpMessage::EdrError { return_code, .. }
+            | GcpMessage::MwrError { return_code, .. } => out.push(*return_code),
+            GcpMessage::EdsRequest(eds) | GcpMessage::EdsResponse(eds) => {
+                out.push(eds.mode);
+                out.extend_from_slice(&eds.port.to_be_bytes());
+                out.extend_from_slice(&eds.channel.to_be_bytes());
+                out.extend_from_slice(&eds.vendor_id.to_be_bytes());
+                out.push(eds.vendor_index);
+                out.extend_from_slice(&eds.data);
+            }
+            GcpMessage::EdrRequest(edr) | GcpMessage::EdrResponse(edr) => {
+                out.push(edr.mode);
+                out.extend_from_slice(&edr.port.to_be_bytes());
+                out.extend_from_slice(&edr.channel.to_be_bytes());
+                out.extend_from_slice(&edr.address.to_be_bytes());
+                out.extend_from_slice(&to_len(edr.data.len())?.to_be_bytes());
+                out.extend_from_slice(&edr.data);
+            }
+            GcpMessage::MwrRequest(mwr) | GcpMessage::MwrResponse(mwr) => {
+                out.push(mwr.mode);
+                out.extend_from_slice(&mwr.port.to_be_bytes());
+                out.extend_from_slice(&mwr.channel.to_be_bytes());
+                out.extend_from_slice(&mwr.address.to_be_bytes());
+                out.extend_from_slice(&mwr.and_mask.to_be_bytes());
+                out.extend_from_slice(&mwr.or_mask.to_be_bytes());
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Header and body, ready to be sent on the GCP TCP connection.
+    /// The header Transaction Identifier is the one of the message.
+    pub fn encode(&self, unit_identifier: u8) -> Result<Vec<u8>, EncodeError> {
+        let body = self.encode_body()?;
+        let header = GcpHeader::for_message(
+            self.transaction_id(),
+            unit_identifier,
+            self.message_id(),
+            body.len(),
+        )?;
+
+        let mut out = Vec::with_capacity(GCP_HEADER_LEN + body.len());
+        header.encode_into(&mut out);
+        out.extend_from_slice(&body);
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::gcp_parser::{header, rcp_offset, split_messages};
+
+    /// Encodes `message` and checks the decoded header, returning the body.
+    fn round_trip(message: &GcpMessage) -> Vec<u8> {
+        let bytes = message.encode(5).unwrap();
+        let (body, hdr) = header(&bytes).unwrap();
+
+        assert_eq!(hdr.transaction_identifier, message.transaction_id());
+        assert_eq!(hdr.protocol_identifier, GCP_PROTOCOL_ID);
+        assert_eq!(usize::from(hdr.length), bytes.len() - 6);
+        assert_eq!(hdr.unit_identifier, 5);
+        assert_eq!(hdr.message_id, message.message_id());
+        assert_eq!(usize::from(hdr.message_length), body.len());
+        assert_eq!(body, message.encode_body().unwrap());
+
+        body.to_vec()
+    }
+
+    #[test]
+    fn header_round_trip() {
+        let header = GcpHeader::for_message(0x1234, 1, MessageId::Req_DM, 6).unwrap();
+        let mut bytes = Vec::new();
+        header.encode_into(&mut bytes);
+        bytes.extend_from_slice(&[0; 6]);
+
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(self::header(&bytes).unwrap(), (&[0u8; 6][..], header));
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        let messages = [
+            GcpMessage::NotifyRequest {
+                transaction_id: 1,
+                mode: 0,
+                status: 2,
+                event_code: 3,
+                event_data: vec![3, 0, 0],
+            },
+            GcpMessage::DmRequest {
+                transaction_id: 2,
+                mode: 0,
+                port: 1,
+                channel: 2,
+                command: 3,
+            },
+            GcpMessage::EdrResponse(Edr {
+                transaction_id: 3,
+                mode: 0,
+                port: 0,
+                channel: 0,
+                address: 0x1000,
+                data: vec![1, 2, 3],
+            }),
+            GcpMessage::MwrError {
+                transaction_id: 4,
+                return_code: 7,
+            },
+        ];
+
+        for message in messages.iter() {
+            round_trip(message);
+        }
+        let body = round_trip(&messages[2]);
+        assert_eq!(&body[11..], &[0, 3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn eds_rcp_data_round_trip() {
+        let rcp = vec![2, 0, 0];
+        let message = GcpMessage::EdsRequest(Eds {
+            transaction_id: 9,
+            mode: 0,
+            port: 0,
+            channel: 0,
+            vendor_id: 4491,
+            vendor_index: 0,
+            data: rcp.clone(),
+        });
+        let body = round_trip(&message);
+        let offset = rcp_offset(message.message_id(), &body).unwrap();
+
+        assert_eq!(&body[offset..], rcp);
+    }
+
+    #[test]
+    fn packed_messages_split_back() {
+        let first = GcpMessage::NotifyError {
+            transaction_id: 1,
+            return_code: 2,
+        };
+        let second = GcpMessage::DmResponse {
+            transaction_id: 2,
+            mode: 0,
+            return_code: 0,
+        };
+        let mut payload = first.encode(0).unwrap();
+        payload.extend(second.encode(0).unwrap());
+        payload.extend_from_slice(&[0, 3]);
+
+        let (messages, rest) = split_messages(&payload);
+        assert_eq!(
+            messages,
+            [first.encode(0).unwrap(), second.encode(0).unwrap()]
+        );
+        assert_eq!(rest, [0, 3]);
+    }
+}
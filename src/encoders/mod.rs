@@ -0,0 +1,5 @@
+pub mod craft;
+pub mod gcp_encoder;
+pub mod pcap_builder;
+pub mod rcp_encoder;
+pub mod tlv_encoder;
@@ -0,0 +1,309 @@
+use std::net::{IpAddr, SocketAddr};
+
+use pnet::packet::ethernet::{EtherTypes, MutableEthernetPacket};
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::ipv4::{self, MutableIpv4Packet};
+use pnet::packet::ipv6::MutableIpv6Packet;
+use pnet::packet::tcp::{self, MutableTcpPacket, TcpFlags};
+use pnet::util::MacAddr;
+
+/// TCP port GCP listens on.
+pub const GCP_PORT: u16 = 8190;
+
+const ETHERNET_LEN: usize = 14;
+const IPV4_LEN: usize = 20;
+const IPV6_LEN: usize = 40;
+const TCP_LEN: usize = 20;
+
+/// One TCP segment, with the addresses of its sender and receiver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcpSegment<'a> {
+    pub src: SocketAddr,
+    pub dst: SocketAddr,
+    pub seq: u32,
+    pub ack: u32,
+    pub flags: u16,
+    pub payload: &'a [u8],
+}
+
+/// Ethernet/IPv4 or Ethernet/IPv6 frame carrying the segment, with valid checksums.
+///
+/// Panics if source and destination are not of the same address family.
+pub fn tcp_frame(segment: &TcpSegment) -> Vec<u8> {
+    let ip_len = match segment.src.ip() {
+        IpAddr::V4(_) => IPV4_LEN,
+        IpAddr::V6(_) => IPV6_LEN,
+    };
+    let tcp_len = TCP_LEN + segment.payload.len();
+    let mut frame = vec![0u8; ETHERNET_LEN + ip_len + tcp_len];
+
+    {
+        let mut tcp = MutableTcpPacket::new(&mut frame[ETHERNET_LEN + ip_len..]).unwrap();
+        tcp.set_source(segment.src.port());
+        tcp.set_destination(segment.dst.port());
+        tcp.set_sequence(segment.seq);
+        tcp.set_acknowledgement(segment.ack);
+        tcp.set_data_offset((TCP_LEN / 4) as u8);
+        tcp.set_flags(segment.flags);
+        tcp.set_window(65535);
+        tcp.set_payload(segment.payload);
+        let checksum = match (segment.src.ip(), segment.dst.ip()) {
+            (IpAddr::V4(s), IpAddr::V4(d)) => tcp::ipv4_checksum(&tcp.to_immutable(), &s, &d),
+            (IpAddr::V6(s), IpAddr::V6(d)) => tcp::ipv6_checksum(&tcp.to_immutable(), &s, &d),
+            _ => panic!("TCP segment between IPv4 and IPv6 addresses"),
+        };
+        tcp.set_checksum(checksum);
+    }
+
+    let ethertype = match (segment.src.ip(), segment.dst.ip()) {
+        (IpAddr::V4(s), IpAddr::V4(d)) => {
+            let mut ip = MutableIpv4Packet::new(&mut frame[ETHERNET_LEN..]).unwrap();
+            ip.set_version(4);
+            ip.set_header_length((IPV4_LEN / 4) as u8);
+            ip.set_total_length((IPV4_LEN + tcp_len) as u16);
+            ip.set_ttl(64);
+            ip.set_next_level_protocol(IpNextHeaderProtocols::Tcp);
+            ip.set_source(s);
+            ip.set_destination(d);
+            let checksum = ipv4::checksum(&ip.to_immutable());
+            ip.set_checksum(checksum);
+            EtherTypes::Ipv4
+        }
+        (IpAddr::V6(s), IpAddr::V6(d)) => {
+            let mut ip = MutableIpv6Packet::new(&mut frame[ETHERNET_LEN..]).unwrap();
+            ip.set_version(6);
+            ip.set_payload_length(tcp_len as u16);
+            ip.set_next_header(IpNextHeaderProtocols::Tcp);
+            ip.set_hop_limit(64);
+            ip.set_source(s);
+            ip.set_destination(d);
+            EtherTypes::Ipv6
+        }
+        _ => unreachable!(),
+    };
+
+    let mut eth = MutableEthernetPacket::new(&mut frame).unwrap();
+    eth.set_destination(mac_for(segment.dst.ip()));
+    eth.set_source(mac_for(segment.src.ip()));
+    eth.set_ethertype(ethertype);
+
+    frame
+}
+
+/// Locally administered MAC derived from the last bytes of the IP address,
+/// so every host of a capture keeps the same MAC.
+fn mac_for(ip: IpAddr) -> MacAddr {
+    let b = match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    };
+    let n = b.len();
+
+    MacAddr::new(0x02, 0, b[n - 4], b[n - 3], b[n - 2], b[n - 1])
+}
+
+/// A frame with its capture time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcapRecord {
+    pub ts_sec: u32,
+    pub ts_usec: u32,
+    pub data: Vec<u8>,
+}
+
+/// Legacy (libpcap) capture file with an Ethernet link type.
+pub fn pcap_file(records: &[PcapRecord]) -> Vec<u8> {
+    // Magic number, version 2.4, time zone, accuracy, snap length, LINKTYPE_ETHERNET
+    let mut out = Vec::new();
+    out.extend_from_slice(&0xa1b2_c3d4u32.to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&4u16.to_le_bytes());
+    out.extend_from_slice(&0i32.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&65535u32.to_le_bytes());
+    out.extend_from_slice(&1u32.to_le_bytes());
+
+    for r in records {
+        let len = r.data.len() as u32;
+        out.extend_from_slice(&r.ts_sec.to_le_bytes());
+        out.extend_from_slice(&r.ts_usec.to_le_bytes());
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&r.data);
+    }
+
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the side that opened the connection, the RPD for GCP.
+    FromClient,
+    /// From the listening side, the Core on port 8190 for GCP.
+    FromServer,
+}
+
+/// A TCP connection keeping the sequence numbers of both sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcpFlow {
+    pub client: SocketAddr,
+    pub server: SocketAddr,
+    pub client_seq: u32,
+    pub server_seq: u32,
+}
+
+impl TcpFlow {
+    pub fn new(client: SocketAddr, server: SocketAddr) -> Self {
+        TcpFlow {
+            client,
+            server,
+            client_seq: 1000,
+            server_seq: 5000,
+        }
+    }
+
+    /// Next segment in `direction`, advancing the sender sequence number by the payload size.
+    pub fn segment<'a>(
+        &mut self,
+        direction: Direction,
+        flags: u16,
+        payload: &'a [u8],
+    ) -> TcpSegment<'a> {
+        // SYN and FIN take one sequence number.
+        let len = payload.len() as u32 + u32::from(flags & (TcpFlags::SYN | TcpFlags::FIN) != 0);
+        let (src, dst, seq, ack) = match direction {
+            Direction::FromClient => {
+                let seq = self.client_seq;
+                self.client_seq = seq.wrapping_add(len);
+                (self.client, self.server, seq, self.server_seq)
+            }
+            Direction::FromServer => {
+                let seq = self.server_seq;
+                self.server_seq = seq.wrapping_add(len);
+                (self.server, self.client, seq, self.client_seq)
+            }
+        };
+
+        TcpSegment {
+            src,
+            dst,
+            seq,
+            ack,
+            flags,
+            payload,
+        }
+    }
+}
+
+/// Builds a capture file in memory, frame by frame. Every frame is `step_usec` after the previous.
+///
+/// ```
+/// use pcap_rparser::encoders::gcp_encoder::GcpMessage;
+/// use pcap_rparser::encoders::pcap_builder::{CaptureBuilder, Direction, TcpFlow};
+///
+/// let rpd = "10.0.0.2:40000".parse().unwrap();
+/// let core = "10.0.0.1:8190".parse().unwrap();
+/// let notify = GcpMessage::NotifyError { transaction_id: 1, return_code: 0 }.encode(0).unwrap();
+/// let response = GcpMessage::DmResponse { transaction_id: 2, mode: 0, return_code: 0 };
+/// let response = response.encode(0).unwrap();
+///
+/// let mut flow = TcpFlow::new(rpd, core);
+/// let builder = CaptureBuilder::new()
+///     .handshake(&mut flow)
+///     .send(&mut flow, Direction::FromClient, &notify)
+///     .send_packed(&mut flow, Direction::FromClient, &[&notify, &response])
+///     .send_segmented(&mut flow, Direction::FromClient, &response, 8);
+/// assert_eq!(builder.records().len(), 7);
+///
+/// let pcap = builder.build();
+/// assert_eq!(&pcap[..4], &[0xd4, 0xc3, 0xb2, 0xa1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CaptureBuilder {
+    records: Vec<PcapRecord>,
+    time_usec: u64,
+    step_usec: u64,
+}
+
+impl Default for CaptureBuilder {
+    fn default() -> Self {
+        CaptureBuilder::new()
+    }
+}
+
+impl CaptureBuilder {
+    pub fn new() -> Self {
+        CaptureBuilder {
+            records: Vec::new(),
+            time_usec: 1_000_000,
+            step_usec: 1000,
+        }
+    }
+
+    /// Time of the next frame.
+    pub fn at(mut self, ts_sec: u32, ts_usec: u32) -> Self {
+        self.time_usec = u64::from(ts_sec) * 1_000_000 + u64::from(ts_usec);
+        self
+    }
+
+    pub fn step(mut self, step_usec: u64) -> Self {
+        self.step_usec = step_usec;
+        self
+    }
+
+    /// Adds a frame as is, e.g. a truncated or non TCP one.
+    pub fn frame(mut self, data: Vec<u8>) -> Self {
+        self.records.push(PcapRecord {
+            ts_sec: (self.time_usec / 1_000_000) as u32,
+            ts_usec: (self.time_usec % 1_000_000) as u32,
+            data,
+        });
+        self.time_usec += self.step_usec;
+        self
+    }
+
+    pub fn segment(self, segment: &TcpSegment) -> Self {
+        self.frame(tcp_frame(segment))
+    }
+
+    /// SYN, SYN-ACK and ACK opening the flow.
+    pub fn handshake(self, flow: &mut TcpFlow) -> Self {
+        let syn = flow.segment(Direction::FromClient, TcpFlags::SYN, &[]);
+        let syn_ack = flow.segment(Direction::FromServer, TcpFlags::SYN | TcpFlags::ACK, &[]);
+        let ack = flow.segment(Direction::FromClient, TcpFlags::ACK, &[]);
+
+        self.segment(&syn).segment(&syn_ack).segment(&ack)
+    }
+
+    /// One segment carrying `payload`, usually a whole GCP message.
+    pub fn send(self, flow: &mut TcpFlow, direction: Direction, payload: &[u8]) -> Self {
+        let segment = flow.segment(direction, TcpFlags::PSH | TcpFlags::ACK, payload);
+        self.segment(&segment)
+    }
+
+    /// Several GCP messages packed in a single segment.
+    pub fn send_packed(self, flow: &mut TcpFlow, direction: Direction, payloads: &[&[u8]]) -> Self {
+        self.send(flow, direction, &payloads.concat())
+    }
+
+    /// `payload` split over segments of at most `mss` bytes.
+    pub fn send_segmented(
+        mut self,
+        flow: &mut TcpFlow,
+        direction: Direction,
+        payload: &[u8],
+        mss: usize,
+    ) -> Self {
+        for chunk in payload.chunks(mss.max(1)) {
+            self = self.send(flow, direction, chunk);
+        }
+        self
+    }
+
+    pub fn records(&self) -> &[PcapRecord] {
+        &self.records
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        pcap_file(&self.records)
+    }
+}
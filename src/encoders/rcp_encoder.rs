@@ -0,0 +1,150 @@
+use crate::encoders::tlv_encoder::{encode_tlvs_into, to_len, EncodeError, TlvNode};
+use crate::parsers::rcp_parser::{Operation, RcpMessageType};
+
+/// TLV types framing an RCP sequence.
+const SEQUENCE: u8 = 9;
+const SEQUENCE_NUMBER: u8 = 10;
+const OPERATION: u8 = 11;
+
+/// An RCP sequence: its number, the operation and the TLVs it applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    pub sequence_number: u16,
+    pub operation: Operation,
+    pub tlvs: Vec<TlvNode>,
+}
+
+impl Sequence {
+    pub fn new(sequence_number: u16, operation: Operation, tlvs: Vec<TlvNode>) -> Self {
+        Sequence {
+            sequence_number,
+            operation,
+            tlvs,
+        }
+    }
+
+    /// The sequence as a TLV (9), with SequenceNumber (10) and Operation (11) first.
+    pub fn to_tlv(&self) -> TlvNode {
+        let mut sub = vec![
+            TlvNode::u16(SEQUENCE_NUMBER, self.sequence_number),
+            TlvNode::u8(OPERATION, self.operation.0),
+        ];
+        sub.extend(self.tlvs.iter().cloned());
+
+        TlvNode::complex(SEQUENCE, sub)
+    }
+}
+
+/// An RCP message (IRA, REX or NTF) carried as GCP EDS or Notify data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RcpMessage {
+    pub message_type: RcpMessageType,
+    pub sequences: Vec<Sequence>,
+}
+
+impl RcpMessage {
+    pub fn ira(sequences: Vec<Sequence>) -> Self {
+        RcpMessage {
+            message_type: RcpMessageType::IRA,
+            sequences,
+        }
+    }
+
+    pub fn rex(sequences: Vec<Sequence>) -> Self {
+        RcpMessage {
+            message_type: RcpMessageType::REX,
+            sequences,
+        }
+    }
+
+    pub fn ntf(sequences: Vec<Sequence>) -> Self {
+        RcpMessage {
+            message_type: RcpMessageType::NTF,
+            sequences,
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, EncodeError> {
+        let sequences: Vec<TlvNode> = self.sequences.iter().map(Sequence::to_tlv).collect();
+        let mut body = Vec::new();
+        encode_tlvs_into(&sequences, &mut body)?;
+
+        let mut out = Vec::with_capacity(3 + body.len());
+        out.push(self.message_type.0);
+        out.extend_from_slice(&to_len(body.len())?.to_be_bytes());
+        out.extend_from_slice(&body);
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoders::tlv_encoder::encode_tlvs;
+    use crate::parsers::tlv_parser::{parse_tlvs, to_u16};
+    use nom::multi::many0;
+    use nom::number::complete::be_u16;
+    use nom_derive::Parse;
+
+    /// Decodes an encoded RCP message back into its type and sequences.
+    fn decode(input: &[u8]) -> (RcpMessageType, Vec<(u16, Operation, Vec<u8>)>) {
+        let (i, message_type) = RcpMessageType::parse(input).unwrap();
+        let (i, len) = be_u16::<_, ()>(i).unwrap();
+        assert_eq!(usize::from(len), i.len());
+
+        let (rest, sequences) = many0(parse_tlvs)(i).unwrap();
+        assert!(rest.is_empty());
+        let sequences = sequences
+            .iter()
+            .map(|seq| {
+                assert_eq!(seq.typ, SEQUENCE);
+                let (rest, tlvs) = many0(parse_tlvs)(seq.val).unwrap();
+                assert!(rest.is_empty());
+                assert_eq!((tlvs[0].typ, tlvs[1].typ), (SEQUENCE_NUMBER, OPERATION));
+
+                let start: usize = tlvs[..2].iter().map(|t| 3 + t.val.len()).sum();
+                let body = seq.val[start..].to_vec();
+                (to_u16(tlvs[0].val), Operation(tlvs[1].val[0]), body)
+            })
+            .collect();
+
+        (message_type, sequences)
+    }
+
+    #[test]
+    fn sequences_round_trip() {
+        let message = RcpMessage::rex(vec![
+            Sequence::new(
+                1,
+                Operation::Read,
+                vec![TlvNode::complex(50, vec![TlvNode::bytes(1, &[])])],
+            ),
+            Sequence::new(
+                2,
+                Operation::Write,
+                vec![
+                    TlvNode::u8(15, 3),
+                    TlvNode::complex(61, vec![TlvNode::u16(1, 7)]),
+                ],
+            ),
+        ]);
+        let (message_type, sequences) = decode(&message.encode().unwrap());
+
+        assert_eq!(message_type, RcpMessageType::REX);
+        assert_eq!(sequences.len(), 2);
+        for (decoded, sequence) in sequences.iter().zip(message.sequences.iter()) {
+            assert_eq!(decoded.0, sequence.sequence_number);
+            assert_eq!(decoded.1, sequence.operation);
+            assert_eq!(decoded.2, encode_tlvs(&sequence.tlvs).unwrap());
+        }
+    }
+
+    #[test]
+    fn empty_message_round_trip() {
+        let bytes = RcpMessage::ntf(vec![]).encode().unwrap();
+
+        assert_eq!(bytes, [3, 0, 0]);
+        assert_eq!(decode(&bytes), (RcpMessageType::NTF, vec![]));
+    }
+}
@@ -0,0 +1,201 @@
+use std::fmt;
+use std::net::IpAddr;
+
+use crate::parsers::tlv_parser::RphyTlv;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeError {
+    /// Encoded value does not fit the 16 bit length field.
+    TooLong(usize),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::TooLong(n) => write!(f, "value too long for a 16 bit length: {n} bytes"),
+        }
+    }
+}
+
+/// Checks `len` fits a 16 bit length field.
+pub(crate) fn to_len(len: usize) -> Result<u16, EncodeError> {
+    u16::try_from(len).map_err(|_| EncodeError::TooLong(len))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlvValue {
+    Bytes(Vec<u8>),
+    /// Sub-TLVs of a Complex TLV.
+    Tlvs(Vec<TlvNode>),
+}
+
+/// A TLV to be encoded, the inverse of [`RphyTlv`]. Complex TLVs nest their sub-TLVs and
+/// the length fields are computed when encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlvNode {
+    pub typ: u8,
+    pub value: TlvValue,
+}
+
+impl TlvNode {
+    pub fn bytes(typ: u8, val: &[u8]) -> Self {
+        TlvNode {
+            typ,
+            value: TlvValue::Bytes(val.to_vec()),
+        }
+    }
+
+    pub fn u8(typ: u8, val: u8) -> Self {
+        TlvNode::bytes(typ, &[val])
+    }
+
+    pub fn u16(typ: u8, val: u16) -> Self {
+        TlvNode::bytes(typ, &val.to_be_bytes())
+    }
+
+    pub fn u32(typ: u8, val: u32) -> Self {
+        TlvNode::bytes(typ, &val.to_be_bytes())
+    }
+
+    pub fn u64(typ: u8, val: u64) -> Self {
+        TlvNode::bytes(typ, &val.to_be_bytes())
+    }
+
+    pub fn bool(typ: u8, val: bool) -> Self {
+        TlvNode::u8(typ, val.into())
+    }
+
+    pub fn string(typ: u8, val: &str) -> Self {
+        TlvNode::bytes(typ, val.as_bytes())
+    }
+
+    pub fn ip(typ: u8, val: IpAddr) -> Self {
+        match val {
+            IpAddr::V4(ip) => TlvNode::bytes(typ, &ip.octets()),
+            IpAddr::V6(ip) => TlvNode::bytes(typ, &ip.octets()),
+        }
+    }
+
+    pub fn complex(typ: u8, sub: Vec<TlvNode>) -> Self {
+        TlvNode {
+            typ,
+            value: TlvValue::Tlvs(sub),
+        }
+    }
+
+    /// Size of the value, without the type and length fields.
+    pub fn value_len(&self) -> usize {
+        match &self.value {
+            TlvValue::Bytes(b) => b.len(),
+            TlvValue::Tlvs(sub) => sub.iter().map(|t| 3 + t.value_len()).sum(),
+        }
+    }
+
+    pub fn encode_into(&self, out: &mut Vec<u8>) -> Result<(), EncodeError> {
+        out.push(self.typ);
+        out.extend_from_slice(&to_len(self.value_len())?.to_be_bytes());
+        match &self.value {
+            TlvValue::Bytes(b) => out.extend_from_slice(b),
+            TlvValue::Tlvs(sub) => encode_tlvs_into(sub, out)?,
+        }
+
+        Ok(())
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, EncodeError> {
+        let mut out = Vec::with_capacity(3 + self.value_len());
+        self.encode_into(&mut out)?;
+
+        Ok(out)
+    }
+}
+
+impl From<&RphyTlv<'_>> for TlvNode {
+    fn from(tlv: &RphyTlv) -> Self {
+        TlvNode::bytes(tlv.typ, tlv.val)
+    }
+}
+
+pub fn encode_tlvs_into(tlvs: &[TlvNode], out: &mut Vec<u8>) -> Result<(), EncodeError> {
+    tlvs.iter().try_for_each(|t| t.encode_into(out))
+}
+
+/// Encodes a stream of TLVs, e.g. the data of a vendor EDS message.
+pub fn encode_tlvs(tlvs: &[TlvNode]) -> Result<Vec<u8>, EncodeError> {
+    let mut out = Vec::new();
+    encode_tlvs_into(tlvs, &mut out)?;
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::tlv_parser::parse_tlvs;
+    use nom::multi::many0;
+    use std::net::Ipv4Addr;
+
+    /// Decodes `input` back into nodes, recursing into the `complex` types.
+    fn decode(input: &[u8], complex: &[u8]) -> Vec<TlvNode> {
+        let (rest, tlvs) = many0(parse_tlvs)(input).unwrap();
+        assert!(rest.is_empty(), "trailing bytes: {rest:?}");
+
+        tlvs.iter()
+            .map(|t| match complex.contains(&t.typ) {
+                true => TlvNode::complex(t.typ, decode(t.val, complex)),
+                false => TlvNode::from(t),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn leaf_round_trip() {
+        let tlvs = vec![
+            TlvNode::u8(1, 7),
+            TlvNode::u16(2, 0x1234),
+            TlvNode::u32(3, 0xdead_beef),
+            TlvNode::u64(4, u64::MAX),
+            TlvNode::bool(5, true),
+            TlvNode::string(6, "RPD"),
+            TlvNode::ip(7, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            TlvNode::bytes(8, &[]),
+        ];
+        let bytes = encode_tlvs(&tlvs).unwrap();
+
+        assert_eq!(&bytes[..4], &[1, 0, 1, 7]);
+        assert_eq!(decode(&bytes, &[]), tlvs);
+    }
+
+    #[test]
+    fn nested_round_trip() {
+        let tlvs = vec![TlvNode::complex(
+            50,
+            vec![
+                TlvNode::u16(1, 2),
+                TlvNode::complex(
+                    19,
+                    vec![
+                        TlvNode::u8(1, 1),
+                        TlvNode::complex(2, vec![TlvNode::u32(3, 9)]),
+                    ],
+                ),
+                TlvNode::complex(20, vec![]),
+            ],
+        )];
+        let bytes = encode_tlvs(&tlvs).unwrap();
+
+        // Lengths cover the nested TLVs, type and length fields included.
+        assert_eq!(bytes.len(), 3 + tlvs[0].value_len());
+        assert_eq!(&bytes[..3], &[50, 0, 25]);
+        assert_eq!(decode(&bytes, &[50, 19, 2, 20]), tlvs);
+    }
+
+    #[test]
+    fn rejects_values_over_16_bits() {
+        let tlv = TlvNode::bytes(1, &vec![0; 0x1_0000]);
+        assert_eq!(tlv.encode(), Err(EncodeError::TooLong(0x1_0000)));
+
+        let nested = TlvNode::complex(2, vec![TlvNode::bytes(1, &vec![0; 0xffff])]);
+        assert_eq!(nested.encode(), Err(EncodeError::TooLong(0x1_0002)));
+    }
+}
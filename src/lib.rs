@@ -0,0 +1,5 @@
+pub mod anonymizer;
+pub mod capture;
+pub mod encoders;
+pub mod parsers;
+pub mod reports;
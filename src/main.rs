@@ -1,7 +1,28 @@
-use clap::{App, Arg};
+use clap::{App, Arg, ArgMatches};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-mod parsers;
-use crate::parsers::gcp_parser::parser;
+use pcap_rparser::anonymizer::{anonymize_file, Anonymizer};
+use pcap_rparser::capture::capture;
+use pcap_rparser::encoders::craft::{craft, CraftDescription, CraftFormat};
+use pcap_rparser::encoders::pcap_builder::GCP_PORT;
+use pcap_rparser::parsers::byte_input::{read_bytes, InputFormat, Layer};
+use pcap_rparser::parsers::dictionary::{dictionary, load_dictionary, select_spec_version};
+use pcap_rparser::parsers::filter::{parse_time, FilterError, FrameFilter, TlvSelector};
+use pcap_rparser::parsers::gcp_parser::{decode_bytes, parser, ParseOptions};
+use pcap_rparser::parsers::hex_view::enable_hex_view;
+use pcap_rparser::parsers::vendor::load_vendor_definitions;
+use pcap_rparser::reports::bringup::bring_up;
+use pcap_rparser::reports::depi::depi_traffic;
+use pcap_rparser::reports::failover::track_failovers;
+use pcap_rparser::reports::inventory::inventory;
+use pcap_rparser::reports::l2tp::l2tp_sessions;
+use pcap_rparser::reports::perf::perf_series;
+use pcap_rparser::reports::pseudowire::pseudowires;
+use pcap_rparser::reports::ptp::ptp_timing;
+use pcap_rparser::reports::throttle::check_throttling;
+use pcap_rparser::reports::topology::topology;
+use pcap_rparser::reports::{ReportError, ReportFormat};
 
 fn main() {
     let matches = App::new("pcap_rparser - PCAP RPHY Parser.")
@@ -11,13 +32,589 @@ fn main() {
         .about("Decodes GCP messages from PCAP network capture files.")
         .arg(
             Arg::new("PCAP_FILE")
-                .help("File path.")
-                .required(true)
+                .help("File path, - for stdin.")
+                .required_unless_present("INTERFACE")
                 .index(1),
         )
+        .arg(
+            Arg::new("FOLLOW")
+                .long("follow")
+                .short('f')
+                .help("Keeps decoding new frames as the file grows, like tail -f.")
+                .conflicts_with("INTERFACE"),
+        )
+        .arg(
+            Arg::new("INPUT_FORMAT")
+                .long("input-format")
+                .help("How the input is written. raw, hex and base64 hold GCP bytes without pcap framing.")
+                .takes_value(true)
+                .possible_values(["pcap", "raw", "hex", "base64"])
+                .default_value("pcap"),
+        )
+        .arg(
+            Arg::new("LAYER")
+                .long("layer")
+                .help("Layer raw, hex and base64 input starts at: GCP messages, an RCP message or RCP sequence TLVs.")
+                .takes_value(true)
+                .possible_values(["gcp", "rcp", "tlv"])
+                .default_value("gcp"),
+        )
+        .arg(
+            Arg::new("HEX")
+                .long("hex")
+                .help("Shows the offset in the frame and the raw bytes of every decoded field and TLV."),
+        )
+        .arg(
+            Arg::new("SUMMARY")
+                .long("summary")
+                .help("Prints totals by GCP message, RCP message, Operation and TLV, and the unsupported TLVs, instead of every frame.")
+                .conflicts_with_all(&["FOLLOW", "INTERFACE"]),
+        )
+        .arg(
+            Arg::new("INTERFACE")
+                .long("interface")
+                .short('i')
+                .help("Network interface to capture from live instead of reading a file.")
+                .takes_value(true)
+                .conflicts_with("PCAP_FILE"),
+        )
+        .arg(
+            Arg::new("DEDUPE")
+                .long("dedupe")
+                .help("Drops the second copy of frames captured twice within 10 ms, e.g. on the loopback interface.")
+                .conflicts_with("PCAP_FILE"),
+        )
+        .arg(
+            Arg::new("DICTIONARY")
+                .long("dictionary")
+                .help("TOML TLV dictionary to use instead of the built-in one.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("SPEC_VERSION")
+                .long("spec-version")
+                .help("R-PHY specification version whose TLV definitions are used, e.g. I10. Defaults to the latest.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("VENDOR_TLVS")
+                .long("vendor-tlvs")
+                .help("TOML file with vendor-specific TLV definitions. Can be repeated.")
+                .takes_value(true)
+                .multiple_occurrences(true),
+        )
+        .arg(
+            Arg::new("MESSAGE")
+                .long("message")
+                .help("Only frames with this GCP message, e.g. EdsRequest or 6. Can be repeated.")
+                .takes_value(true)
+                .multiple_occurrences(true),
+        )
+        .arg(
+            Arg::new("TLV")
+                .long("tlv")
+                .help("Only frames with this RCP TLV, by name or sequence TLV type. Can be repeated.")
+                .takes_value(true)
+                .multiple_occurrences(true),
+        )
+        .arg(
+            Arg::new("RPD")
+                .long("rpd")
+                .help("Only frames to or from this IP address. Can be repeated.")
+                .takes_value(true)
+                .multiple_occurrences(true),
+        )
+        .arg(
+            Arg::new("PORT")
+                .long("port")
+                .help("Only frames to or from this TCP port. Can be repeated. Defaults to 8190 when capturing live.")
+                .takes_value(true)
+                .multiple_occurrences(true),
+        )
+        .arg(
+            Arg::new("FROM")
+                .long("from")
+                .help("Only frames captured at or after this time, RFC 3339 or seconds since epoch.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("TO")
+                .long("to")
+                .help("Only frames captured at or before this time, RFC 3339 or seconds since epoch.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("WRITE")
+                .long("write")
+                .help("Copies the matching frames, unchanged, to this pcap file.")
+                .takes_value(true),
+        )
+        .subcommand_negates_reqs(true)
+        .subcommand(
+            App::new("craft")
+                .about("Encodes a GCP message described in YAML or JSON.")
+                .arg(
+                    Arg::new("DESCRIPTION")
+                        .help("YAML or JSON message description.")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("FORMAT")
+                        .long("format")
+                        .help("Output format.")
+                        .takes_value(true)
+                        .possible_values(["hex", "raw", "pcap"])
+                        .default_value("hex"),
+                )
+                .arg(
+                    Arg::new("OUTPUT")
+                        .long("output")
+                        .short('o')
+                        .help("Output file, stdout by default.")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            App::new("anonymize")
+                .about("Rewrites IP and MAC addresses, CoreIds, serial numbers and locations of a capture.")
+                .arg(
+                    Arg::new("INPUT")
+                        .help("Capture to anonymize.")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("OUTPUT")
+                        .help("Anonymized capture to write.")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("KEY")
+                        .long("key")
+                        .help("Secret key of the mapping. The same key gives the same replacements.")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("MAPPING")
+                        .long("mapping")
+                        .help("CSV file to write the original and anonymized values to.")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(report_command(
+            "inventory",
+            "Lists every RPD of the captures with its identification, location and capacity.",
+        ))
+        .subcommand(
+            report_command(
+                "topology",
+                "Lists the principal, auxiliary and backup Cores of every RPD of the captures.",
+            )
+            .mut_arg("FORMAT", |format| format.possible_value("dot")),
+        )
+        .subcommand(
+            report_command(
+                "failover",
+                "Lists the Core failovers and handovers of every RPD of the captures.",
+            )
+            .arg(
+                Arg::new("EVENTS")
+                    .long("events")
+                    .help("Lists every handover and failover event instead of the failovers."),
+            ),
+        )
+        .subcommand(
+            report_command(
+                "bringup",
+                "Lists the bring-up steps of every RPD of the captures, with their durations.",
+            )
+            .arg(
+                Arg::new("EVENTS")
+                    .long("events")
+                    .help("Lists every RPD state change and notification instead of the steps."),
+            ),
+        )
+        .subcommand(report_command(
+            "perf",
+            "Computes rates, codeword error ratios and SNR/MER trends from successive channel performance reads.",
+        ))
+        .subcommand(
+            report_command(
+                "throttle",
+                "Checks the Notifies of every RPD of the captures against the EvCfg of its Core.",
+            )
+            .arg(
+                Arg::new("VIOLATIONS")
+                    .long("violations")
+                    .help("Lists every Notify the EvCfg did not allow instead of a row per RPD."),
+            ),
+        )
+        .subcommand(report_command(
+            "pseudowires",
+            "Lists the static pseudowires of every RPD of the captures, config joined with status.",
+        ))
+        .subcommand(
+            report_command(
+                "l2tp",
+                "Lists the L2TPv3 sessions of the captures, DEPI and UEPI included, with their setup and teardown.",
+            )
+            .arg(
+                Arg::new("CONNECTIONS")
+                    .long("connections")
+                    .help("Lists the control connections instead of the sessions."),
+            ),
+        )
+        .subcommand(report_command(
+            "depi",
+            "Counts the DEPI and UEPI data packets, bytes and sequence gaps of every L2TPv3 session.",
+        ))
+        .subcommand(
+            report_command(
+                "ptp",
+                "Lines up the PTP exchange of every RPD with the PtpResultNotifications it sends over GCP.",
+            )
+            .arg(
+                Arg::new("CLOCKS")
+                    .long("clocks")
+                    .help("Lists the messages of every PTP port and destination instead."),
+            ),
+        )
         .get_matches();
 
+    // The dictionary goes first, vendor definitions can use its enums.
+    if let Some(file) = matches.value_of("DICTIONARY") {
+        if let Err(e) = load_dictionary(Path::new(file)) {
+            eprintln!("error: loading TLV dictionary: '{}'. {}", file, e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(version) = matches.value_of("SPEC_VERSION") {
+        if let Err(e) = select_spec_version(version) {
+            eprintln!(
+                "error: selecting specification version: {}. Known versions: {}",
+                e,
+                dictionary().spec.versions.join(", ")
+            );
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(files) = matches.values_of("VENDOR_TLVS") {
+        for file in files {
+            if let Err(e) = load_vendor_definitions(Path::new(file)) {
+                eprintln!("error: loading vendor TLV definitions: '{}'. {}", file, e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    match matches.subcommand() {
+        Some(("craft", craft_matches)) => return craft_command(craft_matches),
+        Some(("anonymize", anon_matches)) => return anonymize_command(anon_matches),
+        Some(("inventory", inv_matches)) => return inventory_command(inv_matches),
+        Some(("topology", topo_matches)) => return topology_command(topo_matches),
+        Some(("failover", fail_matches)) => return failover_command(fail_matches),
+        Some(("bringup", bringup_matches)) => return bringup_command(bringup_matches),
+        Some(("perf", perf_matches)) => return perf_command(perf_matches),
+        Some(("throttle", throttle_matches)) => return throttle_command(throttle_matches),
+        Some(("pseudowires", pw_matches)) => return pseudowires_command(pw_matches),
+        Some(("l2tp", l2tp_matches)) => return l2tp_command(l2tp_matches),
+        Some(("depi", depi_matches)) => return depi_command(depi_matches),
+        Some(("ptp", ptp_matches)) => return ptp_command(ptp_matches),
+        _ => (),
+    }
+
+    if matches.is_present("HEX") {
+        enable_hex_view();
+    }
+
+    let mut options = ParseOptions {
+        filter: frame_filter(&matches),
+        write: matches.value_of("WRITE").map(PathBuf::from),
+        follow: matches.is_present("FOLLOW"),
+        summary: matches.is_present("SUMMARY"),
+    };
+
+    if let Some(interface) = matches.value_of("INTERFACE") {
+        if options.filter.ports.is_empty() {
+            options.filter.ports.push(GCP_PORT);
+        }
+        if let Err(e) = capture(interface, &options, matches.is_present("DEDUPE")) {
+            eprintln!("error: capturing on '{}'. {}", interface, e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let file_name = matches.value_of("PCAP_FILE").unwrap();
+    let format: InputFormat = matches.value_of_t("INPUT_FORMAT").unwrap();
+    if format != InputFormat::Pcap {
+        let layer: Layer = matches.value_of_t("LAYER").unwrap();
+        match read_bytes(file_name, format) {
+            Ok(bytes) => {
+                if let Err(e) = decode_bytes(&bytes, layer) {
+                    eprintln!("error: decoding input: '{}'. {}", file_name, e);
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("error: reading input: '{}'. {}", file_name, e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    parser(file_name, &options);
+}
+
+fn frame_filter(matches: &ArgMatches) -> FrameFilter {
+    let values = |name| matches.values_of(name).into_iter().flatten();
+    let mut filter = FrameFilter::default();
+
+    for m in values("MESSAGE") {
+        match m.parse() {
+            Ok(id) => filter.messages.push(id),
+            Err(()) => filter_error(FilterError::UnknownMessage(m.to_string())),
+        }
+    }
+    filter.tlvs = values("TLV").map(TlvSelector::parse).collect();
+    for port in values("PORT") {
+        match port.parse() {
+            Ok(port) => filter.ports.push(port),
+            Err(e) => filter_error(format!("'{}'. {}", port, e)),
+        }
+    }
+    for ip in values("RPD") {
+        match ip.parse() {
+            Ok(ip) => filter.rpds.push(ip),
+            Err(e) => filter_error(format!("'{}'. {}", ip, e)),
+        }
+    }
+    filter.from = matches
+        .value_of("FROM")
+        .map(|t| parse_time(t).unwrap_or_else(|e| filter_error(e)));
+    filter.to = matches
+        .value_of("TO")
+        .map(|t| parse_time(t).unwrap_or_else(|e| filter_error(e)));
+
+    filter
+}
+
+fn filter_error(e: impl std::fmt::Display) -> ! {
+    eprintln!("error: invalid filter: {}", e);
+    std::process::exit(1);
+}
+
+fn craft_command(matches: &ArgMatches) {
+    let file = matches.value_of("DESCRIPTION").unwrap();
+    let format = match matches.value_of("FORMAT") {
+        Some("raw") => CraftFormat::Raw,
+        Some("pcap") => CraftFormat::Pcap,
+        _ => CraftFormat::Hex,
+    };
+
+    let bytes = match CraftDescription::from_file(Path::new(file)).and_then(|d| craft(&d, format)) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("error: crafting message: '{}'. {}", file, e);
+            std::process::exit(1);
+        }
+    };
+
+    let written = match matches.value_of("OUTPUT") {
+        Some(out) => std::fs::write(out, &bytes),
+        None => std::io::stdout().write_all(&bytes),
+    };
+    if let Err(e) = written {
+        eprintln!("error: writing crafted message. {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn anonymize_command(matches: &ArgMatches) {
+    let input = matches.value_of("INPUT").unwrap();
+    let output = matches.value_of("OUTPUT").unwrap();
+    let mut anonymizer = Anonymizer::new(matches.value_of("KEY").unwrap().as_bytes());
+
+    match anonymize_file(&mut anonymizer, Path::new(input), Path::new(output)) {
+        Ok(frames) => {
+            println!("{} frames anonymized to '{}'", frames, output);
+            if anonymizer.blanked() > 0 {
+                eprintln!(
+                    "warning: {} bytes of GCP messages missing a segment were zeroed",
+                    anonymizer.blanked()
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("error: anonymizing capture: '{}'. {}", input, e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(file) = matches.value_of("MAPPING") {
+        if let Err(e) = std::fs::write(file, anonymizer.mapping_csv()) {
+            eprintln!("error: writing mapping: '{}'. {}", file, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Subcommand reading the captures given as arguments into a report.
+fn report_command(name: &'static str, about: &'static str) -> App<'static> {
+    App::new(name)
+        .about(about)
+        .arg(
+            Arg::new("CAPTURES")
+                .help("Captures to read, in order.")
+                .required(true)
+                .multiple_values(true)
+                .index(1),
+        )
+        .arg(report_format_arg())
+        .arg(report_output_arg())
+}
+
+fn report_format_arg() -> Arg<'static> {
+    Arg::new("FORMAT")
+        .long("format")
+        .help("Report format.")
+        .takes_value(true)
+        .possible_values(["table", "csv", "json"])
+        .default_value("table")
+}
+
+fn report_output_arg() -> Arg<'static> {
+    Arg::new("OUTPUT")
+        .long("output")
+        .short('o')
+        .help("Output file, stdout by default.")
+        .takes_value(true)
+}
+
+/// Reads the captures of a report subcommand with `read`, exits if one cannot be read.
+fn read_captures<T>(
+    matches: &ArgMatches,
+    read: impl FnOnce(&[&Path]) -> Result<T, ReportError>,
+) -> T {
+    let captures: Vec<&Path> = matches
+        .values_of("CAPTURES")
+        .unwrap()
+        .map(Path::new)
+        .collect();
+
+    match read(&captures) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: reading captures. {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn write_report(matches: &ArgMatches, report: &str) {
+    let written = match matches.value_of("OUTPUT") {
+        Some(out) => std::fs::write(out, report),
+        None => std::io::stdout().write_all(report.as_bytes()),
+    };
+    if let Err(e) = written {
+        eprintln!("error: writing report. {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn inventory_command(matches: &ArgMatches) {
+    let format: ReportFormat = matches.value_of_t("FORMAT").unwrap();
+    let inventory = read_captures(matches, inventory);
+    write_report(matches, &inventory.table().render(format));
+}
+
+fn topology_command(matches: &ArgMatches) {
+    let topology = read_captures(matches, topology);
+
+    let report = match matches.value_of("FORMAT") {
+        Some("dot") => topology.dot(),
+        _ => topology
+            .table()
+            .render(matches.value_of_t("FORMAT").unwrap()),
+    };
+    write_report(matches, &report);
+}
+
+fn failover_command(matches: &ArgMatches) {
+    let format: ReportFormat = matches.value_of_t("FORMAT").unwrap();
+    let tracker = read_captures(matches, track_failovers);
+
+    let table = match matches.is_present("EVENTS") {
+        true => tracker.events(),
+        false => tracker.failovers(),
+    };
+    write_report(matches, &table.render(format));
+}
+
+fn bringup_command(matches: &ArgMatches) {
+    let format: ReportFormat = matches.value_of_t("FORMAT").unwrap();
+    let bring_up = read_captures(matches, bring_up);
+
+    let table = match matches.is_present("EVENTS") {
+        true => bring_up.events(),
+        false => bring_up.steps(),
+    };
+    write_report(matches, &table.render(format));
+}
+
+fn perf_command(matches: &ArgMatches) {
+    let format: ReportFormat = matches.value_of_t("FORMAT").unwrap();
+    let series = read_captures(matches, perf_series);
+    write_report(matches, &series.rates().render(format));
+}
+
+fn throttle_command(matches: &ArgMatches) {
+    let format: ReportFormat = matches.value_of_t("FORMAT").unwrap();
+    let check = read_captures(matches, check_throttling);
+
+    let table = match matches.is_present("VIOLATIONS") {
+        true => check.violations(),
+        false => check.summary(),
+    };
+    write_report(matches, &table.render(format));
+}
+
+fn pseudowires_command(matches: &ArgMatches) {
+    let format: ReportFormat = matches.value_of_t("FORMAT").unwrap();
+    let pseudowires = read_captures(matches, pseudowires);
+    write_report(matches, &pseudowires.table().render(format));
+}
+
+fn l2tp_command(matches: &ArgMatches) {
+    let format: ReportFormat = matches.value_of_t("FORMAT").unwrap();
+    let sessions = read_captures(matches, l2tp_sessions);
+
+    let table = match matches.is_present("CONNECTIONS") {
+        true => sessions.connections(),
+        false => sessions.sessions(),
+    };
+    write_report(matches, &table.render(format));
+}
+
+fn depi_command(matches: &ArgMatches) {
+    let format: ReportFormat = matches.value_of_t("FORMAT").unwrap();
+    let traffic = read_captures(matches, depi_traffic);
+    write_report(matches, &traffic.table().render(format));
+}
+
+fn ptp_command(matches: &ArgMatches) {
+    let format: ReportFormat = matches.value_of_t("FORMAT").unwrap();
+    let timing = read_captures(matches, ptp_timing);
 
-    parser(file_name);
+    let table = match matches.is_present("CLOCKS") {
+        true => timing.clocks(),
+        false => timing.results(),
+    };
+    write_report(matches, &table.render(format));
 }
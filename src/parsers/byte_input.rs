@@ -0,0 +1,290 @@
+use std::fmt;
+use std::io::{self, Read};
+use std::str::FromStr;
+
+/// How the bytes of a non pcap input are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Pcap,
+    /// Binary, as written by the RPD or the Core.
+    Raw,
+    /// Hex text: a hex stream, spaced or colon separated bytes, a Wireshark or tcpdump hex dump,
+    /// or log lines.
+    Hex,
+    Base64,
+}
+
+/// Protocol layer the bytes start at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// One or more GCP messages, header included.
+    Gcp,
+    /// An RCP message, from its message type.
+    Rcp,
+    /// RCP sequence TLVs.
+    Tlv,
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Io(io::Error),
+    InvalidBase64(base64::DecodeError),
+    NoBytes,
+    Unknown(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io(e) => write!(f, "{e}"),
+            InputError::InvalidBase64(e) => write!(f, "invalid base64: {e}"),
+            InputError::NoBytes => write!(f, "no bytes found in input"),
+            InputError::Unknown(s) => write!(f, "unknown value '{s}'"),
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pcap" => Ok(InputFormat::Pcap),
+            "raw" => Ok(InputFormat::Raw),
+            "hex" => Ok(InputFormat::Hex),
+            "base64" => Ok(InputFormat::Base64),
+            _ => Err(InputError::Unknown(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Layer::Gcp => f.write_str("GCP"),
+            Layer::Rcp => f.write_str("RCP"),
+            Layer::Tlv => f.write_str("TLV"),
+        }
+    }
+}
+
+impl FromStr for Layer {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gcp" => Ok(Layer::Gcp),
+            "rcp" => Ok(Layer::Rcp),
+            "tlv" => Ok(Layer::Tlv),
+            _ => Err(InputError::Unknown(s.to_string())),
+        }
+    }
+}
+
+/// Reads `filename`, or stdin for `-`, and returns the bytes it holds.
+pub fn read_bytes(filename: &str, format: InputFormat) -> Result<Vec<u8>, InputError> {
+    let mut input = Vec::new();
+    if filename == "-" {
+        io::stdin()
+            .read_to_end(&mut input)
+            .map_err(InputError::Io)?;
+    } else {
+        input = std::fs::read(filename).map_err(InputError::Io)?;
+    }
+
+    let bytes = match format {
+        InputFormat::Pcap | InputFormat::Raw => input,
+        InputFormat::Hex => hex_text_to_bytes(&String::from_utf8_lossy(&input)),
+        InputFormat::Base64 => base64_text_to_bytes(&String::from_utf8_lossy(&input))?,
+    };
+
+    if bytes.is_empty() {
+        return Err(InputError::NoBytes);
+    }
+    Ok(bytes)
+}
+
+/// Decodes base64 text, which may be wrapped over several lines.
+pub fn base64_text_to_bytes(text: &str) -> Result<Vec<u8>, InputError> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    base64::decode(text).map_err(InputError::InvalidBase64)
+}
+
+/// Extracts the bytes of hex text, line by line.
+///
+/// ```text
+/// 0001000100150000060b...                               hex stream
+/// 00 01 00 01 00 15 00 00                               spaced bytes, 0x00, 0x01 arrays
+/// 00:01:00:01:00:15:00:00                               colon separated bytes
+/// 0000   00 01 00 01 00 15 00 00 06 0b 00 01   ........ Wireshark hex dump
+/// 0x0000:  0001 0001 0015 0000 060b                     tcpdump -x
+/// Oct  5 13:04:05 rpd gcp: rx 00 01 00 01 00 15         log line
+/// ```
+///
+/// An offset column is dropped and at most 16 bytes are taken after it, so the ASCII
+/// column of a dump is ignored. Other lines keep their longest run of hex tokens.
+pub fn hex_text_to_bytes(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    for line in text.lines() {
+        let tokens: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        let run = match tokens.split_first() {
+            Some((first, rest)) if is_offset(first, rest) => {
+                let mut run = Vec::new();
+                for token in rest.iter().map_while(|t| hex_token(t)) {
+                    if run.len() + token.len() > 16 {
+                        break;
+                    }
+                    run.extend(token);
+                }
+                run
+            }
+            _ => longest_hex_run(&tokens),
+        };
+        bytes.extend(run);
+    }
+
+    bytes
+}
+
+/// `0x0010:` or `0010:`, or `0010` followed by single byte groups as in a Wireshark dump.
+fn is_offset(token: &str, rest: &[&str]) -> bool {
+    let digits = token.trim_start_matches("0x").trim_end_matches(':');
+    let is_hex = digits.len() >= 4 && digits.chars().all(|c| c.is_ascii_hexdigit());
+
+    is_hex
+        && (token.ends_with(':')
+            || token.starts_with("0x") && !rest.is_empty()
+            || rest.first().is_some_and(|t| t.len() == 2))
+}
+
+fn longest_hex_run(tokens: &[&str]) -> Vec<u8> {
+    let mut longest = Vec::new();
+    let mut current = Vec::new();
+
+    for token in tokens {
+        match hex_token(token) {
+            Some(b) => current.extend(b),
+            None => {
+                if current.len() > longest.len() {
+                    longest = std::mem::take(&mut current);
+                }
+                current.clear();
+            }
+        }
+    }
+
+    if current.len() > longest.len() {
+        current
+    } else {
+        longest
+    }
+}
+
+/// Bytes of a token made of an even number of hex digits, with an optional `0x` prefix,
+/// or of colon separated bytes.
+fn hex_token(token: &str) -> Option<Vec<u8>> {
+    if token.contains(':') {
+        return token
+            .split(':')
+            .map(|b| match b.len() {
+                2 => u8::from_str_radix(b, 16).ok(),
+                _ => None,
+            })
+            .collect();
+    }
+    let digits = token.strip_prefix("0x").unwrap_or(token);
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_hex_streams_and_separated_bytes() {
+        assert_eq!(hex_text_to_bytes("0001000A0b\n"), [0, 1, 0, 0x0a, 0x0b]);
+        assert_eq!(hex_text_to_bytes("00 01\n0a 0b"), [0, 1, 0x0a, 0x0b]);
+        assert_eq!(hex_text_to_bytes("0x00, 0x01, 0x0a,"), [0, 1, 0x0a]);
+        assert_eq!(hex_text_to_bytes("00:01:0a:0b"), [0, 1, 0x0a, 0x0b]);
+        assert_eq!(
+            hex_text_to_bytes("Oct  5 13:04:05 rpd gcp: rx 00 01 00 01 00 15"),
+            [0, 1, 0, 1, 0, 0x15]
+        );
+    }
+
+    #[test]
+    fn drops_the_offsets_and_ascii_of_hex_dumps() {
+        let wireshark = "\
+0000   00 01 00 01 00 15 00 00 06 0b 00 01 02 03 04 05   ................
+0010   06 07                                             ..
+";
+        let mut expected = vec![0, 1, 0, 1, 0, 0x15, 0, 0, 6, 0x0b, 0, 1, 2, 3, 4, 5];
+        expected.extend([6, 7]);
+        assert_eq!(hex_text_to_bytes(wireshark), expected);
+
+        let tcpdump = "\
+\t0x0000:  0001 0001 0015 0000 060b 0001 0203 0405  ................
+\t0x0010:  0607
+";
+        assert_eq!(hex_text_to_bytes(tcpdump), expected);
+        assert_eq!(hex_text_to_bytes("0010: 0a 0b"), [0x0a, 0x0b]);
+    }
+
+    #[test]
+    fn ignores_what_is_not_hex() {
+        assert!(hex_text_to_bytes("").is_empty());
+        assert!(hex_text_to_bytes("no bytes here\n").is_empty());
+        // Odd digit counts and bad colon groups are not bytes.
+        assert_eq!(hex_text_to_bytes("abc 0g 00:1:02 0a"), [0x0a]);
+    }
+
+    #[test]
+    fn reads_base64_over_several_lines() {
+        assert_eq!(
+            base64_text_to_bytes("AAEA\nAQAV\n").unwrap(),
+            [0, 1, 0, 1, 0, 0x15]
+        );
+        assert!(matches!(
+            base64_text_to_bytes("AA*"),
+            Err(InputError::InvalidBase64(_))
+        ));
+    }
+
+    #[test]
+    fn reports_unreadable_and_empty_input() {
+        let missing = std::env::temp_dir().join("pcap_rparser-no-such-input");
+        let missing = missing.to_str().unwrap();
+        assert!(matches!(
+            read_bytes(missing, InputFormat::Hex),
+            Err(InputError::Io(_))
+        ));
+
+        let file = format!("pcap_rparser-{}-bytes.txt", std::process::id());
+        let path = std::env::temp_dir().join(file);
+        std::fs::write(&path, "not hex\n").unwrap();
+        let read = read_bytes(path.to_str().unwrap(), InputFormat::Hex);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(read, Err(InputError::NoBytes)));
+
+        assert!(matches!(
+            "hexdump".parse::<InputFormat>(),
+            Err(InputError::Unknown(s)) if s == "hexdump"
+        ));
+        assert!(matches!(
+            "l2tp".parse::<Layer>(),
+            Err(InputError::Unknown(_))
+        ));
+    }
+}
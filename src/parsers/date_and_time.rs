@@ -0,0 +1,317 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike, Utc};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// DateAndTime textual convention from RFC 2579 (SNMPv2-TC).
+///
+/// ```text
+/// field  octets  contents                  range
+/// -----  ------  --------                  -----
+///   1     1-2    year*                     0..65536
+///   2      3     month                     1..12
+///   3      4     day                       1..31
+///   4      5     hour                      0..23
+///   5      6     minutes                   0..59
+///   6      7     seconds                   0..60 (use 60 for leap-second)
+///   7      8     deci-seconds              0..9
+///   8      9     direction from UTC        '+' / '-'
+///   9     10     hours from UTC*           0..13
+///  10     11     minutes from UTC          0..59
+/// ```
+///
+/// The 8 byte form carries no UTC offset and is local time of the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateAndTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub deci_seconds: u8,
+    /// Offset from UTC in minutes. `None` for the 8 byte form.
+    pub utc_offset: Option<i16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateAndTimeError {
+    InvalidLength(usize),
+    OutOfRange(&'static str, u16),
+    InvalidDirection(u8),
+    /// Text not in the ISO-8601 form of `Display`.
+    InvalidText,
+}
+
+impl fmt::Display for DateAndTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateAndTimeError::InvalidLength(n) => {
+                write!(f, "invalid length {n}, expected 8 or 11 bytes")
+            }
+            DateAndTimeError::OutOfRange(field, v) => write!(f, "{field} out of range: {v}"),
+            DateAndTimeError::InvalidDirection(d) => {
+                write!(f, "invalid direction from UTC: {d:#04X}")
+            }
+            DateAndTimeError::InvalidText => {
+                write!(f, "expected YYYY-MM-DDThh:mm:ss[.s][offset]")
+            }
+        }
+    }
+}
+
+fn check(field: &'static str, value: u8, min: u8, max: u8) -> Result<u8, DateAndTimeError> {
+    if (min..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(DateAndTimeError::OutOfRange(field, value.into()))
+    }
+}
+
+impl DateAndTime {
+    pub fn parse(bytes: &[u8]) -> Result<Self, DateAndTimeError> {
+        if bytes.len() != 8 && bytes.len() != 11 {
+            return Err(DateAndTimeError::InvalidLength(bytes.len()));
+        }
+
+        let date = DateAndTime {
+            year: u16::from_be_bytes([bytes[0], bytes[1]]),
+            month: check("month", bytes[2], 1, 12)?,
+            day: check("day", bytes[3], 1, 31)?,
+            hour: check("hour", bytes[4], 0, 23)?,
+            minutes: check("minutes", bytes[5], 0, 59)?,
+            seconds: check("seconds", bytes[6], 0, 60)?,
+            deci_seconds: check("deci-seconds", bytes[7], 0, 9)?,
+            utc_offset: None,
+        };
+
+        let date = if bytes.len() == 11 {
+            let sign = match bytes[8] {
+                b'+' => 1,
+                b'-' => -1,
+                d => return Err(DateAndTimeError::InvalidDirection(d)),
+            };
+            let hours = check("hours from UTC", bytes[9], 0, 13)? as i16;
+            let minutes = check("minutes from UTC", bytes[10], 0, 59)? as i16;
+
+            DateAndTime {
+                utc_offset: Some(sign * (hours * 60 + minutes)),
+                ..date
+            }
+        } else {
+            date
+        };
+
+        // Rejects impossible calendar dates such as February 30th.
+        date.naive()?;
+
+        Ok(date)
+    }
+
+    fn naive(self) -> Result<NaiveDateTime, DateAndTimeError> {
+        let date = NaiveDate::from_ymd_opt(self.year.into(), self.month.into(), self.day.into())
+            .ok_or(DateAndTimeError::OutOfRange("day", self.day.into()))?;
+
+        // chrono represents a leap second as the 59th second plus an extra second of fraction.
+        let (seconds, leap_ms) = if self.seconds == 60 {
+            (59, 1000)
+        } else {
+            (self.seconds, 0)
+        };
+        let millis = leap_ms + u32::from(self.deci_seconds) * 100;
+
+        date.and_hms_milli_opt(
+            self.hour.into(),
+            self.minutes.into(),
+            seconds.into(),
+            millis,
+        )
+        .ok_or(DateAndTimeError::OutOfRange("seconds", self.seconds.into()))
+    }
+
+    /// Wire form: 11 bytes when the UTC offset is known, 8 bytes otherwise.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.year.to_be_bytes().to_vec();
+        out.extend_from_slice(&[
+            self.month,
+            self.day,
+            self.hour,
+            self.minutes,
+            self.seconds,
+            self.deci_seconds,
+        ]);
+        if let Some(o) = self.utc_offset {
+            let direction = if o < 0 { b'-' } else { b'+' };
+            out.extend_from_slice(&[direction, (o.abs() / 60) as u8, (o.abs() % 60) as u8]);
+        }
+
+        out
+    }
+
+    /// Converts to UTC. The 8 byte form has no offset and is taken as UTC.
+    pub fn to_utc(self) -> DateTime<Utc> {
+        let naive = self.naive().expect("DateAndTime validated on parse");
+        let offset = i32::from(self.utc_offset.unwrap_or(0)) * 60;
+
+        DateTime::<Utc>::from_naive_utc_and_offset(
+            naive - chrono::Duration::seconds(offset.into()),
+            Utc,
+        )
+    }
+}
+
+/// ISO-8601 with the original UTC offset, e.g. `2022-10-05T13:04:05.0+02:00`.
+/// The 8 byte form is printed without an offset designator.
+impl fmt::Display for DateAndTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{}",
+            self.year,
+            self.month,
+            self.day,
+            self.hour,
+            self.minutes,
+            self.seconds,
+            self.deci_seconds
+        )?;
+
+        match self.utc_offset {
+            Some(0) => write!(f, "Z"),
+            Some(o) => {
+                let sign = if o < 0 { '-' } else { '+' };
+                write!(f, "{sign}{:02}:{:02}", o.abs() / 60, o.abs() % 60)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses the form printed by `Display`, e.g. `2022-10-05T13:04:05.0+02:00`.
+/// Without an offset designator the date is the 8 byte form.
+impl FromStr for DateAndTime {
+    type Err = DateAndTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (naive, utc_offset) = match DateTime::parse_from_rfc3339(s) {
+            Ok(d) => (
+                d.naive_local(),
+                Some((d.offset().local_minus_utc() / 60) as i16),
+            ),
+            Err(_) => (
+                NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+                    .map_err(|_| DateAndTimeError::InvalidText)?,
+                None,
+            ),
+        };
+        let year = u16::try_from(naive.year()).map_err(|_| DateAndTimeError::InvalidText)?;
+
+        // chrono keeps a leap second as the 59th second with a second of fraction.
+        let (seconds, nanos) = match naive.nanosecond() {
+            n if n >= 1_000_000_000 => (60, n - 1_000_000_000),
+            n => (naive.second() as u8, n),
+        };
+
+        Ok(DateAndTime {
+            year,
+            month: naive.month() as u8,
+            day: naive.day() as u8,
+            hour: naive.hour() as u8,
+            minutes: naive.minute() as u8,
+            seconds,
+            deci_seconds: (nanos / 100_000_000) as u8,
+            utc_offset,
+        })
+    }
+}
+
+/// Capture time of a pcap record, from its seconds and microseconds.
+pub fn pcap_time(ts_sec: u32, ts_usec: u32) -> DateTime<Utc> {
+    pcap_time_nanos(ts_sec, ts_usec.saturating_mul(1000))
+}
+
+/// Capture time of a record of a nanosecond pcap, from its seconds and nanoseconds.
+pub fn pcap_time_nanos(ts_sec: u32, ts_nsec: u32) -> DateTime<Utc> {
+    DateTime::from_timestamp(i64::from(ts_sec), ts_nsec).unwrap_or_default()
+}
+
+/// Allows comparing a decoded date with a capture time such as a pcap timestamp.
+impl PartialEq<DateTime<Utc>> for DateAndTime {
+    fn eq(&self, other: &DateTime<Utc>) -> bool {
+        self.to_utc() == *other
+    }
+}
+
+impl PartialOrd<DateTime<Utc>> for DateAndTime {
+    fn partial_cmp(&self, other: &DateTime<Utc>) -> Option<Ordering> {
+        Some(self.to_utc().cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCAL: [u8; 8] = [0x07, 0xe6, 10, 5, 13, 4, 5, 3];
+    const OFFSET: [u8; 11] = [0x07, 0xe6, 10, 5, 13, 4, 5, 3, b'+', 2, 0];
+
+    #[test]
+    fn parses_the_8_byte_form() {
+        let date = DateAndTime::parse(&LOCAL).unwrap();
+        assert_eq!(date.utc_offset, None);
+        assert_eq!(date.to_string(), "2022-10-05T13:04:05.3");
+        assert_eq!(date.to_bytes(), LOCAL);
+        assert_eq!("2022-10-05T13:04:05.3".parse(), Ok(date));
+    }
+
+    #[test]
+    fn parses_the_11_byte_form() {
+        let date = DateAndTime::parse(&OFFSET).unwrap();
+        assert_eq!(date.utc_offset, Some(120));
+        assert_eq!(date.to_string(), "2022-10-05T13:04:05.3+02:00");
+        assert_eq!(date.to_bytes(), OFFSET);
+        assert_eq!("2022-10-05T13:04:05.3+02:00".parse(), Ok(date));
+        assert_eq!(date.to_utc().to_rfc3339(), "2022-10-05T11:04:05.300+00:00");
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        assert_eq!(
+            DateAndTime::parse(&LOCAL[..7]),
+            Err(DateAndTimeError::InvalidLength(7))
+        );
+        let mut feb30 = LOCAL;
+        feb30[2..4].copy_from_slice(&[2, 30]);
+        assert!(DateAndTime::parse(&feb30).is_err());
+        let mut direction = OFFSET;
+        direction[8] = b'x';
+        assert_eq!(
+            DateAndTime::parse(&direction),
+            Err(DateAndTimeError::InvalidDirection(b'x'))
+        );
+    }
+
+    #[test]
+    fn keeps_leap_seconds() {
+        let bytes = [0x07, 0xe0, 12, 31, 23, 59, 60, 5, b'+', 0, 0];
+        let date = DateAndTime::parse(&bytes).unwrap();
+        let text = date.to_string();
+        assert_eq!(text, "2016-12-31T23:59:60.5Z");
+        assert_eq!(text.parse(), Ok(date));
+    }
+
+    #[test]
+    fn compares_with_capture_times() {
+        let date = DateAndTime::parse(&OFFSET).unwrap();
+        // 2022-10-05T11:04:05.3Z
+        let same = pcap_time(1_664_967_845, 300_000);
+        assert!(date == same);
+        assert!(date < pcap_time(1_664_967_845, 300_001));
+        assert!(date > pcap_time(1_664_967_845, 299_999));
+        assert!(date == pcap_time_nanos(1_664_967_845, 300_000_000));
+        assert!(date < pcap_time_nanos(1_664_967_845, 300_000_001));
+        // The 8 byte form is taken as UTC.
+        let local = DateAndTime::parse(&LOCAL).unwrap();
+        assert!(local > same);
+    }
+}
@@ -0,0 +1,304 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::parsers::tlv_def::{
+    apply_revisions, check_sizes, check_spec_versions, resolve_enums, DefinitionError, EnumTable,
+    TlvDef,
+};
+
+/// R-PHY TLV dictionary built into the binary, see `dictionaries/rphy.toml`.
+const RPHY_DICTIONARY: &str = include_str!("../../dictionaries/rphy.toml");
+
+// Dictionary as loaded, with the definitions of every version.
+static DICTIONARY: OnceLock<Dictionary> = OnceLock::new();
+
+// Definitions of the selected version, built on first use.
+static SELECTED: OnceLock<Dictionary> = OnceLock::new();
+
+// Index of the selected version in `SpecInfo::versions`.
+static SPEC_VERSION: OnceLock<usize> = OnceLock::new();
+
+/// Specification versions known to the dictionary, oldest first.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct SpecInfo {
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+/// TLV tree and shared enum tables used to decode the RCP TLVs.
+#[derive(Debug, Deserialize)]
+pub struct Dictionary {
+    #[serde(default)]
+    pub spec: SpecInfo,
+    #[serde(default)]
+    pub enums: BTreeMap<String, EnumTable>,
+    #[serde(default)]
+    pub tlv: Vec<TlvDef>,
+    /// Changes made by later specification versions, keyed by the version.
+    #[serde(default)]
+    pub version: BTreeMap<String, VersionChanges>,
+}
+
+/// Enum tables replaced from a specification version on.
+///
+/// ```toml
+/// [version.I14.enums.CoreMode]
+/// 1 = "Principal"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct VersionChanges {
+    #[serde(default)]
+    pub enums: BTreeMap<String, EnumTable>,
+}
+
+impl Dictionary {
+    /// Parses a dictionary and checks that the definitions of every version resolve.
+    pub fn from_toml(text: &str) -> Result<Self, DefinitionError> {
+        let dict: Dictionary = toml::from_str(text).map_err(DefinitionError::Toml)?;
+        let versions = &dict.spec.versions;
+        check_spec_versions(&dict.tlv, &dict.enums, versions)?;
+        for (version, changes) in dict.version.iter() {
+            if !versions.contains(version) {
+                return Err(DefinitionError::UnknownSpecVersion(version.clone()));
+            }
+            check_spec_versions(&[], &changes.enums, versions)?;
+        }
+        for i in 0..versions.len() {
+            dict.for_versions(&versions[..=i])?;
+        }
+
+        Ok(dict)
+    }
+
+    /// Definitions and enum tables of a version, given with the versions before it, oldest first.
+    /// Each definition or table is the one of the nearest version it was changed in.
+    pub fn for_versions(&self, versions: &[String]) -> Result<Dictionary, DefinitionError> {
+        let mut enums = self.enums.clone();
+        for changes in versions.iter().filter_map(|v| self.version.get(v)) {
+            enums.extend(changes.enums.clone());
+        }
+        let mut tlv = self.tlv.clone();
+        apply_revisions(&mut tlv, versions);
+        check_sizes(&tlv)?;
+        resolve_enums(&mut tlv, &enums)?;
+
+        Ok(Dictionary {
+            spec: self.spec.clone(),
+            enums,
+            tlv,
+            version: BTreeMap::new(),
+        })
+    }
+
+    /// Definition of a top level TLV.
+    pub fn find(&self, typ: u8) -> Option<&TlvDef> {
+        self.tlv.iter().find(|d| d.typ == typ)
+    }
+}
+
+fn loaded() -> &'static Dictionary {
+    DICTIONARY
+        .get_or_init(|| Dictionary::from_toml(RPHY_DICTIONARY).expect("built-in R-PHY dictionary"))
+}
+
+/// Dictionary in use, with the definitions of the selected version.
+/// Falls back to the built-in one if none was loaded.
+pub fn dictionary() -> &'static Dictionary {
+    SELECTED.get_or_init(|| {
+        loaded()
+            .for_versions(selected_versions())
+            .expect("versions checked when loading")
+    })
+}
+
+/// Specification versions up to the selected one, oldest first.
+pub fn selected_versions() -> &'static [String] {
+    let versions = &loaded().spec.versions;
+    match SPEC_VERSION.get() {
+        Some(i) => &versions[..=*i],
+        None => versions,
+    }
+}
+
+/// Replaces the built-in dictionary. Must be called before anything is decoded.
+pub fn load_dictionary(path: &Path) -> Result<(), DefinitionError> {
+    let text = std::fs::read_to_string(path).map_err(DefinitionError::Io)?;
+    let dict = Dictionary::from_toml(&text)?;
+
+    DICTIONARY
+        .set(dict)
+        .map_err(|_| DefinitionError::AlreadyLoaded)
+}
+
+/// Selects the specification version whose definitions are used. Defaults to the latest one.
+/// Must be called before anything is decoded.
+pub fn select_spec_version(version: &str) -> Result<(), DefinitionError> {
+    let index = loaded()
+        .spec
+        .versions
+        .iter()
+        .position(|v| v == version)
+        .ok_or_else(|| DefinitionError::UnknownSpecVersion(version.to_string()))?;
+
+    if SELECTED.get().is_some() {
+        return Err(DefinitionError::AlreadyLoaded);
+    }
+    SPEC_VERSION
+        .set(index)
+        .map_err(|_| DefinitionError::AlreadyLoaded)
+}
+
+/// Note for a TLV or enum value that is deprecated, or not yet defined, in the selected version.
+pub fn spec_note(since: Option<&str>, deprecated: Option<&str>) -> Option<String> {
+    let versions = &dictionary().spec.versions;
+    let selected = match SPEC_VERSION.get() {
+        Some(i) => *i,
+        None => versions.len().checked_sub(1)?,
+    };
+    let index = |v: &str| versions.iter().position(|x| x == v);
+
+    if let Some(since) = since {
+        if index(since).is_some_and(|i| i > selected) {
+            return Some(format!(
+                "Not defined in {}, since {since}",
+                versions[selected]
+            ));
+        }
+    }
+    match deprecated {
+        Some(d) if index(d).is_some_and(|i| i <= selected) => Some(format!("Deprecated since {d}")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::tlv_def::DataType;
+
+    const VERSIONED: &str = r#"
+[spec]
+versions = ["I01", "I06", "I10", "I14"]
+
+[enums.LinkState]
+1 = "up"
+2 = "down"
+
+[version.I14.enums.LinkState]
+1 = "up"
+2 = "down"
+3 = "testing"
+
+[[tlv]]
+type = 5
+name = "PortMode"
+data_type = "UnsignedByte"
+
+  [tlv.version.I14]
+  name = "Mode"
+  data_type = "Enum"
+  size = 1
+  values = { 1 = "normal", 2 = "standby" }
+
+[[tlv]]
+type = 6
+name = "Link"
+data_type = "Complex"
+
+  [[tlv.tlv]]
+  type = 1
+  name = "State"
+  data_type = "Enum"
+  size = 1
+  enum = "LinkState"
+
+  [[tlv.tlv]]
+  type = 2
+  name = "Rate"
+  data_type = "UnsignedShort"
+  units = "Mbps"
+
+    [tlv.tlv.version.I06]
+    data_type = "UnsignedInt"
+"#;
+
+    fn up_to(dict: &Dictionary, version: &str) -> Dictionary {
+        let versions = &dict.spec.versions;
+        let i = versions.iter().position(|v| v == version).unwrap();
+        dict.for_versions(&versions[..=i]).unwrap()
+    }
+
+    #[test]
+    fn decodes_with_the_definitions_of_the_selected_version() {
+        let dict = Dictionary::from_toml(VERSIONED).unwrap();
+
+        let i06 = up_to(&dict, "I06");
+        let mode = i06.find(5).unwrap();
+        assert_eq!(
+            (mode.name.as_str(), mode.data_type),
+            ("PortMode", DataType::UnsignedByte)
+        );
+        assert_eq!(mode.value(&[2]), "2");
+        let state = &i06.find(6).unwrap().sub[0];
+        assert_eq!(state.value(&[3]), "(3) - Reserved");
+
+        let i14 = up_to(&dict, "I14");
+        let mode = i14.find(5).unwrap();
+        assert_eq!(
+            (mode.name.as_str(), mode.data_type),
+            ("Mode", DataType::Enum)
+        );
+        assert_eq!(mode.value(&[2]), "(2) - standby");
+        let state = &i14.find(6).unwrap().sub[0];
+        assert_eq!(state.value(&[3]), "(3) - testing");
+        assert_eq!(i14.enums["LinkState"].len(), 3);
+    }
+
+    #[test]
+    fn falls_back_to_the_nearest_earlier_version() {
+        let dict = Dictionary::from_toml(VERSIONED).unwrap();
+        let rate = |version| up_to(&dict, version).find(6).unwrap().sub[1].clone();
+
+        assert_eq!(rate("I01").data_type, DataType::UnsignedShort);
+        assert_eq!(rate("I01").value(&[0, 10]), "10");
+        // Changed in I06, I10 and I14 keep it.
+        for version in ["I06", "I10", "I14"] {
+            assert_eq!(rate(version).data_type, DataType::UnsignedInt);
+            assert_eq!(rate(version).value(&[0, 0, 0, 10]), "10");
+            assert_eq!(rate(version).units.as_deref(), Some("Mbps"));
+        }
+        assert_eq!(up_to(&dict, "I10").find(5).unwrap().name, "PortMode");
+    }
+
+    #[test]
+    fn rejects_changes_of_unknown_versions_and_enums() {
+        let unknown_version = VERSIONED.replace("[tlv.version.I14]", "[tlv.version.I15]");
+        assert!(matches!(
+            Dictionary::from_toml(&unknown_version),
+            Err(DefinitionError::UnknownSpecVersion(v)) if v == "I15"
+        ));
+        let unknown_tables = VERSIONED.replace("[version.I14.enums", "[version.I15.enums");
+        assert!(matches!(
+            Dictionary::from_toml(&unknown_tables),
+            Err(DefinitionError::UnknownSpecVersion(v)) if v == "I15"
+        ));
+        let unknown_enum = VERSIONED.replace(
+            "    [tlv.tlv.version.I06]\n    data_type = \"UnsignedInt\"",
+            "    [tlv.tlv.version.I06]\n    enum = \"RateType\"",
+        );
+        assert!(matches!(
+            Dictionary::from_toml(&unknown_enum),
+            Err(DefinitionError::UnknownEnum(name)) if name == "RateType"
+        ));
+    }
+
+    #[test]
+    fn builtin_dictionary_resolves_for_every_version() {
+        let dict = Dictionary::from_toml(RPHY_DICTIONARY).unwrap();
+        assert!(dict.spec.versions.len() > 1);
+        let first = up_to(&dict, &dict.spec.versions[0]);
+        assert!(first.find(50).is_some());
+    }
+}
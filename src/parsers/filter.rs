@@ -0,0 +1,233 @@
+use chrono::DateTime;
+use nom::multi::many0;
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::ipv4::Ipv4Packet;
+use pnet::packet::ipv6::Ipv6Packet;
+use pnet::packet::tcp::TcpPacket;
+use pnet::packet::Packet;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+use crate::parsers::dictionary::dictionary;
+use crate::parsers::gcp_parser::{rcp_offset, MessageId};
+use crate::parsers::l2tp_parser::{l2tp_packet, L2tpTransport};
+use crate::parsers::ptp_parser::ptp_packet;
+use crate::parsers::tlv_def::{DataType, TlvDef};
+use crate::parsers::tlv_parser::parse_tlvs;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterError {
+    UnknownMessage(String),
+    InvalidTime(String),
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterError::UnknownMessage(m) => write!(f, "unknown GCP message '{m}'"),
+            FilterError::InvalidTime(t) => {
+                write!(
+                    f,
+                    "invalid time '{t}', expected RFC 3339 or seconds since epoch"
+                )
+            }
+        }
+    }
+}
+
+/// A TLV to look for: its name at any depth, or the type of a TLV directly in a sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlvSelector {
+    Name(String),
+    Type(u8),
+}
+
+impl TlvSelector {
+    pub fn parse(text: &str) -> Self {
+        match text.parse() {
+            Ok(typ) => TlvSelector::Type(typ),
+            Err(_) => TlvSelector::Name(text.to_string()),
+        }
+    }
+}
+
+/// Parses `2022-10-05T13:04:05Z` or `1665000000.5` into microseconds since epoch.
+pub fn parse_time(text: &str) -> Result<i64, FilterError> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Ok(date.timestamp_micros());
+    }
+
+    text.parse::<f64>()
+        .map(|secs| (secs * 1_000_000.0).round() as i64)
+        .map_err(|_| FilterError::InvalidTime(text.to_string()))
+}
+
+/// Frame selection. Criteria of different kinds must all match, values of the same kind
+/// are alternatives. An empty filter matches every frame.
+#[derive(Debug, Clone, Default)]
+pub struct FrameFilter {
+    pub messages: Vec<MessageId>,
+    pub tlvs: Vec<TlvSelector>,
+    /// Address of the RPD, or any end of the connection.
+    pub rpds: Vec<IpAddr>,
+    /// TCP port of either end.
+    pub ports: Vec<u16>,
+    /// Capture time range in microseconds since epoch, both ends included.
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+/// What the filter looks at in a frame.
+#[derive(Debug, Default)]
+struct FrameContent {
+    addresses: Vec<IpAddr>,
+    ports: Vec<u16>,
+    messages: Vec<MessageId>,
+    tlv_types: Vec<u8>,
+    tlv_names: Vec<String>,
+}
+
+impl FrameFilter {
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+            && self.tlvs.is_empty()
+            && self.rpds.is_empty()
+            && self.ports.is_empty()
+            && self.from.is_none()
+            && self.to.is_none()
+    }
+
+    /// `time` is the capture time in microseconds since epoch and `messages` the GCP
+    /// messages the frame completes, with the bytes of the previous segments they started in.
+    pub(crate) fn matches(&self, time: i64, frame: Option<&IpFrame>, messages: &[&[u8]]) -> bool {
+        if self.from.is_some_and(|from| time < from) || self.to.is_some_and(|to| time > to) {
+            return false;
+        }
+        if self.messages.is_empty()
+            && self.tlvs.is_empty()
+            && self.rpds.is_empty()
+            && self.ports.is_empty()
+        {
+            return true;
+        }
+
+        let content = frame_content(frame, messages);
+        let rpd_ok =
+            self.rpds.is_empty() || self.rpds.iter().any(|ip| content.addresses.contains(ip));
+        let port_ok = self.ports.is_empty() || self.ports.iter().any(|p| content.ports.contains(p));
+        let message_ok =
+            self.messages.is_empty() || self.messages.iter().any(|m| content.messages.contains(m));
+        let tlv_ok = self.tlvs.is_empty()
+            || self.tlvs.iter().any(|t| match t {
+                TlvSelector::Type(typ) => content.tlv_types.contains(typ),
+                TlvSelector::Name(name) => content.tlv_names.contains(name),
+            });
+
+        rpd_ok && port_ok && message_ok && tlv_ok
+    }
+}
+
+/// A frame from its IP header on.
+pub(crate) struct IpFrame {
+    pub addresses: [IpAddr; 2],
+    /// Source and destination ports, for TCP.
+    pub ports: Option<[u16; 2]>,
+    /// Sequence number, for TCP.
+    pub seq: Option<u32>,
+    /// TCP payload, empty for other protocols.
+    pub payload: Vec<u8>,
+    /// L2TPv3 packet, over IP or UDP, and how it is carried.
+    pub l2tp: Option<(L2tpTransport, Vec<u8>)>,
+    /// PTP message over UDP.
+    pub ptp: Option<Vec<u8>>,
+}
+
+impl IpFrame {
+    /// Source and destination of a TCP segment.
+    pub fn sockets(&self) -> Option<[SocketAddr; 2]> {
+        let ports = self.ports?;
+        Some([
+            SocketAddr::new(self.addresses[0], ports[0]),
+            SocketAddr::new(self.addresses[1], ports[1]),
+        ])
+    }
+}
+
+pub(crate) fn ip_frame(data: &[u8]) -> Option<IpFrame> {
+    let (addresses, protocol, payload): ([IpAddr; 2], _, _) = match data.first().map(|b| b >> 4) {
+        Some(4) => {
+            let ip = Ipv4Packet::new(data)?;
+            (
+                [ip.get_source().into(), ip.get_destination().into()],
+                ip.get_next_level_protocol(),
+                ip.payload().to_vec(),
+            )
+        }
+        Some(6) => {
+            let ip = Ipv6Packet::new(data)?;
+            (
+                [ip.get_source().into(), ip.get_destination().into()],
+                ip.get_next_header(),
+                ip.payload().to_vec(),
+            )
+        }
+        _ => return None,
+    };
+
+    let l2tp = l2tp_packet(protocol, &payload).map(|(t, packet)| (t, packet.to_vec()));
+    let ptp = ptp_packet(protocol, &payload).map(<[u8]>::to_vec);
+    let tcp = match protocol {
+        IpNextHeaderProtocols::Tcp => TcpPacket::new(&payload),
+        _ => None,
+    };
+    Some(IpFrame {
+        addresses,
+        ports: tcp.as_ref().map(|t| [t.get_source(), t.get_destination()]),
+        seq: tcp.as_ref().map(TcpPacket::get_sequence),
+        payload: tcp.map(|t| t.payload().to_vec()).unwrap_or_default(),
+        l2tp,
+        ptp,
+    })
+}
+
+fn frame_content(frame: Option<&IpFrame>, messages: &[&[u8]]) -> FrameContent {
+    let mut content = FrameContent::default();
+
+    let frame = match frame {
+        Some(frame) => frame,
+        None => return content,
+    };
+    content.addresses.extend(frame.addresses);
+    content.ports.extend(frame.ports.into_iter().flatten());
+
+    for msg in messages {
+        let message_id = MessageId(msg[7]);
+        content.messages.push(message_id);
+
+        let body = &msg[10..];
+        if let Some(rcp) = rcp_offset(message_id, body).and_then(|off| body.get(off + 3..)) {
+            let (_, sequences) = many0(parse_tlvs)(rcp).unwrap_or((&[], Vec::new()));
+            for seq in sequences {
+                let (_, tlvs) = many0(parse_tlvs)(seq.val).unwrap_or((&[], Vec::new()));
+                content.tlv_types.extend(tlvs.iter().map(|t| t.typ));
+                collect_names(&dictionary().tlv, seq.val, &mut content.tlv_names);
+            }
+        }
+    }
+
+    content
+}
+
+/// Names of the TLVs in `input` and, for Complex TLVs, of their sub-TLVs.
+fn collect_names(defs: &[TlvDef], input: &[u8], names: &mut Vec<String>) {
+    let (_, tlvs) = many0(parse_tlvs)(input).unwrap_or((&[], Vec::new()));
+
+    for t in tlvs.iter() {
+        if let Some(def) = defs.iter().find(|d| d.typ == t.typ) {
+            names.push(def.name.clone());
+            if def.data_type == DataType::Complex {
+                collect_names(&def.sub, t.val, names);
+            }
+        }
+    }
+}
@@ -1,15 +1,20 @@
 extern crate nom;
 
 use pcap_parser::data::{get_packetdata, PacketData};
-use pcap_parser::traits::PcapReaderIterator;
-use pcap_parser::{LegacyPcapReader, Linktype, PcapBlockOwned, PcapError};
+use pcap_parser::{LegacyPcapBlock, Linktype, PcapBlockOwned, PcapHeader, ToVec};
 
 // use std::net::{Ipv4Addr, Ipv6Addr};
+use hex_fmt::HexFmt;
 use std::fmt;
+use std::io::{self, Read, Write};
 use std::net::IpAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::{fs::File, path::Path};
 
-use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::packet::ethernet::EthernetPacket;
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
+use pnet::packet::ipv4::Ipv4Packet;
 use pnet::packet::ipv6::Ipv6Packet;
 use pnet::packet::tcp::TcpPacket;
 use pnet::packet::Packet;
@@ -19,9 +24,25 @@ use nom::IResult;
 
 // use nom::number::streaming::{be_u16, be_u8};
 
+use crate::parsers::byte_input::Layer;
+use crate::parsers::date_and_time::{pcap_time, pcap_time_nanos};
+use crate::parsers::filter::{ip_frame, FrameFilter};
+use crate::parsers::hex_view::{self, hex_view_enabled};
+use crate::parsers::l2tp_parser::{l2tp_packet, parse_l2tp, print_l2tp, L2tpTransport};
+use crate::parsers::pcap_reader::read_pcap;
+use crate::parsers::ptp_parser::{parse_ptp, print_ptp, ptp_packet, PtpTransport, PTP_ETHERTYPE};
 use crate::parsers::rcp_parser::parse_rcp;
-
-const SNAP_LEN: usize = 65536;
+use crate::parsers::reassembly::Reassembly;
+use crate::parsers::summary::Summary;
+use crate::parsers::tlv_parser::parse_rphy_tlvs;
+use crate::parsers::vendor::{
+    is_vendor_registered, parse_vendor_tlvs, vendor_label, CABLELABS_VENDOR_ID,
+};
+
+/// GCP header, up to the Message Length included.
+const GCP_HEADER_LEN: usize = 10;
+const ETHERNET_HEADER_LEN: usize = 14;
+const IPV6_HEADER_LEN: usize = 40;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct GcpHeader {
@@ -58,6 +79,42 @@ impl MessageId {
     pub const Rsp_MWR_Error: MessageId = MessageId(147);
 }
 
+// Names used on the command line and in craft descriptions.
+const MESSAGE_NAMES: &[(&str, MessageId)] = &[
+    ("NotifyRequest", MessageId::Req_Notify),
+    ("NotifyResponse", MessageId::Rsp_Notify),
+    ("NotifyError", MessageId::Rsp_Notify_Error),
+    ("DmRequest", MessageId::Req_DM),
+    ("DmResponse", MessageId::Rsp_DM),
+    ("DmError", MessageId::Rsp_DM_Error),
+    ("EdsRequest", MessageId::Req_EDS),
+    ("EdsResponse", MessageId::Rsp_EDS),
+    ("EdsError", MessageId::Rsp_EDS_Error),
+    ("EdrRequest", MessageId::Req_EDR),
+    ("EdrResponse", MessageId::Rsp_EDR),
+    ("EdrError", MessageId::Rsp_EDR_Error),
+    ("MwrRequest", MessageId::Req_MWR),
+    ("MwrResponse", MessageId::Rsp_MWR),
+    ("MwrError", MessageId::Rsp_MWR_Error),
+];
+
+/// Parses a message name such as `EdsRequest`, or the message id number.
+impl FromStr for MessageId {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(n) = s.parse() {
+            return Ok(MessageId(n));
+        }
+
+        MESSAGE_NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|(_, id)| *id)
+            .ok_or(())
+    }
+}
+
 impl fmt::Debug for MessageId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.0 {
@@ -132,7 +189,7 @@ impl fmt::Display for ReturnCode {
     }
 }
 
-fn header(input: &[u8]) -> IResult<&[u8], GcpHeader> {
+pub(crate) fn header(input: &[u8]) -> IResult<&[u8], GcpHeader> {
     // Parse the header
     let (i, transaction_identifier) = be_u16(input)?;
     let (i, protocol_identifier) = be_u16(i)?;
@@ -151,125 +208,489 @@ fn header(input: &[u8]) -> IResult<&[u8], GcpHeader> {
     };
     Ok((i, header))
 }
-pub fn parser(filename: &str) {
+/// Splits a TCP payload into whole GCP messages, header included.
+/// Also returns the bytes left, e.g. a message continued in the next segment.
+pub fn split_messages(payload: &[u8]) -> (Vec<&[u8]>, &[u8]) {
+    let mut messages = Vec::new();
+    let mut rest = payload;
+
+    while rest.len() >= 10 {
+        // The header length counts from the Unit ID, 6 bytes in.
+        let total = 6 + usize::from(u16::from_be_bytes([rest[4], rest[5]]));
+        if total < 10 || rest.len() < total {
+            break;
+        }
+        messages.push(&rest[..total]);
+        rest = &rest[total..];
+    }
+
+    (messages, rest)
+}
+
+/// Offset of the RCP data in a message body, for the messages carrying one.
+pub fn rcp_offset(message_id: MessageId, body: &[u8]) -> Option<usize> {
+    match message_id {
+        MessageId::Req_Notify if body.len() > 8 => Some(8),
+        MessageId::Req_EDS | MessageId::Rsp_EDS if body.len() > 12 => {
+            let vendor_id = u32::from_be_bytes(body[7..11].try_into().unwrap());
+            (vendor_id == CABLELABS_VENDOR_ID).then_some(12)
+        }
+        _ => None,
+    }
+}
+
+/// What to do with the frames of a capture besides decoding them.
+#[derive(Debug, Default)]
+pub struct ParseOptions {
+    pub filter: FrameFilter,
+    /// Capture to copy the matching frames to.
+    pub write: Option<PathBuf>,
+    /// Keep reading the file as it grows instead of stopping at its end.
+    pub follow: bool,
+    /// Print totals at the end instead of decoding every frame.
+    pub summary: bool,
+}
+
+/// Decodes the capture in `filename`, or in stdin for `-`.
+pub fn parser(filename: &str, options: &ParseOptions) {
+    if filename == "-" {
+        // A pipe already waits for the writer, there is nothing to follow.
+        return pcap_parser(io::stdin(), options, false);
+    }
+
     let path = Path::new(filename);
     let pfilename = File::open(path);
 
     // Handles File IO
     match pfilename {
         // Ok(_) => println!("Hey lets run this mother"),
-        Ok(_) => pcap_parser(pfilename.unwrap()),
+        Ok(_) => pcap_parser(pfilename.unwrap(), options, options.follow),
         Err(e) => eprintln!("error: opening pcap file: '{}'. {}", path.display(), e),
     };
 }
 
-fn pcap_parser(pcap_filename: File) {
-    let mut num_blocks = 0;
-    let mut reader = LegacyPcapReader::new(SNAP_LEN, pcap_filename).expect("LegacyPcapReader");
-
-    loop {
-        match reader.next() {
-            // NOTE: block counter num_block counts pcap file header
-            Ok((offset, block)) => {
-                // num_blocks += 1;
-                let packetdata = match block {
-                    //
-                    PcapBlockOwned::LegacyHeader(hdr) => {
-                        println!("{}", "*".repeat(50));
-                        println!("* PCAP File header:");
-                        println!("* Magic Number: {}", hdr.magic_number);
-                        println!("* Version: {}.{}", hdr.version_major, hdr.version_minor);
-                        println!("* Time Zone: {}", hdr.thiszone);
-                        println!("* Time Stamp Accuracy: {}", hdr.sigfigs);
-                        println!("* Max len capture packet size: {}", hdr.snaplen);
-                        println!("* Linktype {}", hdr.network);
-                        println!("{}", "*".repeat(50));
-                        reader.consume(offset);
-                        continue;
-                    }
-                    PcapBlockOwned::Legacy(b) => {
-                        //
-                        num_blocks += 1;
-                        print!("Frame: {}, ", num_blocks);
-                        print!("Arrival: {}.{}, ", b.ts_sec, b.ts_usec);
-                        // println!(", Data: {:X?}", &b.data);
-                        let blem = b.caplen as usize;
-                        get_packetdata(b.data, Linktype::ETHERNET, blem)
-                            .expect("Error parsing this packet")
+/// Why bytes given with [`decode_bytes`] do not decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The bytes end inside a message.
+    Truncated(Layer),
+    /// A field of the message does not decode.
+    Malformed(Layer, nom::error::ErrorKind),
+}
+
+impl DecodeError {
+    fn from_nom(layer: Layer, e: nom::Err<nom::error::Error<&[u8]>>) -> Self {
+        match e {
+            nom::Err::Incomplete(_) => DecodeError::Truncated(layer),
+            nom::Err::Error(e) | nom::Err::Failure(e) if e.code == nom::error::ErrorKind::Eof => {
+                DecodeError::Truncated(layer)
+            }
+            nom::Err::Error(e) | nom::Err::Failure(e) => DecodeError::Malformed(layer, e.code),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Truncated(layer) => write!(f, "truncated {layer} message"),
+            DecodeError::Malformed(layer, kind) => {
+                write!(f, "malformed {layer} message: {}", kind.description())
+            }
+        }
+    }
+}
+
+/// Decodes bytes taken out of a pcap, e.g. from a hex dump, starting at `layer`.
+pub fn decode_bytes(data: &[u8], layer: Layer) -> Result<(), DecodeError> {
+    match layer {
+        Layer::Gcp => {
+            let (messages, rest) = split_messages(data);
+            let mut offset = 0;
+            for msg in messages {
+                let (i, hdr) = header(msg).map_err(|e| DecodeError::from_nom(layer, e))?;
+                print_header(&hdr, msg, Some(offset));
+                message_decoder(i, Some(offset + GCP_HEADER_LEN), hdr.message_id)
+                    .map_err(|e| DecodeError::from_nom(layer, e))?;
+                offset += msg.len();
+            }
+            if !rest.is_empty() {
+                return Err(DecodeError::Truncated(layer));
+            }
+        }
+        Layer::Rcp => {
+            parse_rcp(data, Some(0), 2).map_err(|e| DecodeError::from_nom(layer, e))?;
+        }
+        Layer::Tlv => parse_rphy_tlvs(data, Some(0), 2),
+    }
+    Ok(())
+}
+
+fn create_output(path: &Path) -> File {
+    match File::create(path) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("error: creating pcap file: '{}'. {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn pcap_parser<R: Read>(input: R, options: &ParseOptions, follow: bool) {
+    let mut handler = FrameHandler::new(options);
+
+    let read = read_pcap(input, follow, |block| match block {
+        //
+        PcapBlockOwned::LegacyHeader(hdr) => {
+            println!("{}", "*".repeat(50));
+            println!("* PCAP File header:");
+            println!("* Magic Number: {}", hdr.magic_number);
+            println!("* Version: {}.{}", hdr.version_major, hdr.version_minor);
+            println!("* Time Zone: {}", hdr.thiszone);
+            println!("* Time Stamp Accuracy: {}", hdr.sigfigs);
+            println!("* Max len capture packet size: {}", hdr.snaplen);
+            println!("* Linktype {}", hdr.network);
+            println!("{}", "*".repeat(50));
+            handler.header(hdr);
+        }
+        PcapBlockOwned::Legacy(b) => handler.frame(&b),
+        PcapBlockOwned::NG(_) => unreachable!(),
+    });
+    if let Err(e) = read {
+        eprintln!("error: reading pcap file. {}", e);
+        std::process::exit(1);
+    }
+    handler.finish();
+}
+
+/// Filters, decodes and exports the frames of a capture, whatever its source.
+pub(crate) struct FrameHandler<'a> {
+    options: &'a ParseOptions,
+    output: Option<File>,
+    summary: Option<Summary>,
+    linktype: Linktype,
+    nanosecond: bool,
+    reassembly: Reassembly,
+    num_blocks: usize,
+}
+
+/// The GCP messages a TCP segment completes and the bytes it leaves.
+struct GcpSegment<'a> {
+    messages: Vec<&'a [u8]>,
+    rest: &'a [u8],
+    /// `rest` starts a message continued in the next segment.
+    continued: bool,
+    /// Bytes of earlier segments the first message starts with.
+    carried: usize,
+}
+
+impl<'a> FrameHandler<'a> {
+    pub(crate) fn new(options: &'a ParseOptions) -> Self {
+        FrameHandler {
+            options,
+            output: options.write.as_deref().map(create_output),
+            summary: options.summary.then(Summary::default),
+            linktype: Linktype::ETHERNET,
+            nanosecond: false,
+            reassembly: Reassembly::default(),
+            num_blocks: 0,
+        }
+    }
+
+    /// Takes the link type and timestamp precision of the capture and starts the exported capture, if any,
+    /// with the header of the input.
+    pub(crate) fn header(&mut self, mut hdr: PcapHeader) {
+        self.linktype = hdr.network;
+        self.nanosecond = hdr.is_nanosecond_precision();
+        if let Some(out) = self.output.as_mut() {
+            // Records are written little-endian.
+            if hdr.is_bigendian() {
+                hdr.magic_number = hdr.magic_number.swap_bytes();
+            }
+            write_output(out, &hdr.to_vec_raw().unwrap());
+        }
+    }
+
+    /// `block` holds a frame of the link type given by the capture header.
+    pub(crate) fn frame(&mut self, block: &LegacyPcapBlock) {
+        self.num_blocks += 1;
+        // println!(", Data: {:X?}", &b.data);
+        let blem = (block.caplen as usize).min(block.data.len());
+        let packetdata = get_packetdata(block.data, self.linktype, blem);
+
+        // Network layer data, with the Ethernet frame for PTP over Ethernet.
+        let (data, ethertype, ethernet) = match packetdata {
+            Some(PacketData::L2(data)) => (
+                data.get(14..).unwrap_or_default(),
+                data.get(12..14).map(|t| u16::from_be_bytes([t[0], t[1]])),
+                Some(data),
+            ),
+            Some(PacketData::L3(ethertype, data)) => (data, Some(ethertype), None),
+            _ => {
+                println!(
+                    "Frame: {}, Unsupported link type {}",
+                    self.num_blocks, self.linktype
+                );
+                return;
+            }
+        };
+
+        // Offsets of the hex view count from the start of the frame.
+        let offset = hex_view::offset_in(block.data, data);
+        let time = match self.nanosecond {
+            true => pcap_time_nanos(block.ts_sec, block.ts_usec),
+            false => pcap_time(block.ts_sec, block.ts_usec),
+        };
+        // A GCP message split over segments is complete with the last of them.
+        let ip = ip_frame(data);
+        let mut stream = Vec::new();
+        let mut continued = false;
+        let mut carried = 0;
+        if let Some((sockets, seq, payload)) = ip
+            .as_ref()
+            .and_then(|ip| Some((ip.sockets()?, ip.seq?, &ip.payload)))
+        {
+            stream = self.reassembly.stream(sockets, seq, payload);
+            carried = stream.len() - payload.len();
+            let next = seq.wrapping_add(payload.len() as u32);
+            continued = self
+                .reassembly
+                .keep(sockets, next, split_messages(&stream).1);
+        }
+        let (messages, rest) = split_messages(&stream);
+        let gcp = GcpSegment {
+            messages,
+            rest,
+            continued,
+            carried,
+        };
+
+        if self
+            .options
+            .filter
+            .matches(time.timestamp_micros(), ip.as_ref(), &gcp.messages)
+        {
+            match self.summary.as_mut() {
+                Some(summary) => summary.add(self.num_blocks, &gcp.messages),
+                None => {
+                    print!("Frame: {}, ", self.num_blocks);
+                    print!("Arrival: {}.{}, ", block.ts_sec, block.ts_usec);
+                    match (ethertype, ethernet) {
+                        (Some(PTP_ETHERTYPE), Some(frame)) => decode_ptp(frame),
+                        (Some(PTP_ETHERTYPE), None) => {
+                            print_ptp_packet(PtpTransport::Ethernet, data, offset)
+                        }
+                        _ => decode_packet_data(data, offset, &gcp),
                     }
-                    PcapBlockOwned::NG(_) => unreachable!(),
-                };
-
-                let data = match packetdata {
-                    PacketData::L2(data) => &data[14..],
-                    PacketData::L3(_, data) => data,
-                    _ => panic!("Unsupported packet data type"),
-                };
-                decode_packet_data(data);
-                reader.consume(offset);
+                }
             }
-            Err(PcapError::Eof) => break,
-            Err(PcapError::Incomplete) => {
-                reader.refill().unwrap();
+            if let Some(out) = self.output.as_mut() {
+                // The original record, link header and timestamps included.
+                write_output(out, &block.to_vec_raw().unwrap());
             }
-            Err(e) => panic!("error while reading: {:?}", e),
         }
     }
-    // println!("num_blocks: {}", num_blocks);
+
+    /// Called at the end of the capture.
+    pub(crate) fn finish(self) {
+        if let Some(summary) = self.summary {
+            summary.print();
+        }
+    }
 }
 
-fn decode_packet_data(data: &[u8]) {
+fn write_output(out: &mut File, bytes: &[u8]) {
+    if let Err(e) = out.write_all(bytes) {
+        eprintln!("error: writing pcap file. {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// `offset` is the offset of `data`, the IP packet, within the frame.
+fn decode_packet_data(data: &[u8], offset: Option<usize>, gcp: &GcpSegment) {
     if data.is_empty() {
         return;
     }
     // check L3
     match data[0] & 0xf0 {
         // IPv4
-        // 0x40 => {ipv4}
+        0x40 => match Ipv4Packet::new(data) {
+            Some(ipv4) => {
+                let src = IpAddr::V4(ipv4.get_source());
+                let dst = IpAddr::V4(ipv4.get_destination());
+                print!("sIP: {}, dIP: {}, ", src, dst);
+
+                let offset = offset.map(|o| o + usize::from(ipv4.get_header_length()) * 4);
+                decode_ip_payload(ipv4.get_next_level_protocol(), ipv4.payload(), offset, gcp);
+            }
+            None => println!("Malformed IPv4 packet, Size: {}", data.len()),
+        },
         // IPv6
-        0x60 => {
-            //
-            let ipv6 = &Ipv6Packet::new(data).unwrap();
-
-            // println!("next level proto: {:?}", ipv6.get_next_header());
-            let src = IpAddr::V6(ipv6.get_source());
-            let dst = IpAddr::V6(ipv6.get_destination());
-            print!("sIP: {}, dIP: {}, ", src, dst);
-
-            match ipv6.get_next_header() {
-                IpNextHeaderProtocols::Tcp => {
-                    if let Some(tcp) = TcpPacket::new(ipv6.payload()) {
-                        //parse tcp
-                        println!(
-                            "sPort: {}, sPort: {}, Size: {}",
-                            tcp.get_source(),
-                            tcp.get_destination(),
-                            tcp.payload().len(),
-                        );
-
-                        let (i, hdr) = header(tcp.payload()).unwrap();
-                        println!("{}{:?}", " ".repeat(2), hdr);
-
-                        let (_data, _is_done) = message_decoder(i, hdr.message_id).unwrap();
-                    }
-                }
-                IpNextHeaderProtocols::Udp => {
-                    println!("UDP is currently not supported");
-                }
-                _ => (),
+        0x60 => match Ipv6Packet::new(data) {
+            Some(ipv6) => {
+                let src = IpAddr::V6(ipv6.get_source());
+                let dst = IpAddr::V6(ipv6.get_destination());
+                print!("sIP: {}, dIP: {}, ", src, dst);
+
+                let offset = offset.map(|o| o + IPV6_HEADER_LEN);
+                decode_ip_payload(ipv6.get_next_header(), ipv6.payload(), offset, gcp);
             }
-        }
+            None => println!("Malformed IPv6 packet, Size: {}", data.len()),
+        },
         _ => {
             println!("Unknown layer 3 protocol");
         }
     }
 }
 
-fn message_decoder(input: &[u8], msg_type: MessageId) -> IResult<&[u8], bool> {
+/// PTP directly over Ethernet.
+fn decode_ptp(frame: &[u8]) {
+    if let Some(ethernet) = EthernetPacket::new(frame) {
+        print!(
+            "sMAC: {}, dMAC: {}, ",
+            ethernet.get_source(),
+            ethernet.get_destination()
+        );
+        print_ptp_packet(
+            PtpTransport::Ethernet,
+            ethernet.payload(),
+            Some(ETHERNET_HEADER_LEN),
+        );
+    }
+}
+
+fn print_ptp_packet(transport: PtpTransport, packet: &[u8], offset: Option<usize>) {
+    println!("PTP over {}, Size: {}", transport, packet.len());
+    match parse_ptp(packet) {
+        Ok((_, message)) => print_ptp(&message, offset, 2),
+        Err(_) => println!("  Malformed PTP message"),
+    }
+}
+
+/// `offset` is the offset of `payload` within the frame.
+fn decode_ip_payload(
+    protocol: IpNextHeaderProtocol,
+    payload: &[u8],
+    offset: Option<usize>,
+    gcp: &GcpSegment,
+) {
+    // Offset of a packet carried in the payload, e.g. past a UDP header.
+    let offset_of = |packet: &[u8]| Some(offset? + hex_view::offset_in(payload, packet)?);
+
+    if let Some((transport, packet)) = l2tp_packet(protocol, payload) {
+        println!("L2TPv3 over {}, Size: {}", transport, packet.len());
+        // Over IP, the control header follows a zero Session ID.
+        let header_offset = match transport {
+            L2tpTransport::Ip => offset_of(packet).map(|o| o + 4),
+            L2tpTransport::Udp => offset_of(packet),
+        };
+        match parse_l2tp(packet, transport) {
+            Ok((_, message)) => print_l2tp(&message, header_offset, 2),
+            Err(_) => println!("  Malformed L2TPv3 packet"),
+        }
+        return;
+    }
+    if let Some(packet) = ptp_packet(protocol, payload) {
+        print_ptp_packet(PtpTransport::Udp, packet, offset_of(packet));
+        return;
+    }
+
+    match protocol {
+        IpNextHeaderProtocols::Tcp => {
+            if let Some(tcp) = TcpPacket::new(payload) {
+                //parse tcp
+                println!(
+                    "sPort: {}, sPort: {}, Size: {}",
+                    tcp.get_source(),
+                    tcp.get_destination(),
+                    tcp.payload().len(),
+                );
+
+                let offset = offset.map(|o| o + usize::from(tcp.get_data_offset()) * 4);
+                decode_gcp(gcp, offset);
+            }
+        }
+        IpNextHeaderProtocols::Udp => {
+            println!("UDP is currently not supported");
+        }
+        _ => (),
+    }
+}
+
+/// Decodes the GCP messages a TCP segment completes. A message continued in the next
+/// segment is decoded with the segment completing it. `offset` is the offset of the TCP
+/// payload within the frame, if known.
+fn decode_gcp(gcp: &GcpSegment, offset: Option<usize>) {
+    let mut start = 0;
+    for &msg in &gcp.messages {
+        // Whole messages start with a whole header.
+        let (i, hdr) = header(msg).expect("GCP header");
+        // A message started in an earlier segment is not all in the frame.
+        let offset = offset.filter(|_| start >= gcp.carried);
+        let offset = offset.map(|o| o + start - gcp.carried);
+        start += msg.len();
+        print_header(&hdr, msg, offset);
+        if message_decoder(i, offset.map(|o| o + GCP_HEADER_LEN), hdr.message_id).is_err() {
+            println!(
+                "    Malformed {:?} message: {:X}",
+                hdr.message_id,
+                HexFmt(i)
+            );
+        }
+    }
+    if gcp.continued {
+        println!(
+            "  {} bytes of a GCP message continued in the next segment",
+            gcp.rest.len()
+        );
+    } else if !gcp.rest.is_empty() {
+        println!("  {} bytes left, not a whole GCP message", gcp.rest.len());
+    }
+}
+
+/// Prints the GCP header, field by field with their bytes in the hex view.
+/// `offset` is the offset of the message within the frame, if known.
+fn print_header(hdr: &GcpHeader, raw: &[u8], offset: Option<usize>) {
+    if !hex_view_enabled() {
+        println!("{}{:?}", " ".repeat(2), hdr);
+        return;
+    }
+
+    let hex =
+        |start: usize, end: usize| hex_view::field(offset.map(|o| o + start), &raw[start..end]);
+    let m = " ".repeat(4);
+    println!("  GCP Header:");
+    println!(
+        "{m}Transaction Identifier: {}{}",
+        hdr.transaction_identifier,
+        hex(0, 2)
+    );
+    println!(
+        "{m}Protocol Identifier: {}{}",
+        hdr.protocol_identifier,
+        hex(2, 4)
+    );
+    println!("{m}Length: {}{}", hdr.length, hex(4, 6));
+    println!("{m}Unit Identifier: {}{}", hdr.unit_identifier, hex(6, 7));
+    println!("{m}Message ID: {:?}{}", hdr.message_id, hex(7, 8));
+    println!("{m}Message Length: {}{}", hdr.message_length, hex(8, 10));
+}
+
+/// Decodes a message body. `offset` is the offset of `input` within the frame, if known.
+fn message_decoder(
+    input: &[u8],
+    offset: Option<usize>,
+    msg_type: MessageId,
+) -> IResult<&[u8], bool> {
     //
     let mut ret = false;
     let (i, transaction_id) = be_u16(input)?;
     let margin = 4;
+    // Raw bytes of the field at `start..end` of the message body, for the hex view.
+    let hex =
+        |start: usize, end: usize| hex_view::field(offset.map(|o| o + start), &input[start..end]);
+    // Offset of the rest `i` of the body.
+    let offset_of = |i: &[u8]| offset.map(|o| o + input.len() - i.len());
 
     match msg_type {
         //
@@ -281,9 +702,10 @@ fn message_decoder(input: &[u8], msg_type: MessageId) -> IResult<&[u8], bool> {
             //prints
             println!("{}(2) Request Notify:", " ".repeat(margin));
             println!(
-                "{}Transaction ID: {}",
+                "{}Transaction ID: {}{}",
                 " ".repeat(margin + 4),
-                transaction_id
+                transaction_id,
+                hex(0, 2)
             );
 
             // TODO - Do this with an impl instead
@@ -302,13 +724,29 @@ fn message_decoder(input: &[u8], msg_type: MessageId) -> IResult<&[u8], bool> {
             } else {
                 bstr.push_str(", bit 6=0 : Event data is text");
             }
-            println!("{}Mode: {:b} ({})", " ".repeat(margin + 4), mode, bstr);
+            println!(
+                "{}Mode: {:b} ({}){}",
+                " ".repeat(margin + 4),
+                mode,
+                bstr,
+                hex(2, 3)
+            );
 
-            println!("{}Status: {:?}", " ".repeat(margin + 4), Status(status));
-            println!("{}Event Code: {:#08X}", " ".repeat(margin + 4), event_code);
+            println!(
+                "{}Status: {:?}{}",
+                " ".repeat(margin + 4),
+                Status(status),
+                hex(3, 4)
+            );
+            println!(
+                "{}Event Code: {:#08X}{}",
+                " ".repeat(margin + 4),
+                event_code,
+                hex(4, 8)
+            );
 
             //decode rcp
-            let (_rem, _is_done) = parse_rcp(i, margin + 4).unwrap();
+            let (_rem, _is_done) = parse_rcp(i, offset_of(i), margin + 4)?;
         }
         MessageId::Rsp_Notify => {
             println!("{}(3) Response Notify:", " ".repeat(margin));
@@ -316,20 +754,27 @@ fn message_decoder(input: &[u8], msg_type: MessageId) -> IResult<&[u8], bool> {
             let (_i, event_code) = be_u32(i)?;
 
             println!(
-                "{}Transaction ID: {}",
+                "{}Transaction ID: {}{}",
+                " ".repeat(margin + 4),
+                transaction_id,
+                hex(0, 2)
+            );
+            println!("{}Mode: {}{}", " ".repeat(margin + 4), mode, hex(2, 3));
+            println!(
+                "{}Event Code: {:#08X}{}",
                 " ".repeat(margin + 4),
-                transaction_id
+                event_code,
+                hex(3, 7)
             );
-            println!("{}Mode: {}", " ".repeat(margin + 4), mode);
-            println!("{}Event Code: {:#08X}", " ".repeat(margin + 4), event_code);
         }
         MessageId::Rsp_Notify_Error => {
-            let (_i, return_code) = be_u8(input)?;
+            let (_i, return_code) = be_u8(i)?;
             println!("{}(131) Response Notify Error:", " ".repeat(margin));
             println!(
-                "{}Return Code: {}",
+                "{}Return Code: {}{}",
                 " ".repeat(margin + 4),
-                ReturnCode(return_code)
+                ReturnCode(return_code),
+                hex(2, 3)
             );
         }
         MessageId::Req_DM => {
@@ -349,21 +794,34 @@ fn message_decoder(input: &[u8], msg_type: MessageId) -> IResult<&[u8], bool> {
 
             println!("{}(4) Request (DM) Device Management:", " ".repeat(margin));
             println!(
-                "{}Transaction ID: {}",
+                "{}Transaction ID: {}{}",
                 " ".repeat(margin + 4),
-                transaction_id
+                transaction_id,
+                hex(0, 2)
+            );
+            println!(
+                "{}Mode: {:b} ({}){}",
+                " ".repeat(margin + 4),
+                mode,
+                mode_bit7,
+                hex(2, 3)
+            );
+            println!("{}Port: {:#06X}{}", " ".repeat(margin + 4), port, hex(3, 5));
+            println!(
+                "{}Channel: {:#06X}{}",
+                " ".repeat(margin + 4),
+                channel,
+                hex(5, 7)
             );
-            println!("{}Mode: {:b} ({})", " ".repeat(margin + 4), mode, mode_bit7);
-            println!("{}Port: {:#06X}", " ".repeat(margin + 4), port);
-            println!("{}Channel: {:#06X}", " ".repeat(margin + 4), channel);
             if command == 0 {
                 println!(
-                    "{}Command: {} - Null (default)",
+                    "{}Command: {} - Null (default){}",
                     " ".repeat(margin + 4),
-                    channel
+                    channel,
+                    hex(7, 8)
                 );
             } else {
-                println!("{}Command: (Reserved)", " ".repeat(margin + 4));
+                println!("{}Command: (Reserved){}", " ".repeat(margin + 4), hex(7, 8));
             }
         }
         MessageId::Rsp_DM => {
@@ -371,15 +829,17 @@ fn message_decoder(input: &[u8], msg_type: MessageId) -> IResult<&[u8], bool> {
             let (_i, return_code) = be_u8(i)?;
             println!("{}(5) Response (DM) Device Management:", " ".repeat(margin));
             println!(
-                "{}Transaction ID: {:?}",
+                "{}Transaction ID: {:?}{}",
                 " ".repeat(margin + 4),
-                transaction_id
+                transaction_id,
+                hex(0, 2)
             );
-            println!("{}Mode: {}", " ".repeat(margin + 4), mode);
+            println!("{}Mode: {}{}", " ".repeat(margin + 4), mode, hex(2, 3));
             println!(
-                "{}Return Code: {}",
+                "{}Return Code: {}{}",
                 " ".repeat(margin + 4),
-                ReturnCode(return_code)
+                ReturnCode(return_code),
+                hex(3, 4)
             );
         }
         MessageId::Rsp_DM_Error => {
@@ -390,14 +850,16 @@ fn message_decoder(input: &[u8], msg_type: MessageId) -> IResult<&[u8], bool> {
                 " ".repeat(margin)
             );
             println!(
-                "{}Transaction ID: {:?}",
+                "{}Transaction ID: {:?}{}",
                 " ".repeat(margin + 4),
-                transaction_id
+                transaction_id,
+                hex(0, 2)
             );
             println!(
-                "{}Return Code: {}",
+                "{}Return Code: {}{}",
                 " ".repeat(margin + 4),
-                ReturnCode(return_code)
+                ReturnCode(return_code),
+                hex(2, 3)
             );
         }
         MessageId::Req_EDS => {
@@ -414,18 +876,38 @@ fn message_decoder(input: &[u8], msg_type: MessageId) -> IResult<&[u8], bool> {
                 " ".repeat(margin)
             );
             println!(
-                "{}Transaction ID: {}",
+                "{}Transaction ID: {}{}",
+                " ".repeat(margin + 4),
+                transaction_id,
+                hex(0, 2)
+            );
+            println!("{}Mode: {:#04X}{}", " ".repeat(margin + 4), mode, hex(2, 3));
+            println!("{}Port: {:#06X}{}", " ".repeat(margin + 4), port, hex(3, 5));
+            println!(
+                "{}Channel: {:#06X}{}",
+                " ".repeat(margin + 4),
+                channel,
+                hex(5, 7)
+            );
+            println!(
+                "{}Vendor ID: {}{}",
                 " ".repeat(margin + 4),
-                transaction_id
+                vendor_label(vendor_id),
+                hex(7, 11)
+            );
+            println!(
+                "{}Vendor Index: {}{}",
+                " ".repeat(margin + 4),
+                vendor_index,
+                hex(11, 12)
             );
-            println!("{}Mode: {:#04X}", " ".repeat(margin + 4), mode);
-            println!("{}Port: {:#06X}", " ".repeat(margin + 4), port);
-            println!("{}Channel: {:#06X}", " ".repeat(margin + 4), channel);
-            println!("{}Vendor ID: {}", " ".repeat(margin + 4), vendor_id);
-            println!("{}Vendor Index: {}", " ".repeat(margin + 4), vendor_index);
 
-            //decode rcp
-            let (_rem, _is_done) = parse_rcp(i, margin + 4).unwrap();
+            if vendor_id != CABLELABS_VENDOR_ID && is_vendor_registered(vendor_id) {
+                parse_vendor_tlvs(vendor_id, i, offset_of(i), margin + 4);
+            } else {
+                //decode rcp
+                let (_rem, _is_done) = parse_rcp(i, offset_of(i), margin + 4)?;
+            }
         }
         MessageId::Rsp_EDS => {
             let (i, mode) = be_u8(i)?;
@@ -439,18 +921,38 @@ fn message_decoder(input: &[u8], msg_type: MessageId) -> IResult<&[u8], bool> {
                 " ".repeat(margin)
             );
             println!(
-                "{}Transaction ID: {:?}",
+                "{}Transaction ID: {:?}{}",
+                " ".repeat(margin + 4),
+                transaction_id,
+                hex(0, 2)
+            );
+            println!("{}Mode: {:#04X}{}", " ".repeat(margin + 4), mode, hex(2, 3));
+            println!("{}Port: {:#06X}{}", " ".repeat(margin + 4), port, hex(3, 5));
+            println!(
+                "{}Channel: {:#06X}{}",
                 " ".repeat(margin + 4),
-                transaction_id
+                channel,
+                hex(5, 7)
+            );
+            println!(
+                "{}Vendor ID: {}{}",
+                " ".repeat(margin + 4),
+                vendor_label(vendor_id),
+                hex(7, 11)
+            );
+            println!(
+                "{}Vendor Index: {}{}",
+                " ".repeat(margin + 4),
+                vendor_index,
+                hex(11, 12)
             );
-            println!("{}Mode: {:#04X}", " ".repeat(margin + 4), mode);
-            println!("{}Port: {:#06X}", " ".repeat(margin + 4), port);
-            println!("{}Channel: {:#06X}", " ".repeat(margin + 4), channel);
-            println!("{}Vendor ID: {}", " ".repeat(margin + 4), vendor_id);
-            println!("{}Vendor Index: {}", " ".repeat(margin + 4), vendor_index);
 
-            //decode rcp
-            let (_rem, _is_done) = parse_rcp(i, margin + 4).unwrap();
+            if vendor_id != CABLELABS_VENDOR_ID && is_vendor_registered(vendor_id) {
+                parse_vendor_tlvs(vendor_id, i, offset_of(i), margin + 4);
+            } else {
+                //decode rcp
+                let (_rem, _is_done) = parse_rcp(i, offset_of(i), margin + 4)?;
+            }
         }
         MessageId::Rsp_EDS_Error => {
             println!(
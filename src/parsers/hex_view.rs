@@ -0,0 +1,58 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::parsers::tlv_parser::RphyTlv;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Prints the offset and raw bytes of every decoded field, as with `--hex`.
+pub fn enable_hex_view() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn hex_view_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Offset of `part` within `whole`, if it is a slice of it, e.g. the payload of a packet.
+pub(crate) fn offset_in(whole: &[u8], part: &[u8]) -> Option<usize> {
+    let (start, addr) = (whole.as_ptr() as usize, part.as_ptr() as usize);
+
+    (addr >= start && addr + part.len() <= start + whole.len()).then(|| addr - start)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `  [0x0036] 00 01` for the bytes of a field at `offset` in the frame, without the offset
+/// when it is not known. Empty when the hex view is off.
+pub(crate) fn field(offset: Option<usize>, bytes: &[u8]) -> String {
+    if !hex_view_enabled() {
+        return String::new();
+    }
+
+    match offset {
+        Some(off) => format!("  [{off:#06x}] {}", hex(bytes)),
+        None => format!("  {}", hex(bytes)),
+    }
+}
+
+/// Type and length bytes of the TLV, then its value after a `|`.
+/// The value of a Complex TLV is left to its sub-TLVs.
+pub(crate) fn tlv(tlv: &RphyTlv, complex: bool) -> String {
+    if !hex_view_enabled() {
+        return String::new();
+    }
+
+    let [len_hi, len_lo] = tlv.len.to_be_bytes();
+    let header = field(tlv.offset, &[tlv.typ, len_hi, len_lo]);
+    if complex || tlv.val.is_empty() {
+        header
+    } else {
+        format!("{header} | {}", hex(tlv.val))
+    }
+}
@@ -0,0 +1,778 @@
+use hex_fmt::HexFmt;
+use nom::bytes::complete::take;
+use nom::error::{Error, ErrorKind};
+use nom::multi::many0;
+use nom::number::complete::{be_u16, be_u32};
+use nom::IResult;
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
+use pnet::packet::udp::UdpPacket;
+use pnet::packet::Packet;
+use std::fmt;
+
+use crate::parsers::dictionary::dictionary;
+use crate::parsers::hex_view;
+use crate::parsers::tlv_def::EnumValue;
+use crate::parsers::tlv_parser::{to_ipv4, to_ipv6, to_u16, to_u32, to_u64};
+
+/// L2TPv3 directly over IP, as DEPI and UEPI run it.
+pub const L2TP_IP_PROTOCOL: IpNextHeaderProtocol = IpNextHeaderProtocols::L2tp;
+pub const L2TP_UDP_PORT: u16 = 1701;
+
+/// AVP vendor of the IETF attributes.
+pub const IETF_VENDOR: u16 = 0;
+/// AVP vendor of the DEPI and UEPI attributes, the CableLabs enterprise number.
+pub const CABLELABS_AVP_VENDOR: u16 = 4491;
+
+// T bit of the control and data message header.
+const TYPE_CONTROL: u16 = 0x8000;
+const CONTROL_HEADER_LEN: usize = 12;
+const AVP_HEADER_LEN: usize = 6;
+
+/// How the L2TPv3 messages of a packet are carried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L2tpTransport {
+    Ip,
+    Udp,
+}
+
+impl fmt::Display for L2tpTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            L2tpTransport::Ip => f.write_str("IP"),
+            L2tpTransport::Udp => f.write_str("UDP"),
+        }
+    }
+}
+
+/// The L2TPv3 packet in an IP payload, if any, and how it is carried.
+pub fn l2tp_packet(
+    protocol: IpNextHeaderProtocol,
+    payload: &[u8],
+) -> Option<(L2tpTransport, &[u8])> {
+    match protocol {
+        L2TP_IP_PROTOCOL => Some((L2tpTransport::Ip, payload)),
+        IpNextHeaderProtocols::Udp => {
+            let udp = UdpPacket::new(payload)?;
+            let is_l2tp =
+                udp.get_source() == L2TP_UDP_PORT || udp.get_destination() == L2TP_UDP_PORT;
+            // The UDP payload is a slice of `payload`, past the 8 bytes of the UDP header.
+            (is_l2tp && payload.len() >= 8)
+                .then(|| (L2tpTransport::Udp, &payload[8..8 + udp.payload().len()]))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlHeader {
+    /// T, L and S bits and the version, 3.
+    pub flags: u16,
+    pub length: u16,
+    /// Control Connection ID assigned by the recipient.
+    pub connection_id: u32,
+    pub ns: u16,
+    pub nr: u16,
+}
+
+impl ControlHeader {
+    pub fn version(&self) -> u16 {
+        self.flags & 0x000f
+    }
+}
+
+/// An Attribute Value Pair of a control message.
+#[derive(Debug, Clone, Copy)]
+pub struct Avp<'a> {
+    pub mandatory: bool,
+    pub hidden: bool,
+    pub vendor_id: u16,
+    pub typ: u16,
+    pub value: &'a [u8],
+    /// The whole AVP, header included.
+    pub raw: &'a [u8],
+}
+
+/// An L2TPv3 control message, or a data message with the session it belongs to.
+#[derive(Debug, Clone)]
+pub enum L2tpMessage<'a> {
+    Control {
+        header: ControlHeader,
+        avps: Vec<Avp<'a>>,
+    },
+    Data {
+        /// Session ID assigned by the recipient.
+        session_id: u32,
+        payload: &'a [u8],
+    },
+}
+
+impl<'a> L2tpMessage<'a> {
+    /// Message Type AVP of a control message. A control message without AVPs is a ZLB ack.
+    pub fn message_type(&self) -> Option<u16> {
+        match self {
+            L2tpMessage::Control { avps, .. } => avps
+                .iter()
+                .find(|a| a.vendor_id == IETF_VENDOR && a.typ == MESSAGE_TYPE)
+                .filter(|a| a.value.len() == 2)
+                .map(|a| to_u16(a.value)),
+            L2tpMessage::Data { .. } => None,
+        }
+    }
+
+    /// First AVP of this vendor and type.
+    pub fn avp(&self, vendor_id: u16, typ: u16) -> Option<&Avp<'a>> {
+        match self {
+            L2tpMessage::Control { avps, .. } => avps
+                .iter()
+                .find(|a| a.vendor_id == vendor_id && a.typ == typ),
+            L2tpMessage::Data { .. } => None,
+        }
+    }
+
+    /// Every AVP of this vendor and type.
+    pub fn avps(&self, vendor_id: u16, typ: u16) -> impl Iterator<Item = &Avp<'a>> {
+        let avps: &[Avp<'a>] = match self {
+            L2tpMessage::Control { avps, .. } => avps,
+            L2tpMessage::Data { .. } => &[],
+        };
+        avps.iter()
+            .filter(move |a| a.vendor_id == vendor_id && a.typ == typ)
+    }
+}
+
+fn avp(input: &[u8]) -> IResult<&[u8], Avp<'_>> {
+    let (_, word) = be_u16(input)?;
+    let len = usize::from(word & 0x03ff);
+    if len < AVP_HEADER_LEN {
+        return Err(nom::Err::Error(Error::new(input, ErrorKind::LengthValue)));
+    }
+
+    let (rest, raw) = take(len)(input)?;
+    let (i, _) = be_u16(raw)?;
+    let (i, vendor_id) = be_u16(i)?;
+    let (value, typ) = be_u16(i)?;
+
+    let avp = Avp {
+        mandatory: word & 0x8000 != 0,
+        hidden: word & 0x4000 != 0,
+        vendor_id,
+        typ,
+        value,
+        raw,
+    };
+    Ok((rest, avp))
+}
+
+fn control_message(input: &[u8]) -> IResult<&[u8], L2tpMessage<'_>> {
+    let (i, flags) = be_u16(input)?;
+    let (i, length) = be_u16(i)?;
+    let (i, connection_id) = be_u32(i)?;
+    let (i, ns) = be_u16(i)?;
+    let (i, nr) = be_u16(i)?;
+
+    let header = ControlHeader {
+        flags,
+        length,
+        connection_id,
+        ns,
+        nr,
+    };
+    let avp_len = usize::from(length).saturating_sub(CONTROL_HEADER_LEN);
+    let (rest, avp_bytes) = take(avp_len)(i)?;
+    let (_, avps) = many0(avp)(avp_bytes)?;
+
+    Ok((rest, L2tpMessage::Control { header, avps }))
+}
+
+/// Parses the L2TPv3 packet of an IP or UDP payload.
+pub fn parse_l2tp(input: &[u8], transport: L2tpTransport) -> IResult<&[u8], L2tpMessage<'_>> {
+    match transport {
+        // Over IP, the Session ID comes first and is 0 for the control messages.
+        L2tpTransport::Ip => {
+            let (i, session_id) = be_u32(input)?;
+            match session_id {
+                0 => control_message(i),
+                _ => Ok((
+                    &[],
+                    L2tpMessage::Data {
+                        session_id,
+                        payload: i,
+                    },
+                )),
+            }
+        }
+        L2tpTransport::Udp => {
+            let (_, flags) = be_u16(input)?;
+            if flags & TYPE_CONTROL != 0 {
+                return control_message(input);
+            }
+            let (i, _reserved) = be_u16(&input[2..])?;
+            let (payload, session_id) = be_u32(i)?;
+            Ok((
+                &[],
+                L2tpMessage::Data {
+                    session_id,
+                    payload,
+                },
+            ))
+        }
+    }
+}
+
+// IETF AVP types the sessions are built from.
+pub const MESSAGE_TYPE: u16 = 0;
+pub const RESULT_CODE: u16 = 1;
+pub const HOST_NAME: u16 = 7;
+pub const ASSIGNED_CONNECTION_ID: u16 = 61;
+pub const LOCAL_SESSION_ID: u16 = 63;
+pub const REMOTE_SESSION_ID: u16 = 64;
+pub const PSEUDOWIRE_TYPE: u16 = 68;
+pub const L2_SPECIFIC_SUBLAYER: u16 = 69;
+pub const CIRCUIT_STATUS: u16 = 71;
+
+// DEPI AVP types the sessions are built from.
+pub const DEPI_LOCAL_MTU: u16 = 4;
+pub const DEPI_REMOTE_MTU: u16 = 7;
+pub const DEPI_PW_SUBTYPE: u16 = 16;
+pub const DEPI_REMOTE_MULTICAST_JOIN: u16 = 19;
+
+/// Control message types, RFC 3931 section 3.1.
+const MESSAGE_TYPES: &[(u16, &str)] = &[
+    (1, "SCCRQ"),
+    (2, "SCCRP"),
+    (3, "SCCCN"),
+    (4, "StopCCN"),
+    (6, "HELLO"),
+    (7, "OCRQ"),
+    (8, "OCRP"),
+    (9, "OCCN"),
+    (10, "ICRQ"),
+    (11, "ICRP"),
+    (12, "ICCN"),
+    (14, "CDN"),
+    (15, "WEN"),
+    (16, "SLI"),
+    (20, "ACK"),
+];
+
+/// `SCCRQ`, or the number of an unknown message type.
+pub fn message_type_name(typ: u16) -> String {
+    MESSAGE_TYPES
+        .iter()
+        .find(|(t, _)| *t == typ)
+        .map_or_else(|| typ.to_string(), |(_, name)| name.to_string())
+}
+
+/// How an AVP value is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AvpFormat {
+    Unsigned,
+    Text,
+    Hex,
+    Address,
+    MessageType,
+    /// Result Code, then the optional Error Code and message.
+    ResultCode,
+    /// A value of an enum of the R-PHY dictionary.
+    Enum(&'static str),
+    /// A list of 16 bit values of an enum of the R-PHY dictionary.
+    EnumList(&'static str),
+    CircuitStatus,
+    /// Reserved field, then the source and group addresses.
+    Multicast,
+}
+
+/// Names and formats of the IETF AVPs, by type, RFC 3931.
+const IETF_AVPS: &[(u16, &str, AvpFormat)] = &[
+    (MESSAGE_TYPE, "MessageType", AvpFormat::MessageType),
+    (RESULT_CODE, "ResultCode", AvpFormat::ResultCode),
+    (2, "ProtocolVersion", AvpFormat::Hex),
+    (3, "FramingCapabilities", AvpFormat::Unsigned),
+    (4, "BearerCapabilities", AvpFormat::Unsigned),
+    (5, "TieBreaker", AvpFormat::Hex),
+    (6, "FirmwareRevision", AvpFormat::Unsigned),
+    (HOST_NAME, "HostName", AvpFormat::Text),
+    (8, "VendorName", AvpFormat::Text),
+    (10, "ReceiveWindowSize", AvpFormat::Unsigned),
+    (11, "Challenge", AvpFormat::Hex),
+    (13, "ChallengeResponse", AvpFormat::Hex),
+    (15, "CallSerialNumber", AvpFormat::Unsigned),
+    (25, "PhysicalChannelId", AvpFormat::Unsigned),
+    (36, "RandomVector", AvpFormat::Hex),
+    (58, "ExtendedVendorId", AvpFormat::Unsigned),
+    (59, "MessageDigest", AvpFormat::Hex),
+    (60, "RouterId", AvpFormat::Address),
+    (
+        ASSIGNED_CONNECTION_ID,
+        "AssignedControlConnectionId",
+        AvpFormat::Unsigned,
+    ),
+    (
+        62,
+        "PseudowireCapabilitiesList",
+        AvpFormat::EnumList("PwType"),
+    ),
+    (LOCAL_SESSION_ID, "LocalSessionId", AvpFormat::Unsigned),
+    (REMOTE_SESSION_ID, "RemoteSessionId", AvpFormat::Unsigned),
+    (65, "AssignedCookie", AvpFormat::Hex),
+    (66, "RemoteEndId", AvpFormat::Hex),
+    (PSEUDOWIRE_TYPE, "PseudowireType", AvpFormat::Enum("PwType")),
+    (
+        L2_SPECIFIC_SUBLAYER,
+        "L2SpecificSublayer",
+        AvpFormat::Enum("L2SublayerType"),
+    ),
+    (70, "DataSequencing", AvpFormat::Unsigned),
+    (CIRCUIT_STATUS, "CircuitStatus", AvpFormat::CircuitStatus),
+    (72, "PreferredLanguage", AvpFormat::Text),
+    (73, "ControlMessageAuthenticationNonce", AvpFormat::Hex),
+    (74, "TxConnectSpeed", AvpFormat::Unsigned),
+    (75, "RxConnectSpeed", AvpFormat::Unsigned),
+];
+
+/// Names and formats of the CableLabs AVPs, by type, from the R-DEPI and R-UEPI
+/// specifications. The DsQamChannel ones are the M-CMTS DEPI pseudowire AVPs.
+const CABLELABS_AVPS: &[(u16, &str, AvpFormat)] = &[
+    (1, "DepiResultCode", AvpFormat::ResultCode),
+    (2, "DepiResourceAllocationRequest", AvpFormat::Hex),
+    (3, "DepiResourceAllocationReply", AvpFormat::Hex),
+    (
+        DEPI_LOCAL_MTU,
+        "DepiLocalMtuMaxPayload",
+        AvpFormat::Unsigned,
+    ),
+    (5, "DocsisSyncControl", AvpFormat::Unsigned),
+    (6, "EqamCapabilityBits", AvpFormat::Unsigned),
+    (
+        DEPI_REMOTE_MTU,
+        "DepiRemoteMtuMaxPayload",
+        AvpFormat::Unsigned,
+    ),
+    (8, "DepiLocalUdpPort", AvpFormat::Unsigned),
+    (9, "DprSessionType", AvpFormat::Unsigned),
+    (10, "DprSessionStatus", AvpFormat::Unsigned),
+    (
+        15,
+        "DepiPwSubtypeCapabilityList",
+        AvpFormat::EnumList("DepiPwSubtype"),
+    ),
+    (
+        DEPI_PW_SUBTYPE,
+        "DepiPwSubtype",
+        AvpFormat::Enum("DepiPwSubtype"),
+    ),
+    (
+        17,
+        "DepiL2SpecificSublayerSubtype",
+        AvpFormat::Enum("L2SublayerSubType"),
+    ),
+    (18, "DepiMulticastCapability", AvpFormat::Unsigned),
+    (
+        DEPI_REMOTE_MULTICAST_JOIN,
+        "DepiRemoteMulticastJoin",
+        AvpFormat::Multicast,
+    ),
+    (20, "DepiRemoteMulticastLeave", AvpFormat::Multicast),
+    (40, "DsQamChannelTsidGroup", AvpFormat::Hex),
+    (41, "DsQamChannelFrequency", AvpFormat::Unsigned),
+    (42, "DsQamChannelPower", AvpFormat::Unsigned),
+    (43, "DsQamChannelModulation", AvpFormat::Unsigned),
+    (44, "DsQamChannelJ83Annex", AvpFormat::Unsigned),
+    (45, "DsQamChannelSymbolRate", AvpFormat::Unsigned),
+    (46, "DsQamChannelInterleaverDepth", AvpFormat::Hex),
+    (47, "DsQamChannelRfBlockMuting", AvpFormat::Hex),
+];
+
+fn enum_label(table: &str, n: u64) -> String {
+    let name = dictionary()
+        .enums
+        .get(table)
+        .and_then(|t| t.get(&n.to_string()))
+        .map_or_else(|| "Reserved".to_string(), EnumValue::label);
+    format!("({n}) - {name}")
+}
+
+fn address(val: &[u8]) -> Option<String> {
+    match val.len() {
+        4 => Some(to_ipv4(val).to_string()),
+        16 => Some(to_ipv6(val).to_string()),
+        _ => None,
+    }
+}
+
+fn unsigned(val: &[u8]) -> Option<u64> {
+    match val.len() {
+        1 => Some(u64::from(val[0])),
+        2 => Some(u64::from(to_u16(val))),
+        4 => Some(u64::from(to_u32(val))),
+        8 => Some(to_u64(val)),
+        _ => None,
+    }
+}
+
+impl<'a> Avp<'a> {
+    fn definition(&self) -> Option<&'static (u16, &'static str, AvpFormat)> {
+        let table = match self.vendor_id {
+            IETF_VENDOR => IETF_AVPS,
+            CABLELABS_AVP_VENDOR => CABLELABS_AVPS,
+            _ => return None,
+        };
+        table.iter().find(|(typ, _, _)| *typ == self.typ)
+    }
+
+    /// Name of the AVP, e.g. `LocalSessionId`, if known.
+    pub fn name(&self) -> Option<&'static str> {
+        self.definition().map(|(_, name, _)| *name)
+    }
+
+    /// Value as an unsigned number, for the values of 1, 2, 4 or 8 bytes.
+    pub fn number(&self) -> Option<u64> {
+        unsigned(self.value)
+    }
+
+    /// Value as printed by the decoder.
+    pub fn text(&self) -> String {
+        let val = self.value;
+        let format = match self.definition() {
+            Some((_, _, format)) if !self.hidden => *format,
+            _ => AvpFormat::Hex,
+        };
+        let hex = || format!("{:X}", HexFmt(val));
+
+        match format {
+            AvpFormat::Unsigned => unsigned(val).map_or_else(hex, |n| n.to_string()),
+            AvpFormat::Text => String::from_utf8_lossy(val).to_string(),
+            AvpFormat::Hex => hex(),
+            AvpFormat::Address => address(val).unwrap_or_else(hex),
+            AvpFormat::MessageType if val.len() == 2 => {
+                let typ = to_u16(val);
+                format!("({typ}) {}", message_type_name(typ))
+            }
+            AvpFormat::ResultCode if val.len() >= 2 => {
+                let mut text = format!("Result {}", to_u16(val));
+                if val.len() >= 4 {
+                    text.push_str(&format!(", Error {}", to_u16(&val[2..])));
+                }
+                if val.len() > 4 {
+                    text.push_str(&format!(", '{}'", String::from_utf8_lossy(&val[4..])));
+                }
+                text
+            }
+            AvpFormat::Enum(table) => unsigned(val).map_or_else(hex, |n| enum_label(table, n)),
+            AvpFormat::EnumList(table) if val.len().is_multiple_of(2) => val
+                .chunks(2)
+                .map(|c| enum_label(table, u64::from(to_u16(c))))
+                .collect::<Vec<_>>()
+                .join(", "),
+            AvpFormat::CircuitStatus if val.len() == 2 => {
+                let status = to_u16(val);
+                let active = match status & 0x0001 {
+                    0 => "Inactive",
+                    _ => "Active",
+                };
+                match status & 0x0002 {
+                    0 => active.to_string(),
+                    _ => format!("{active}, New"),
+                }
+            }
+            AvpFormat::Multicast if val.len() > 2 && (val.len() - 2).is_multiple_of(2) => {
+                let (source, group) = val[2..].split_at((val.len() - 2) / 2);
+                match (address(source), address(group)) {
+                    (Some(source), Some(group)) => format!("Source {source}, Group {group}"),
+                    _ => hex(),
+                }
+            }
+            _ => hex(),
+        }
+    }
+
+    /// Name of the enum value without the number, e.g. `MPTPW, MPT Pseudowire Type`,
+    /// the text of the value for the other AVPs.
+    pub fn label(&self) -> String {
+        match (self.definition(), unsigned(self.value)) {
+            (Some((_, _, AvpFormat::Enum(table))), Some(n)) if !self.hidden => dictionary()
+                .enums
+                .get(*table)
+                .and_then(|t| t.get(&n.to_string()))
+                .map_or_else(|| self.text(), EnumValue::label),
+            _ => self.text(),
+        }
+    }
+
+    /// Prints the AVP, found at `offset` in the frame if known.
+    fn print(&self, offset: Option<usize>, margin: usize) {
+        let _m = " ".repeat(margin);
+        let hex = hex_view::field(offset, self.raw);
+        let hidden = if self.hidden { " (hidden)" } else { "" };
+
+        match (self.name(), self.vendor_id) {
+            (Some(name), IETF_VENDOR) => println!("{_m}{name}: {}{hidden}{hex}", self.text()),
+            (Some(name), vendor) => {
+                println!("{_m}{name} (Vendor {vendor}): {}{hidden}{hex}", self.text())
+            }
+            (None, vendor) => println!(
+                "{_m}Unsupported AVP: Vendor:{vendor}, Type:{}, Value:{:X}{hex}",
+                self.typ,
+                HexFmt(self.value)
+            ),
+        }
+    }
+}
+
+/// Prints an L2TPv3 message, its control header and AVPs or its data session.
+/// `offset` is the offset of the control header within the frame, if known.
+pub fn print_l2tp(message: &L2tpMessage, offset: Option<usize>, margin: usize) {
+    let _m = " ".repeat(margin);
+
+    match message {
+        L2tpMessage::Control { header, avps } => {
+            let typ = match message.message_type() {
+                Some(typ) => format!("({typ}) {}", message_type_name(typ)),
+                None => "ZLB".to_string(),
+            };
+            println!("{_m}L2TPv3 Control Message: {typ}");
+            println!(
+                "{_m}  Version: {}, Length: {}",
+                header.version(),
+                header.length
+            );
+            println!(
+                "{_m}  Control Connection ID: {}, Ns: {}, Nr: {}",
+                header.connection_id, header.ns, header.nr
+            );
+            let mut at = offset.map(|o| o + CONTROL_HEADER_LEN);
+            for avp in avps {
+                avp.print(at, margin + 2);
+                at = at.map(|o| o + avp.raw.len());
+            }
+        }
+        L2tpMessage::Data {
+            session_id,
+            payload,
+        } => {
+            println!(
+                "{_m}L2TPv3 Data Message: Session ID: {session_id}, Size: {}",
+                payload.len()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// AVP with the M bit set.
+    fn avp(vendor_id: u16, typ: u16, value: &[u8]) -> Vec<u8> {
+        let len = (AVP_HEADER_LEN + value.len()) as u16;
+        let mut avp = (0x8000 | len).to_be_bytes().to_vec();
+        avp.extend_from_slice(&vendor_id.to_be_bytes());
+        avp.extend_from_slice(&typ.to_be_bytes());
+        avp.extend_from_slice(value);
+        avp
+    }
+
+    /// Control message with the T, L and S bits, version 3, Control Connection ID 7, Ns 1
+    /// and Nr 2.
+    fn control(avps: &[Vec<u8>]) -> Vec<u8> {
+        let avps = avps.concat();
+        let mut message = 0xc803u16.to_be_bytes().to_vec();
+        message.extend_from_slice(&((CONTROL_HEADER_LEN + avps.len()) as u16).to_be_bytes());
+        message.extend_from_slice(&[0, 0, 0, 7, 0, 1, 0, 2]);
+        message.extend(avps);
+        message
+    }
+
+    fn icrq() -> Vec<u8> {
+        control(&[
+            avp(IETF_VENDOR, MESSAGE_TYPE, &10u16.to_be_bytes()),
+            avp(IETF_VENDOR, LOCAL_SESSION_ID, &0x1234u32.to_be_bytes()),
+            avp(CABLELABS_AVP_VENDOR, DEPI_PW_SUBTYPE, &1u16.to_be_bytes()),
+        ])
+    }
+
+    /// Text of the single AVP of a control message.
+    fn text(vendor_id: u16, typ: u16, value: &[u8]) -> String {
+        let message = control(&[avp(vendor_id, typ, value)]);
+        let (_, message) = parse_l2tp(&message, L2tpTransport::Udp).unwrap();
+        message.avp(vendor_id, typ).unwrap().text()
+    }
+
+    #[test]
+    fn parses_control_messages_over_ip_and_udp() {
+        let mut over_ip = vec![0, 0, 0, 0];
+        over_ip.extend(icrq());
+        over_ip.extend_from_slice(&[0xff, 0xff]);
+
+        let (rest, message) = parse_l2tp(&over_ip, L2tpTransport::Ip).unwrap();
+        assert_eq!(rest, [0xff, 0xff]);
+        let header = match &message {
+            L2tpMessage::Control { header, avps } => {
+                assert_eq!(avps.len(), 3);
+                assert!(avps.iter().all(|a| a.mandatory && !a.hidden));
+                header
+            }
+            L2tpMessage::Data { .. } => panic!("not a control message"),
+        };
+        assert_eq!(header.version(), 3);
+        assert_eq!((header.length, header.connection_id), (38, 7));
+        assert_eq!((header.ns, header.nr), (1, 2));
+        assert_eq!(message.message_type(), Some(10));
+        assert_eq!(message_type_name(10), "ICRQ");
+        let session_id = message.avp(IETF_VENDOR, LOCAL_SESSION_ID).unwrap();
+        assert_eq!(session_id.name(), Some("LocalSessionId"));
+        assert_eq!(session_id.number(), Some(0x1234));
+        assert_eq!(
+            message.avps(CABLELABS_AVP_VENDOR, DEPI_PW_SUBTYPE).count(),
+            1
+        );
+
+        let over_udp = icrq();
+        let (_, message) = parse_l2tp(&over_udp, L2tpTransport::Udp).unwrap();
+        assert_eq!(message.message_type(), Some(10));
+        // A control message without AVPs is a ZLB ack.
+        let zlb = control(&[]);
+        let (_, zlb) = parse_l2tp(&zlb, L2tpTransport::Udp).unwrap();
+        assert_eq!(zlb.message_type(), None);
+    }
+
+    #[test]
+    fn parses_data_messages_over_ip_and_udp() {
+        let (_, message) =
+            parse_l2tp(&[0, 0, 1, 0, 0x40, 0, 0, 1, 0xaa], L2tpTransport::Ip).unwrap();
+        assert!(matches!(
+            message,
+            L2tpMessage::Data { session_id: 0x100, payload } if payload == [0x40, 0, 0, 1, 0xaa]
+        ));
+        assert_eq!(message.message_type(), None);
+        assert!(message.avp(IETF_VENDOR, MESSAGE_TYPE).is_none());
+
+        // Over UDP, the flags and a reserved field come before the Session ID.
+        let (_, message) = parse_l2tp(&[0, 3, 0, 0, 0, 0, 2, 0, 0xbb], L2tpTransport::Udp).unwrap();
+        assert!(matches!(
+            message,
+            L2tpMessage::Data { session_id: 0x200, payload } if payload == [0xbb]
+        ));
+    }
+
+    #[test]
+    fn finds_l2tp_over_ip_and_udp() {
+        let packet = [0, 0, 1, 0, 0xaa];
+        assert_eq!(
+            l2tp_packet(L2TP_IP_PROTOCOL, &packet),
+            Some((L2tpTransport::Ip, &packet[..]))
+        );
+
+        let mut udp = vec![0x30, 0x39, 0x06, 0xa5, 0, 13, 0, 0];
+        udp.extend_from_slice(&packet);
+        assert_eq!(
+            l2tp_packet(IpNextHeaderProtocols::Udp, &udp),
+            Some((L2tpTransport::Udp, &packet[..]))
+        );
+        udp[2..4].copy_from_slice(&1702u16.to_be_bytes());
+        assert_eq!(l2tp_packet(IpNextHeaderProtocols::Udp, &udp), None);
+        assert_eq!(l2tp_packet(IpNextHeaderProtocols::Udp, &udp[..4]), None);
+        assert_eq!(l2tp_packet(IpNextHeaderProtocols::Tcp, &udp), None);
+    }
+
+    #[test]
+    fn prints_every_avp_format() {
+        // Unsigned, text and hex.
+        assert_eq!(text(IETF_VENDOR, 10, &[0, 4]), "4");
+        assert_eq!(text(IETF_VENDOR, 10, &[0, 0, 4]), "000004");
+        assert_eq!(text(IETF_VENDOR, HOST_NAME, b"core-a"), "core-a");
+        assert_eq!(text(IETF_VENDOR, 5, &[0xde, 0xad]), "DEAD");
+        assert_eq!(text(IETF_VENDOR, 60, &[10, 0, 0, 1]), "10.0.0.1");
+        assert_eq!(text(IETF_VENDOR, MESSAGE_TYPE, &[0, 4]), "(4) StopCCN");
+        assert_eq!(text(IETF_VENDOR, MESSAGE_TYPE, &[0, 99]), "(99) 99");
+        assert_eq!(
+            text(IETF_VENDOR, RESULT_CODE, b"\x00\x02\x00\x06busy"),
+            "Result 2, Error 6, 'busy'"
+        );
+        assert_eq!(text(CABLELABS_AVP_VENDOR, 1, &[0, 1]), "Result 1");
+        assert_eq!(
+            text(IETF_VENDOR, PSEUDOWIRE_TYPE, &[0, 12]),
+            "(12) - MPTPW, MPT Pseudowire Type"
+        );
+        assert_eq!(
+            text(IETF_VENDOR, PSEUDOWIRE_TYPE, &[0, 99]),
+            "(99) - Reserved"
+        );
+        assert_eq!(
+            text(IETF_VENDOR, 62, &[0, 12, 0, 13]),
+            "(12) - MPTPW, MPT Pseudowire Type, (13) - PSPPW, PSP Pseudowire"
+        );
+        assert_eq!(text(IETF_VENDOR, CIRCUIT_STATUS, &[0, 3]), "Active, New");
+        assert_eq!(text(IETF_VENDOR, CIRCUIT_STATUS, &[0, 0]), "Inactive");
+        assert_eq!(
+            text(
+                CABLELABS_AVP_VENDOR,
+                DEPI_REMOTE_MULTICAST_JOIN,
+                &[0, 0, 10, 0, 0, 1, 232, 1, 1, 1]
+            ),
+            "Source 10.0.0.1, Group 232.1.1.1"
+        );
+        assert_eq!(
+            text(CABLELABS_AVP_VENDOR, DEPI_REMOTE_MULTICAST_JOIN, &[0, 0, 1]),
+            "000001"
+        );
+        assert_eq!(
+            text(CABLELABS_AVP_VENDOR, DEPI_LOCAL_MTU, &[5, 0xdc]),
+            "1500"
+        );
+    }
+
+    #[test]
+    fn labels_enums_and_leaves_unknown_and_hidden_avps_in_hex() {
+        let message = control(&[
+            avp(CABLELABS_AVP_VENDOR, DEPI_PW_SUBTYPE, &[0, 1]),
+            avp(65000, 1, &[1, 2]),
+        ]);
+        let (_, message) = parse_l2tp(&message, L2tpTransport::Udp).unwrap();
+        let subtype = message.avp(CABLELABS_AVP_VENDOR, DEPI_PW_SUBTYPE).unwrap();
+        assert_eq!(subtype.label(), "MPT-DEPI-PW, MPT DEPI Pseudowire Subtype");
+        let unknown = message.avp(65000, 1).unwrap();
+        assert_eq!((unknown.name(), unknown.text()), (None, "0102".to_string()));
+
+        // The H bit: the value is encrypted.
+        let mut hidden = avp(IETF_VENDOR, HOST_NAME, b"ab");
+        hidden[0] |= 0x40;
+        let message = control(&[hidden]);
+        let (_, message) = parse_l2tp(&message, L2tpTransport::Udp).unwrap();
+        let avp = message.avp(IETF_VENDOR, HOST_NAME).unwrap();
+        assert!(avp.hidden);
+        assert_eq!(
+            (avp.text(), avp.label()),
+            ("6162".to_string(), "6162".to_string())
+        );
+    }
+
+    #[test]
+    fn short_buffers_are_errors() {
+        let mut over_ip = vec![0, 0, 0, 0];
+        over_ip.extend(icrq());
+        for end in 0..over_ip.len() {
+            if end < 4 + CONTROL_HEADER_LEN {
+                assert!(parse_l2tp(&over_ip[..end], L2tpTransport::Ip).is_err());
+            }
+            let _ = parse_l2tp(&over_ip[..end], L2tpTransport::Ip);
+            let _ = parse_l2tp(&over_ip[4..end.max(4)], L2tpTransport::Udp);
+        }
+        // The Length of the header counts more bytes than there are.
+        assert!(parse_l2tp(&icrq()[..30], L2tpTransport::Udp).is_err());
+        assert!(parse_l2tp(&[0], L2tpTransport::Udp).is_err());
+        assert!(parse_l2tp(&[0, 0, 0, 0, 0, 1], L2tpTransport::Udp).is_err());
+
+        // AVPs shorter than their header, or longer than the message, end the AVPs.
+        let mut short = avp(IETF_VENDOR, MESSAGE_TYPE, &[0, 1]);
+        short[1] = 3;
+        let short = control(&[short]);
+        let (_, message) = parse_l2tp(&short, L2tpTransport::Udp).unwrap();
+        assert!(matches!(message, L2tpMessage::Control { avps, .. } if avps.is_empty()));
+        let mut long = avp(IETF_VENDOR, MESSAGE_TYPE, &[0, 1]);
+        long[1] = 20;
+        let long = control(&[long]);
+        let (_, message) = parse_l2tp(&long, L2tpTransport::Udp).unwrap();
+        assert!(matches!(message, L2tpMessage::Control { avps, .. } if avps.is_empty()));
+    }
+}
@@ -1,3 +1,15 @@
+pub mod byte_input;
+pub mod date_and_time;
+pub mod dictionary;
+pub mod filter;
 pub mod gcp_parser;
+pub mod hex_view;
+pub mod l2tp_parser;
+pub mod pcap_reader;
+pub mod ptp_parser;
 pub mod rcp_parser;
+pub mod reassembly;
+pub mod summary;
+pub mod tlv_def;
 pub mod tlv_parser;
+pub mod vendor;
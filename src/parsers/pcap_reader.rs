@@ -0,0 +1,225 @@
+use pcap_parser::traits::PcapReaderIterator;
+use pcap_parser::{LegacyPcapReader, PcapBlockOwned, PcapError};
+use std::fmt;
+use std::io::{self, Cursor, Read};
+use std::thread;
+use std::time::Duration;
+
+/// Size of the read buffer, so the largest record a capture may hold.
+pub const SNAP_LEN: usize = 65536;
+const PCAP_HEADER_LEN: usize = 24;
+/// How often a followed capture is checked for new records.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug)]
+pub enum PcapReadError {
+    Io(io::Error),
+    Pcap(PcapError<&'static [u8]>),
+}
+
+impl fmt::Display for PcapReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PcapReadError::Io(e) => write!(f, "{e}"),
+            PcapReadError::Pcap(e) => write!(f, "{e:?}"),
+        }
+    }
+}
+
+impl From<PcapError<&[u8]>> for PcapReadError {
+    fn from(e: PcapError<&[u8]>) -> Self {
+        PcapReadError::Pcap(e.to_owned_vec())
+    }
+}
+
+/// Reads the whole file header, which a pipe or a file being written may deliver in pieces.
+fn read_header<R: Read>(input: &mut R, follow: bool) -> io::Result<Vec<u8>> {
+    let mut header = vec![0; PCAP_HEADER_LEN];
+    let mut len = 0;
+
+    while len < PCAP_HEADER_LEN {
+        match input.read(&mut header[len..])? {
+            0 if follow => thread::sleep(FOLLOW_INTERVAL),
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => len += n,
+        }
+    }
+
+    Ok(header)
+}
+
+/// Calls `f` with the file header, then with every record of the legacy pcap in `input`.
+/// A last record cut short ends the capture. With `follow`, the capture is read as it is
+/// written instead, until the process is stopped.
+pub fn read_pcap<R, F>(mut input: R, follow: bool, mut f: F) -> Result<(), PcapReadError>
+where
+    R: Read,
+    F: FnMut(PcapBlockOwned),
+{
+    let header = read_header(&mut input, follow).map_err(PcapReadError::Io)?;
+    let mut reader = LegacyPcapReader::new(SNAP_LEN, Cursor::new(header).chain(input))?;
+
+    loop {
+        match reader.next() {
+            Ok((offset, block)) => {
+                f(block);
+                reader.consume(offset);
+            }
+            Err(PcapError::Eof) if follow => {
+                thread::sleep(FOLLOW_INTERVAL);
+                reader.refill()?;
+            }
+            Err(PcapError::Eof) => break,
+            Err(PcapError::Incomplete) => {
+                if reader.reader_exhausted() {
+                    // The rest of the record is not written yet, or the capture was cut short.
+                    if !follow {
+                        break;
+                    }
+                    thread::sleep(FOLLOW_INTERVAL);
+                }
+                reader.refill()?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoders::pcap_builder::{pcap_file, PcapRecord};
+    use std::collections::VecDeque;
+    use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+
+    fn capture(frames: usize) -> Vec<u8> {
+        let records: Vec<PcapRecord> = (0..frames)
+            .map(|n| PcapRecord {
+                ts_sec: 1,
+                ts_usec: n as u32,
+                data: vec![n as u8; 60],
+            })
+            .collect();
+        pcap_file(&records)
+    }
+
+    /// First byte of every frame read, the frame number given by `capture`.
+    fn frames<R: Read>(input: R) -> Result<Vec<u8>, PcapReadError> {
+        let mut frames = Vec::new();
+        read_pcap(input, false, |block| {
+            if let PcapBlockOwned::Legacy(b) = block {
+                frames.push(b.data[0]);
+            }
+        })?;
+        Ok(frames)
+    }
+
+    /// A pipe, handing out at most `chunk` bytes per read.
+    struct Pipe<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Pipe<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    /// A file being written: reads return what was written so far, nothing while the
+    /// writer is idle, and an error once it sends `None`.
+    struct Growing {
+        written: Receiver<Option<Vec<u8>>>,
+        unread: VecDeque<u8>,
+    }
+
+    impl Read for Growing {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            while let Ok(chunk) = self.written.try_recv() {
+                match chunk {
+                    Some(chunk) => self.unread.extend(chunk),
+                    None => return Err(io::ErrorKind::BrokenPipe.into()),
+                }
+            }
+            let n = buf.len().min(self.unread.len());
+            for (b, u) in buf.iter_mut().zip(self.unread.drain(..n)) {
+                *b = u;
+            }
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn reads_a_pipe_delivering_records_in_pieces() {
+        let pcap = capture(3);
+
+        for chunk in [1, 7, 24, 100] {
+            let pipe = Pipe { data: &pcap, chunk };
+            assert_eq!(frames(pipe).unwrap(), [0, 1, 2]);
+        }
+    }
+
+    #[test]
+    fn a_record_cut_short_ends_the_capture() {
+        let pcap = capture(3);
+
+        assert_eq!(frames(&pcap[..pcap.len() - 20]).unwrap(), [0, 1]);
+        assert!(matches!(
+            frames(&pcap[..10]),
+            Err(PcapReadError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+        assert!(matches!(
+            frames(&[0u8; 24][..]),
+            Err(PcapReadError::Pcap(_))
+        ));
+    }
+
+    #[test]
+    fn follows_a_capture_as_it_is_written() {
+        let pcap = capture(3);
+        let record_len = (pcap.len() - 24) / 3;
+        let (write, written) = mpsc::channel();
+        let (read, frames) = mpsc::channel();
+        let input = Growing {
+            written,
+            unread: VecDeque::new(),
+        };
+        let reader = std::thread::spawn(move || {
+            read_pcap(input, true, |block| {
+                if let PcapBlockOwned::Legacy(b) = block {
+                    read.send(b.data[0]).unwrap();
+                }
+            })
+        });
+        let wait = FOLLOW_INTERVAL * 3;
+
+        // The file header and the first record, each in two writes.
+        write.send(Some(pcap[..10].to_vec())).unwrap();
+        std::thread::sleep(wait);
+        write.send(Some(pcap[10..40].to_vec())).unwrap();
+        assert_eq!(frames.recv_timeout(wait), Err(RecvTimeoutError::Timeout));
+        write
+            .send(Some(pcap[40..24 + record_len].to_vec()))
+            .unwrap();
+        assert_eq!(frames.recv_timeout(wait * 5), Ok(0));
+
+        // A record not written whole yet is waited for.
+        let second = 24 + record_len;
+        write
+            .send(Some(pcap[second..second + 30].to_vec()))
+            .unwrap();
+        assert_eq!(frames.recv_timeout(wait), Err(RecvTimeoutError::Timeout));
+        assert!(!reader.is_finished());
+        write.send(Some(pcap[second + 30..].to_vec())).unwrap();
+        assert_eq!(frames.recv_timeout(wait * 5), Ok(1));
+        assert_eq!(frames.recv_timeout(wait * 5), Ok(2));
+
+        write.send(None).unwrap();
+        assert!(reader.join().unwrap().is_err());
+    }
+}
@@ -0,0 +1,716 @@
+use hex_fmt::HexFmt;
+use nom::bytes::complete::take;
+use nom::error::{Error, ErrorKind};
+use nom::multi::many0;
+use nom::number::complete::{be_i16, be_i64, be_i8, be_u16, be_u32, be_u8};
+use nom::IResult;
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
+use pnet::packet::udp::UdpPacket;
+use pnet::packet::Packet;
+use std::fmt;
+
+use crate::parsers::hex_view;
+
+/// Ethertype of PTP directly over Ethernet, IEEE 1588 annex F.
+pub const PTP_ETHERTYPE: u16 = 0x88f7;
+/// UDP ports of the event messages, timestamped, and of the general ones, annex D and E.
+pub const PTP_EVENT_PORT: u16 = 319;
+pub const PTP_GENERAL_PORT: u16 = 320;
+
+const HEADER_LEN: usize = 34;
+const TLV_HEADER_LEN: usize = 4;
+
+// messageType values of the header.
+pub const SYNC: u8 = 0x0;
+pub const DELAY_REQ: u8 = 0x1;
+pub const FOLLOW_UP: u8 = 0x8;
+pub const DELAY_RESP: u8 = 0x9;
+pub const ANNOUNCE: u8 = 0xb;
+pub const SIGNALING: u8 = 0xc;
+
+/// Message types, IEEE 1588-2008 section 13.3.2.2.
+const MESSAGE_TYPES: &[(u8, &str)] = &[
+    (SYNC, "Sync"),
+    (DELAY_REQ, "Delay_Req"),
+    (0x2, "Pdelay_Req"),
+    (0x3, "Pdelay_Resp"),
+    (FOLLOW_UP, "Follow_Up"),
+    (DELAY_RESP, "Delay_Resp"),
+    (0xa, "Pdelay_Resp_Follow_Up"),
+    (ANNOUNCE, "Announce"),
+    (SIGNALING, "Signaling"),
+    (0xd, "Management"),
+];
+
+/// `Sync`, or the number of an unknown message type.
+pub fn message_type_name(typ: u8) -> String {
+    MESSAGE_TYPES
+        .iter()
+        .find(|(t, _)| *t == typ)
+        .map_or_else(|| typ.to_string(), |(_, name)| name.to_string())
+}
+
+/// Bits of the flagField, section 13.3.2.6.
+const FLAGS: &[(u16, &str)] = &[
+    (0x0100, "alternateMaster"),
+    (0x0200, "twoStep"),
+    (0x0400, "unicast"),
+    (0x2000, "profileSpecific1"),
+    (0x4000, "profileSpecific2"),
+    (0x0001, "leap61"),
+    (0x0002, "leap59"),
+    (0x0004, "currentUtcOffsetValid"),
+    (0x0008, "ptpTimescale"),
+    (0x0010, "timeTraceable"),
+    (0x0020, "frequencyTraceable"),
+];
+
+/// Time sources of the Announce message, section 7.6.2.6.
+const TIME_SOURCES: &[(u8, &str)] = &[
+    (0x10, "ATOMIC_CLOCK"),
+    (0x20, "GPS"),
+    (0x30, "TERRESTRIAL_RADIO"),
+    (0x40, "PTP"),
+    (0x50, "NTP"),
+    (0x60, "HAND_SET"),
+    (0x90, "OTHER"),
+    (0xa0, "INTERNAL_OSCILLATOR"),
+];
+
+/// `GPS`, or the number of an unknown time source.
+pub fn time_source_name(source: u8) -> String {
+    TIME_SOURCES
+        .iter()
+        .find(|(s, _)| *s == source)
+        .map_or_else(|| format!("{source:#04x}"), |(_, name)| name.to_string())
+}
+
+/// TLV types of the Signaling messages, unicast negotiation of section 16.1.
+const TLV_TYPES: &[(u16, &str)] = &[
+    (0x0003, "RequestUnicastTransmission"),
+    (0x0004, "GrantUnicastTransmission"),
+    (0x0005, "CancelUnicastTransmission"),
+    (0x0006, "AcknowledgeCancelUnicastTransmission"),
+];
+
+/// How a PTP message is carried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtpTransport {
+    Ethernet,
+    Udp,
+}
+
+impl fmt::Display for PtpTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PtpTransport::Ethernet => f.write_str("Ethernet"),
+            PtpTransport::Udp => f.write_str("UDP"),
+        }
+    }
+}
+
+/// The PTP message in an IP payload, if any: the UDP payload of the PTP ports.
+pub fn ptp_packet(protocol: IpNextHeaderProtocol, payload: &[u8]) -> Option<&[u8]> {
+    if protocol != IpNextHeaderProtocols::Udp {
+        return None;
+    }
+    let udp = UdpPacket::new(payload)?;
+    let is_ptp = [udp.get_source(), udp.get_destination()]
+        .iter()
+        .any(|p| *p == PTP_EVENT_PORT || *p == PTP_GENERAL_PORT);
+    // The UDP payload is a slice of `payload`, past the 8 bytes of the UDP header.
+    (is_ptp && payload.len() >= 8).then(|| &payload[8..8 + udp.payload().len()])
+}
+
+/// EUI-64 identity of a clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ClockIdentity(pub [u8; 8]);
+
+impl fmt::Display for ClockIdentity {
+    /// `001122.fffe.334455`, as linuxptp prints it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let b = self.0;
+        write!(
+            f,
+            "{:02x}{:02x}{:02x}.{:02x}{:02x}.{:02x}{:02x}{:02x}",
+            b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]
+        )
+    }
+}
+
+/// A PTP port: its clock and port number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortIdentity {
+    pub clock: ClockIdentity,
+    pub port: u16,
+}
+
+impl fmt::Display for PortIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.clock, self.port)
+    }
+}
+
+/// A PTP timestamp, 48 bit seconds and nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp {
+    pub seconds: u64,
+    pub nanoseconds: u32,
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:09}", self.seconds, self.nanoseconds)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtpHeader {
+    pub transport_specific: u8,
+    pub message_type: u8,
+    pub version: u8,
+    pub length: u16,
+    pub domain: u8,
+    pub flags: u16,
+    /// Correction in nanoseconds, times 2^16.
+    pub correction: i64,
+    pub source: PortIdentity,
+    pub sequence_id: u16,
+    pub control: u8,
+    pub log_message_interval: i8,
+}
+
+impl PtpHeader {
+    /// Names of the flags set, e.g. `twoStep+unicast`.
+    pub fn flag_names(&self) -> String {
+        FLAGS
+            .iter()
+            .filter(|(bit, _)| self.flags & bit != 0)
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+/// Quality of the grandmaster clock of an Announce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockQuality {
+    pub class: u8,
+    pub accuracy: u8,
+    pub offset_scaled_log_variance: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Announce {
+    pub origin: Timestamp,
+    pub current_utc_offset: i16,
+    pub priority1: u8,
+    pub quality: ClockQuality,
+    pub priority2: u8,
+    pub grandmaster: ClockIdentity,
+    pub steps_removed: u16,
+    pub time_source: u8,
+}
+
+/// A TLV of a Signaling message.
+#[derive(Debug, Clone, Copy)]
+pub struct PtpTlv<'a> {
+    pub typ: u16,
+    pub value: &'a [u8],
+    /// The whole TLV, header included.
+    pub raw: &'a [u8],
+}
+
+impl<'a> PtpTlv<'a> {
+    /// Name of the TLV, e.g. `GrantUnicastTransmission`, if known.
+    pub fn name(&self) -> Option<&'static str> {
+        TLV_TYPES
+            .iter()
+            .find(|(typ, _)| *typ == self.typ)
+            .map(|(_, name)| *name)
+    }
+
+    /// Value as printed by the decoder. The unicast requests and grants name the message
+    /// type, its log interval and the duration in seconds.
+    pub fn text(&self) -> String {
+        let val = self.value;
+        match self.typ {
+            0x0003 | 0x0004 if val.len() >= 6 => {
+                let mut text = format!(
+                    "{}, Log Interval: {}, Duration: {} s",
+                    message_type_name(val[0] >> 4),
+                    val[1] as i8,
+                    u32::from_be_bytes([val[2], val[3], val[4], val[5]])
+                );
+                // Grants end with the R flag, the grant is renewed.
+                if self.typ == 0x0004 && val.len() >= 8 && val[7] & 0x01 != 0 {
+                    text.push_str(", Renewal");
+                }
+                text
+            }
+            0x0005 | 0x0006 if !val.is_empty() => message_type_name(val[0] >> 4),
+            _ => format!("{:X}", HexFmt(val)),
+        }
+    }
+}
+
+/// Body of a PTP message, after the header.
+#[derive(Debug, Clone)]
+pub enum PtpBody<'a> {
+    Sync {
+        origin: Timestamp,
+    },
+    DelayReq {
+        origin: Timestamp,
+    },
+    FollowUp {
+        precise_origin: Timestamp,
+    },
+    DelayResp {
+        receive: Timestamp,
+        requesting: PortIdentity,
+    },
+    Announce(Announce),
+    Signaling {
+        target: PortIdentity,
+        tlvs: Vec<PtpTlv<'a>>,
+    },
+    /// Peer delay and Management messages, not decoded.
+    Other(&'a [u8]),
+}
+
+/// A PTP version 2 message.
+#[derive(Debug, Clone)]
+pub struct PtpMessage<'a> {
+    pub header: PtpHeader,
+    pub body: PtpBody<'a>,
+    /// The whole message.
+    pub raw: &'a [u8],
+}
+
+fn clock_identity(input: &[u8]) -> IResult<&[u8], ClockIdentity> {
+    let (i, bytes) = take(8usize)(input)?;
+    let mut identity = [0; 8];
+    identity.copy_from_slice(bytes);
+    Ok((i, ClockIdentity(identity)))
+}
+
+fn port_identity(input: &[u8]) -> IResult<&[u8], PortIdentity> {
+    let (i, clock) = clock_identity(input)?;
+    let (i, port) = be_u16(i)?;
+    Ok((i, PortIdentity { clock, port }))
+}
+
+fn timestamp(input: &[u8]) -> IResult<&[u8], Timestamp> {
+    let (i, high) = be_u16(input)?;
+    let (i, low) = be_u32(i)?;
+    let (i, nanoseconds) = be_u32(i)?;
+    let timestamp = Timestamp {
+        seconds: (u64::from(high) << 32) | u64::from(low),
+        nanoseconds,
+    };
+    Ok((i, timestamp))
+}
+
+fn header(input: &[u8]) -> IResult<&[u8], PtpHeader> {
+    let (i, first) = be_u8(input)?;
+    let (i, version) = be_u8(i)?;
+    let (i, length) = be_u16(i)?;
+    let (i, domain) = be_u8(i)?;
+    let (i, _reserved) = be_u8(i)?;
+    let (i, flags) = be_u16(i)?;
+    let (i, correction) = be_i64(i)?;
+    let (i, _reserved) = be_u32(i)?;
+    let (i, source) = port_identity(i)?;
+    let (i, sequence_id) = be_u16(i)?;
+    let (i, control) = be_u8(i)?;
+    let (i, log_message_interval) = be_i8(i)?;
+
+    let header = PtpHeader {
+        transport_specific: first >> 4,
+        message_type: first & 0x0f,
+        version: version & 0x0f,
+        length,
+        domain,
+        flags,
+        correction,
+        source,
+        sequence_id,
+        control,
+        log_message_interval,
+    };
+    Ok((i, header))
+}
+
+fn announce(input: &[u8]) -> IResult<&[u8], Announce> {
+    let (i, origin) = timestamp(input)?;
+    let (i, current_utc_offset) = be_i16(i)?;
+    let (i, _reserved) = be_u8(i)?;
+    let (i, priority1) = be_u8(i)?;
+    let (i, class) = be_u8(i)?;
+    let (i, accuracy) = be_u8(i)?;
+    let (i, offset_scaled_log_variance) = be_u16(i)?;
+    let (i, priority2) = be_u8(i)?;
+    let (i, grandmaster) = clock_identity(i)?;
+    let (i, steps_removed) = be_u16(i)?;
+    let (i, time_source) = be_u8(i)?;
+
+    let announce = Announce {
+        origin,
+        current_utc_offset,
+        priority1,
+        quality: ClockQuality {
+            class,
+            accuracy,
+            offset_scaled_log_variance,
+        },
+        priority2,
+        grandmaster,
+        steps_removed,
+        time_source,
+    };
+    Ok((i, announce))
+}
+
+fn tlv(input: &[u8]) -> IResult<&[u8], PtpTlv<'_>> {
+    let (_, typ) = be_u16(input)?;
+    let (_, len) = be_u16(&input[2..])?;
+    let (rest, raw) = take(TLV_HEADER_LEN + usize::from(len))(input)?;
+
+    let tlv = PtpTlv {
+        typ,
+        value: &raw[TLV_HEADER_LEN..],
+        raw,
+    };
+    Ok((rest, tlv))
+}
+
+/// Parses a PTP message, from Ethernet or UDP. Only version 2 is supported.
+pub fn parse_ptp(input: &[u8]) -> IResult<&[u8], PtpMessage<'_>> {
+    let (i, header) = header(input)?;
+    if header.version != 2 || usize::from(header.length) < HEADER_LEN {
+        return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)));
+    }
+    // Ethernet pads short frames, the messageLength tells where the message ends.
+    let (rest, raw) = take(header.length)(input)?;
+    let body_bytes = &i[..raw.len() - HEADER_LEN];
+
+    let body = match header.message_type {
+        SYNC => PtpBody::Sync {
+            origin: timestamp(body_bytes)?.1,
+        },
+        DELAY_REQ => PtpBody::DelayReq {
+            origin: timestamp(body_bytes)?.1,
+        },
+        FOLLOW_UP => PtpBody::FollowUp {
+            precise_origin: timestamp(body_bytes)?.1,
+        },
+        DELAY_RESP => {
+            let (b, receive) = timestamp(body_bytes)?;
+            let (_, requesting) = port_identity(b)?;
+            PtpBody::DelayResp {
+                receive,
+                requesting,
+            }
+        }
+        ANNOUNCE => PtpBody::Announce(announce(body_bytes)?.1),
+        SIGNALING => {
+            let (b, target) = port_identity(body_bytes)?;
+            let (_, tlvs) = many0(tlv)(b)?;
+            PtpBody::Signaling { target, tlvs }
+        }
+        _ => PtpBody::Other(body_bytes),
+    };
+
+    Ok((rest, PtpMessage { header, body, raw }))
+}
+
+/// Prints a PTP message, its header and the fields of its body. `offset` is the offset of
+/// the message within the frame, if known.
+pub fn print_ptp(message: &PtpMessage, offset: Option<usize>, margin: usize) {
+    let _m = " ".repeat(margin);
+    let h = &message.header;
+    let at = |start: usize| offset.map(|o| o + start);
+    let hex = |start: usize, end: usize| hex_view::field(at(start), &message.raw[start..end]);
+
+    println!(
+        "{_m}PTPv{} {}: Domain: {}, Sequence ID: {}{}",
+        h.version,
+        message_type_name(h.message_type),
+        h.domain,
+        h.sequence_id,
+        hex(0, HEADER_LEN)
+    );
+    println!("{_m}  Source Port: {}", h.source);
+    println!(
+        "{_m}  Flags: {}, Correction: {} ns, Log Message Interval: {}",
+        h.flag_names(),
+        h.correction >> 16,
+        h.log_message_interval
+    );
+
+    let body = |len: usize| hex(HEADER_LEN, (HEADER_LEN + len).min(message.raw.len()));
+    match &message.body {
+        PtpBody::Sync { origin } | PtpBody::DelayReq { origin } => {
+            println!("{_m}  Origin Timestamp: {origin}{}", body(10));
+        }
+        PtpBody::FollowUp { precise_origin } => {
+            println!(
+                "{_m}  Precise Origin Timestamp: {precise_origin}{}",
+                body(10)
+            );
+        }
+        PtpBody::DelayResp {
+            receive,
+            requesting,
+        } => {
+            println!("{_m}  Receive Timestamp: {receive}{}", body(10));
+            println!("{_m}  Requesting Port: {requesting}");
+        }
+        PtpBody::Announce(a) => {
+            println!(
+                "{_m}  Origin Timestamp: {}, Current UTC Offset: {}{}",
+                a.origin,
+                a.current_utc_offset,
+                body(30)
+            );
+            println!(
+                "{_m}  Grandmaster: {}, Priority1: {}, Priority2: {}",
+                a.grandmaster, a.priority1, a.priority2
+            );
+            println!(
+                "{_m}  Clock Class: {}, Clock Accuracy: {:#04x}, Offset Scaled Log Variance: {:#06x}",
+                a.quality.class, a.quality.accuracy, a.quality.offset_scaled_log_variance
+            );
+            println!(
+                "{_m}  Steps Removed: {}, Time Source: {}",
+                a.steps_removed,
+                time_source_name(a.time_source)
+            );
+        }
+        PtpBody::Signaling { target, tlvs } => {
+            println!("{_m}  Target Port: {target}");
+            // The TLVs follow the target port identity.
+            let mut start = HEADER_LEN + 10;
+            for tlv in tlvs {
+                let hex = hex_view::field(at(start), tlv.raw);
+                start += tlv.raw.len();
+                match tlv.name() {
+                    Some(name) => println!("{_m}  {name}: {}{hex}", tlv.text()),
+                    None => println!(
+                        "{_m}  Unsupported TLV: Type:{:#06x}, Value:{:X}{hex}",
+                        tlv.typ,
+                        HexFmt(tlv.value)
+                    ),
+                }
+            }
+        }
+        PtpBody::Other(data) => {
+            if !data.is_empty() {
+                println!("{_m}  Body: {:X}{}", HexFmt(data), body(data.len()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLOCK: [u8; 8] = [0x00, 0x11, 0x22, 0xff, 0xfe, 0x33, 0x44, 0x55];
+
+    /// Message of this type with the twoStep flag, domain 0, a correction of 3 ns, port 1 of
+    /// `CLOCK`, Sequence ID 9 and a log message interval of -3.
+    fn message(message_type: u8, body: &[u8]) -> Vec<u8> {
+        let mut message = vec![message_type, 2];
+        message.extend_from_slice(&((HEADER_LEN + body.len()) as u16).to_be_bytes());
+        message.extend_from_slice(&[0, 0, 0x02, 0x00]);
+        message.extend_from_slice(&(3i64 << 16).to_be_bytes());
+        message.extend_from_slice(&[0; 4]);
+        message.extend_from_slice(&CLOCK);
+        message.extend_from_slice(&[0, 1, 0, 9, 0, 0xfd]);
+        message.extend_from_slice(body);
+        message
+    }
+
+    /// Timestamp of 0x1_00000002 seconds and 500 ns.
+    fn timestamp() -> Vec<u8> {
+        vec![0, 1, 0, 0, 0, 2, 0, 0, 0x01, 0xf4]
+    }
+
+    fn port() -> Vec<u8> {
+        [&CLOCK[..], &[0, 2]].concat()
+    }
+
+    fn signaling_tlv(typ: u16, value: &[u8]) -> Vec<u8> {
+        let mut tlv = typ.to_be_bytes().to_vec();
+        tlv.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        tlv.extend_from_slice(value);
+        tlv
+    }
+
+    #[test]
+    fn parses_the_header_and_the_timestamps() {
+        let mut sync = message(SYNC, &timestamp());
+        // Ethernet padding after the messageLength.
+        sync.extend_from_slice(&[0; 4]);
+
+        let (rest, parsed) = parse_ptp(&sync).unwrap();
+        assert_eq!(rest, [0; 4]);
+        assert_eq!(parsed.raw.len(), 44);
+        let h = parsed.header;
+        assert_eq!((h.message_type, h.version, h.length), (SYNC, 2, 44));
+        assert_eq!((h.correction >> 16, h.sequence_id), (3, 9));
+        assert_eq!(h.log_message_interval, -3);
+        assert_eq!(h.flag_names(), "twoStep");
+        assert_eq!(h.source.to_string(), "001122.fffe.334455-1");
+        let origin = match parsed.body {
+            PtpBody::Sync { origin } => origin,
+            body => panic!("not a Sync: {body:?}"),
+        };
+        assert_eq!(origin.to_string(), "4294967298.000000500");
+
+        for typ in [DELAY_REQ, FOLLOW_UP] {
+            let bytes = message(typ, &timestamp());
+            let (_, parsed) = parse_ptp(&bytes).unwrap();
+            assert!(matches!(
+                parsed.body,
+                PtpBody::DelayReq { .. } | PtpBody::FollowUp { .. }
+            ));
+        }
+
+        let resp = message(DELAY_RESP, &[timestamp(), port()].concat());
+        let (_, parsed) = parse_ptp(&resp).unwrap();
+        match parsed.body {
+            PtpBody::DelayResp {
+                receive,
+                requesting,
+            } => {
+                assert_eq!(receive.nanoseconds, 500);
+                assert_eq!(requesting.to_string(), "001122.fffe.334455-2");
+            }
+            body => panic!("not a Delay_Resp: {body:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_announce() {
+        let mut body = timestamp();
+        body.extend_from_slice(&37i16.to_be_bytes());
+        body.extend_from_slice(&[0, 128, 6, 0x21, 0x4e, 0x5d, 128]);
+        body.extend_from_slice(&CLOCK);
+        body.extend_from_slice(&[0, 1, 0x20]);
+        let announce = message(ANNOUNCE, &body);
+
+        let (_, parsed) = parse_ptp(&announce).unwrap();
+        let a = match parsed.body {
+            PtpBody::Announce(a) => a,
+            body => panic!("not an Announce: {body:?}"),
+        };
+        assert_eq!(
+            (a.current_utc_offset, a.priority1, a.priority2),
+            (37, 128, 128)
+        );
+        assert_eq!(a.quality.class, 6);
+        assert_eq!(a.quality.accuracy, 0x21);
+        assert_eq!(a.quality.offset_scaled_log_variance, 0x4e5d);
+        assert_eq!(a.grandmaster.to_string(), "001122.fffe.334455");
+        assert_eq!(a.steps_removed, 1);
+        assert_eq!(time_source_name(a.time_source), "GPS");
+        assert_eq!(time_source_name(0x11), "0x11");
+    }
+
+    #[test]
+    fn parses_the_tlvs_of_signaling() {
+        let tlvs = [
+            // Sync every 1/8 s for 300 s.
+            signaling_tlv(0x0003, &[0x00, 0xfd, 0, 0, 1, 0x2c]),
+            signaling_tlv(0x0004, &[0x00, 0xfd, 0, 0, 1, 0x2c, 0, 1]),
+            signaling_tlv(0x0005, &[0xb0, 0]),
+            signaling_tlv(0x0006, &[0x90, 0]),
+            signaling_tlv(0x8001, &[0xab, 0xcd]),
+        ];
+        let signaling = message(SIGNALING, &[port(), tlvs.concat()].concat());
+
+        let (_, parsed) = parse_ptp(&signaling).unwrap();
+        let (target, tlvs) = match &parsed.body {
+            PtpBody::Signaling { target, tlvs } => (target, tlvs),
+            body => panic!("not a Signaling: {body:?}"),
+        };
+        assert_eq!(target.port, 2);
+        let texts: Vec<(Option<&str>, String)> =
+            tlvs.iter().map(|t| (t.name(), t.text())).collect();
+        assert_eq!(
+            texts,
+            [
+                (
+                    Some("RequestUnicastTransmission"),
+                    "Sync, Log Interval: -3, Duration: 300 s".to_string()
+                ),
+                (
+                    Some("GrantUnicastTransmission"),
+                    "Sync, Log Interval: -3, Duration: 300 s, Renewal".to_string()
+                ),
+                (Some("CancelUnicastTransmission"), "Announce".to_string()),
+                (
+                    Some("AcknowledgeCancelUnicastTransmission"),
+                    "Delay_Resp".to_string()
+                ),
+                (None, "ABCD".to_string()),
+            ]
+        );
+        assert_eq!(tlvs[4].raw.len(), 6);
+    }
+
+    #[test]
+    fn leaves_other_messages_undecoded() {
+        let pdelay = message(0x2, &[1, 2, 3]);
+        let (_, parsed) = parse_ptp(&pdelay).unwrap();
+        assert!(matches!(parsed.body, PtpBody::Other([1, 2, 3])));
+        assert_eq!(message_type_name(0x2), "Pdelay_Req");
+        assert_eq!(message_type_name(0x7), "7");
+    }
+
+    #[test]
+    fn finds_ptp_over_udp() {
+        let sync = message(SYNC, &timestamp());
+        let mut udp = [PTP_EVENT_PORT.to_be_bytes(), PTP_EVENT_PORT.to_be_bytes()].concat();
+        udp.extend_from_slice(&((8 + sync.len()) as u16).to_be_bytes());
+        udp.extend_from_slice(&[0, 0]);
+        udp.extend_from_slice(&sync);
+
+        assert_eq!(
+            ptp_packet(IpNextHeaderProtocols::Udp, &udp),
+            Some(&sync[..])
+        );
+        assert_eq!(ptp_packet(IpNextHeaderProtocols::Tcp, &udp), None);
+        udp[..4].copy_from_slice(&[0, 53, 0, 53]);
+        assert_eq!(ptp_packet(IpNextHeaderProtocols::Udp, &udp), None);
+    }
+
+    #[test]
+    fn short_buffers_are_errors() {
+        let sync = message(SYNC, &timestamp());
+        for len in 0..sync.len() {
+            assert!(parse_ptp(&sync[..len]).is_err(), "{len} bytes");
+        }
+
+        // A body shorter than its message type needs.
+        let short = message(DELAY_RESP, &timestamp());
+        assert!(parse_ptp(&short).is_err());
+        let short = message(ANNOUNCE, &timestamp());
+        assert!(parse_ptp(&short).is_err());
+        // A TLV longer than the message ends the TLVs.
+        let truncated = message(SIGNALING, &[port(), vec![0, 3, 0, 10, 0]].concat());
+        let (_, parsed) = parse_ptp(&truncated).unwrap();
+        assert!(matches!(parsed.body, PtpBody::Signaling { tlvs, .. } if tlvs.is_empty()));
+
+        // Version 1 and a messageLength shorter than the header.
+        let mut v1 = sync.clone();
+        v1[1] = 1;
+        assert!(parse_ptp(&v1).is_err());
+        let mut too_short = sync;
+        too_short[2..4].copy_from_slice(&10u16.to_be_bytes());
+        assert!(parse_ptp(&too_short).is_err());
+    }
+}
@@ -5,6 +5,7 @@ pub use nom::IResult;
 use nom_derive::{NomBE, Parse};
 use rusticata_macros::newtype_enum;
 
+use crate::parsers::hex_view;
 use crate::parsers::tlv_parser::parse_rphy_tlvs;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Default, NomBE)]
@@ -18,14 +19,6 @@ newtype_enum! {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub struct RcpSequence {
-    pub sequence: u8,
-    pub sequence_size: u16,
-    pub sequence_number: u16,
-    pub operation: Operation,
-}
-
 //------------------------------
 // TLVs Start
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Default, NomBE)]
@@ -44,32 +37,38 @@ newtype_enum! {
     }
 }
 
-pub fn parse_rcp(input: &[u8], margin_base: usize) -> IResult<&[u8], bool> {
-    let (i, message_type) = RcpMessageType::parse(input).unwrap();
+/// Decodes an RCP message. `offset` is the offset of `input` within the frame, if known.
+pub fn parse_rcp(input: &[u8], offset: Option<usize>, margin_base: usize) -> IResult<&[u8], bool> {
+    let (i, message_type) = RcpMessageType::parse(input)?;
     let (i, _message_size) = be_u16(i)?; //just jumping bytes, not needed
+    let hex = hex_view::field(offset, &input[..3]);
+    let tlvs_offset = offset.map(|o| o + 3);
 
     // dbg!(message_type);
     match message_type {
         RcpMessageType::IRA => {
             println!(
-                "{}IRA: Identification and Resource Advertising",
+                "{}IRA: Identification and Resource Advertising{hex}",
                 " ".repeat(margin_base)
             );
             // decode_rcp_sequences(i).expect("\t Sequence: Unable to decode sequence.");
-            parse_rphy_tlvs(i, margin_base + 2);
+            parse_rphy_tlvs(i, tlvs_offset, margin_base + 2);
         }
         RcpMessageType::REX => {
-            println!("{}REX: RCP Object Exchange", " ".repeat(margin_base));
+            println!("{}REX: RCP Object Exchange{hex}", " ".repeat(margin_base));
 
-            parse_rphy_tlvs(i, margin_base + 2);
+            parse_rphy_tlvs(i, tlvs_offset, margin_base + 2);
         }
         RcpMessageType::NTF => {
-            println!("{}NTF: Notification", " ".repeat(margin_base));
+            println!("{}NTF: Notification{hex}", " ".repeat(margin_base));
 
-            parse_rphy_tlvs(i, margin_base + 2);
+            parse_rphy_tlvs(i, tlvs_offset, margin_base + 2);
         }
         _ => {
-            println!("{}Unsupported RCP Message Type", " ".repeat(margin_base));
+            println!(
+                "{}Unsupported RCP Message Type{hex}",
+                " ".repeat(margin_base)
+            );
         }
     }
 
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use crate::parsers::gcp_parser::split_messages;
+
+/// Start of the GCP message continued in the next segment, for every TCP direction,
+/// with the sequence number that segment has to start at.
+#[derive(Debug, Default)]
+pub struct Reassembly {
+    pending: HashMap<[SocketAddr; 2], (u32, Vec<u8>)>,
+}
+
+impl Reassembly {
+    /// Largest GCP message: the header length counts up to 65535 bytes from the Unit ID.
+    pub const MAX_MESSAGE: usize = 6 + 65535;
+
+    /// Segment payload following the bytes left by the previous segment, if it continues them.
+    /// Bytes left for a segment that did not come, lost or not captured, are dropped.
+    pub fn stream(&mut self, sockets: [SocketAddr; 2], seq: u32, payload: &[u8]) -> Vec<u8> {
+        match self.pending.remove(&sockets) {
+            Some((next, mut data)) if next == seq => {
+                data.extend_from_slice(payload);
+                data
+            }
+            _ => payload.to_vec(),
+        }
+    }
+
+    /// Keeps `rest`, the start of a GCP message, until the segment starting at `next`.
+    /// Returns false if `rest` cannot start a message and is not kept.
+    pub fn keep(&mut self, sockets: [SocketAddr; 2], next: u32, rest: &[u8]) -> bool {
+        // The header length counts from the Unit ID, 6 bytes in.
+        let header_ok =
+            rest.len() < 10 || 6 + usize::from(u16::from_be_bytes([rest[4], rest[5]])) >= 10;
+        let kept = !rest.is_empty() && rest.len() < Self::MAX_MESSAGE && header_ok;
+        if kept {
+            self.pending.insert(sockets, (next, rest.to_vec()));
+        }
+
+        kept
+    }
+
+    /// Whole GCP messages of a TCP segment, the first one completed with the bytes left by
+    /// the previous segments of the direction. Keeps the start of a message continued in the
+    /// next segment.
+    pub fn messages(&mut self, sockets: [SocketAddr; 2], seq: u32, payload: &[u8]) -> Vec<Vec<u8>> {
+        let stream = self.stream(sockets, seq, payload);
+        let (messages, rest) = split_messages(&stream);
+        self.keep(sockets, seq.wrapping_add(payload.len() as u32), rest);
+
+        messages.into_iter().map(<[u8]>::to_vec).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sockets() -> [SocketAddr; 2] {
+        [
+            "10.0.0.2:40000".parse().unwrap(),
+            "10.0.0.1:8190".parse().unwrap(),
+        ]
+    }
+
+    /// GCP message with a body of `len` bytes, all `fill`.
+    fn message(fill: u8, len: u16) -> Vec<u8> {
+        let mut msg = vec![0, 1, 0, 1];
+        msg.extend_from_slice(&(len + 4).to_be_bytes());
+        msg.extend_from_slice(&[0, 7]);
+        msg.extend_from_slice(&len.to_be_bytes());
+        msg.extend(std::iter::repeat_n(fill, usize::from(len)));
+        msg
+    }
+
+    #[test]
+    fn completes_messages_split_over_segments() {
+        let (a, b) = (message(0xa, 20), message(0xb, 4));
+        let stream = [&a[..], &b[..]].concat();
+        let mut reassembly = Reassembly::default();
+
+        assert!(reassembly.messages(sockets(), 100, &stream[..5]).is_empty());
+        assert!(reassembly
+            .messages(sockets(), 105, &stream[5..25])
+            .is_empty());
+        assert_eq!(reassembly.messages(sockets(), 125, &stream[25..]), [a, b]);
+        assert!(reassembly.pending.is_empty());
+    }
+
+    #[test]
+    fn drops_the_start_of_a_message_missing_a_segment() {
+        let (a, b) = (message(0xa, 20), message(0xb, 4));
+        let mut reassembly = Reassembly::default();
+
+        assert!(reassembly.messages(sockets(), 100, &a[..10]).is_empty());
+        // The segment at 110 was lost, the next one starts a message.
+        assert_eq!(
+            reassembly.messages(sockets(), 130, &b),
+            std::slice::from_ref(&b)
+        );
+        // Directions are kept apart.
+        let [client, server] = sockets();
+        assert!(reassembly.messages(sockets(), 200, &a[..10]).is_empty());
+        assert_eq!(reassembly.messages([server, client], 210, &b), [b]);
+        assert_eq!(reassembly.pending.len(), 1);
+    }
+
+    #[test]
+    fn keeps_only_what_can_start_a_message() {
+        let mut reassembly = Reassembly::default();
+
+        assert!(reassembly.keep(sockets(), 0, &[0, 1, 0]));
+        assert!(!reassembly.keep(sockets(), 0, &[]));
+        // A header length below 4 cannot be GCP.
+        assert!(!reassembly.keep(sockets(), 0, &[0, 1, 0, 1, 0, 2, 0, 7, 0, 0]));
+    }
+}
@@ -0,0 +1,215 @@
+use nom::multi::many0;
+use std::collections::BTreeMap;
+
+use crate::parsers::dictionary::dictionary;
+use crate::parsers::gcp_parser::{rcp_offset, MessageId};
+use crate::parsers::rcp_parser::{Operation, RcpMessageType};
+use crate::parsers::tlv_def::{DataType, TlvDef};
+use crate::parsers::tlv_parser::{extension_vendor_id, parse_tlvs};
+use crate::parsers::vendor::{
+    is_vendor_registered, vendor_knows, vendor_label, CABLELABS_VENDOR_ID,
+};
+
+// TLV type 21, whose sub-TLVs other than the VendorId belong to the vendor.
+const VENDOR_SPECIFIC_EXTENSION: u8 = 21;
+
+/// Where a TLV sits: the types and names of its parents and its own,
+/// and the vendor for the TLVs of a vendor data structure.
+#[derive(Debug, Clone, Default)]
+struct TlvPath {
+    vendor: Option<u32>,
+    types: Vec<u8>,
+    names: Vec<String>,
+}
+
+impl TlvPath {
+    fn child(&self, typ: u8, name: Option<&str>) -> Self {
+        let mut path = self.clone();
+        path.types.push(typ);
+        path.names
+            .push(name.map_or_else(|| typ.to_string(), str::to_string));
+        path
+    }
+
+    fn key(&self) -> (Option<u32>, Vec<u8>) {
+        (self.vendor, self.types.clone())
+    }
+
+    /// `RfChannel/DsScQamChannelConfig (16.3)`
+    fn label(&self) -> String {
+        let types: Vec<String> = self.types.iter().map(u8::to_string).collect();
+        let label = format!("{} ({})", self.names.join("/"), types.join("."));
+
+        match self.vendor {
+            Some(id) => format!("Vendor {}: {label}", vendor_label(id)),
+            None => label,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TlvCount {
+    label: String,
+    count: usize,
+    first_frame: usize,
+}
+
+/// What a capture contains: GCP messages, RCP messages and Operations, and TLVs at every
+/// depth, with the TLVs the decoder reports as Unsupported and the first frame they are in.
+#[derive(Debug, Default)]
+pub struct Summary {
+    frames: usize,
+    gcp_frames: usize,
+    messages: BTreeMap<u8, usize>,
+    rcp_types: BTreeMap<u8, usize>,
+    operations: BTreeMap<u8, usize>,
+    tlvs: BTreeMap<(Option<u32>, Vec<u8>), TlvCount>,
+    unsupported: BTreeMap<(Option<u32>, Vec<u8>), TlvCount>,
+}
+
+impl Summary {
+    /// Counts a frame and the whole GCP messages it carries, a message split over TCP
+    /// segments with the frame completing it.
+    pub fn add(&mut self, frame_number: usize, messages: &[&[u8]]) {
+        self.frames += 1;
+        if !messages.is_empty() {
+            self.gcp_frames += 1;
+        }
+
+        for msg in messages {
+            let message_id = MessageId(msg[7]);
+            *self.messages.entry(message_id.0).or_default() += 1;
+            let body = &msg[10..];
+
+            if matches!(message_id, MessageId::Req_EDS | MessageId::Rsp_EDS) && body.len() > 12 {
+                let vendor_id = u32::from_be_bytes(body[7..11].try_into().unwrap());
+                if vendor_id != CABLELABS_VENDOR_ID && is_vendor_registered(vendor_id) {
+                    let path = TlvPath {
+                        vendor: Some(vendor_id),
+                        ..TlvPath::default()
+                    };
+                    self.vendor_tlvs(frame_number, vendor_id, &path, &body[12..]);
+                    continue;
+                }
+            }
+
+            let rcp = match rcp_offset(message_id, body) {
+                Some(off) => &body[off..],
+                None => continue,
+            };
+            *self.rcp_types.entry(rcp[0]).or_default() += 1;
+
+            let (_, sequences) =
+                many0(parse_tlvs)(rcp.get(3..).unwrap_or_default()).unwrap_or((&[], Vec::new()));
+            for seq in sequences {
+                let (_, tlvs) = many0(parse_tlvs)(seq.val).unwrap_or((&[], Vec::new()));
+                if let Some(op) = tlvs.iter().find(|t| t.typ == 11 && t.len == 1) {
+                    *self.operations.entry(op.val[0]).or_default() += 1;
+                }
+                self.count_tlvs(
+                    frame_number,
+                    &dictionary().tlv,
+                    &TlvPath::default(),
+                    seq.val,
+                );
+            }
+        }
+    }
+
+    fn count(map: &mut BTreeMap<(Option<u32>, Vec<u8>), TlvCount>, frame: usize, path: &TlvPath) {
+        map.entry(path.key())
+            .or_insert_with(|| TlvCount {
+                label: path.label(),
+                count: 0,
+                first_frame: frame,
+            })
+            .count += 1;
+    }
+
+    fn count_tlvs(&mut self, frame: usize, defs: &[TlvDef], parent: &TlvPath, input: &[u8]) {
+        let (_, tlvs) = many0(parse_tlvs)(input).unwrap_or((&[], Vec::new()));
+
+        for t in tlvs.iter() {
+            let def = defs.iter().find(|d| d.typ == t.typ);
+            let path = parent.child(t.typ, def.map(|d| d.name.as_str()));
+            Summary::count(&mut self.tlvs, frame, &path);
+
+            match def {
+                Some(def) if parent.types.is_empty() && def.typ == VENDOR_SPECIFIC_EXTENSION => {
+                    self.extension_tlvs(frame, def, &path, t.val)
+                }
+                Some(def) if def.data_type == DataType::Complex => {
+                    self.count_tlvs(frame, &def.sub, &path, t.val)
+                }
+                Some(_) => (),
+                None => Summary::count(&mut self.unsupported, frame, &path),
+            }
+        }
+    }
+
+    /// Sub-TLVs of a VendorSpecificExtension: the VendorId, then TLVs of that vendor.
+    fn extension_tlvs(&mut self, frame: usize, def: &TlvDef, parent: &TlvPath, input: &[u8]) {
+        let (_, tlvs) = many0(parse_tlvs)(input).unwrap_or((&[], Vec::new()));
+        let vendor_id = extension_vendor_id(&tlvs);
+
+        for t in tlvs.iter() {
+            let name = def
+                .sub
+                .iter()
+                .find(|d| d.typ == t.typ)
+                .map(|d| d.name.as_str());
+            let path = parent.child(t.typ, name);
+            Summary::count(&mut self.tlvs, frame, &path);
+
+            let known = t.typ == 1 || vendor_id.is_some_and(|id| vendor_knows(id, t.typ));
+            if !known {
+                Summary::count(&mut self.unsupported, frame, &path);
+            }
+        }
+    }
+
+    fn vendor_tlvs(&mut self, frame: usize, vendor_id: u32, parent: &TlvPath, input: &[u8]) {
+        let (_, tlvs) = many0(parse_tlvs)(input).unwrap_or((&[], Vec::new()));
+
+        for t in tlvs.iter() {
+            let path = parent.child(t.typ, None);
+            Summary::count(&mut self.tlvs, frame, &path);
+            if !vendor_knows(vendor_id, t.typ) {
+                Summary::count(&mut self.unsupported, frame, &path);
+            }
+        }
+    }
+
+    pub fn print(&self) {
+        println!("{}", "*".repeat(50));
+        println!(
+            "* Summary: {} frames, {} with GCP messages",
+            self.frames, self.gcp_frames
+        );
+        println!("{}", "*".repeat(50));
+
+        println!("GCP Messages:");
+        for (id, count) in &self.messages {
+            println!("  {:?}: {count}", MessageId(*id));
+        }
+        println!("RCP Messages:");
+        for (typ, count) in &self.rcp_types {
+            println!("  {}: {count}", RcpMessageType(*typ));
+        }
+        println!("Operations:");
+        for (op, count) in &self.operations {
+            println!("  {}: {count}", Operation(*op));
+        }
+        println!("TLVs:");
+        for tlv in self.tlvs.values() {
+            println!("  {}: {}", tlv.label, tlv.count);
+        }
+        println!("Unsupported TLVs:");
+        for tlv in self.unsupported.values() {
+            println!(
+                "  {}: {}, first in frame {}",
+                tlv.label, tlv.count, tlv.first_frame
+            );
+        }
+    }
+}
@@ -0,0 +1,394 @@
+use eui48::MacAddress;
+use hex_fmt::HexFmt;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str;
+
+use crate::parsers::dictionary::spec_note;
+use crate::parsers::hex_view;
+use crate::parsers::tlv_parser::{
+    parse_tlvs_at, to_date_and_time, to_ipv4, to_ipv6, to_u16, to_u32, to_u64, BitField, BitFlag,
+    RphyTlv,
+};
+
+/// Enum value names keyed by the decimal value.
+pub type EnumTable = BTreeMap<String, EnumValue>;
+
+/// Name of an enum value, optionally limited to a range of specification versions.
+///
+/// ```toml
+/// values = { 1 = "StartUpNotification", 6 = { deprecated = "I06" } }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum EnumValue {
+    Name(String),
+    Versioned {
+        name: Option<String>,
+        since: Option<String>,
+        deprecated: Option<String>,
+    },
+}
+
+impl EnumValue {
+    pub fn label(&self) -> String {
+        match self {
+            EnumValue::Name(name) => name.clone(),
+            EnumValue::Versioned {
+                name,
+                since,
+                deprecated,
+            } => {
+                let name = name.as_deref().unwrap_or("Reserved");
+                match spec_note(since.as_deref(), deprecated.as_deref()) {
+                    Some(note) => format!("{name} ({note})"),
+                    None => name.to_string(),
+                }
+            }
+        }
+    }
+}
+
+/// Value encodings used by the R-PHY TLV definitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum DataType {
+    UnsignedByte,
+    UnsignedShort,
+    Short,
+    UnsignedInt,
+    UnsignedLong,
+    Bool,
+    IpAddress,
+    MacAddress,
+    HexBinary,
+    String,
+    DateAndTime,
+    Enum,
+    Bits,
+    Complex,
+}
+
+/// Declarative description of a TLV and, for Complex TLVs, of its sub-TLVs.
+///
+/// ```toml
+/// [[tlv]]
+/// type = 2
+/// name = "Mode"
+/// data_type = "Enum"
+/// size = 1
+/// values = { 1 = "on", 2 = "off" }
+///
+/// [[tlv]]
+/// type = 3
+/// name = "AdminState"
+/// data_type = "Enum"
+/// size = 1
+/// enum = "AdminStateType"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct TlvDef {
+    #[serde(rename = "type")]
+    pub typ: u8,
+    pub name: String,
+    pub data_type: DataType,
+    #[serde(default)]
+    pub units: Option<String>,
+    /// Size in bytes of an Enum or Bits value, see [`check_sizes`].
+    #[serde(default)]
+    pub size: Option<usize>,
+    /// First specification version defining the TLV.
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Specification version the TLV was deprecated in.
+    #[serde(default)]
+    pub deprecated: Option<String>,
+    /// Shared enum table the values are taken from, see [`resolve_enums`].
+    #[serde(default, rename = "enum")]
+    pub enum_name: Option<String>,
+    #[serde(default)]
+    pub values: EnumTable,
+    /// Flags of a Bits TLV.
+    #[serde(default)]
+    pub flags: Vec<BitFlag>,
+    #[serde(default, rename = "tlv")]
+    pub sub: Vec<TlvDef>,
+    /// Changes made by later specification versions, keyed by the version.
+    #[serde(default)]
+    pub version: BTreeMap<String, TlvRevision>,
+}
+
+/// Definition of a TLV from a specification version on. Fields left out keep their earlier value.
+///
+/// ```toml
+/// [tlv.version.I14]
+/// data_type = "UnsignedInt"
+/// units = "packets"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct TlvRevision {
+    pub name: Option<String>,
+    pub data_type: Option<DataType>,
+    pub size: Option<usize>,
+    pub units: Option<String>,
+    #[serde(rename = "enum")]
+    pub enum_name: Option<String>,
+    pub values: Option<EnumTable>,
+    pub flags: Option<Vec<BitFlag>>,
+}
+
+impl TlvDef {
+    /// Value as printed by the decoder, without the units.
+    /// Zero-length values, as sent in Read requests, are empty.
+    pub fn value(&self, val: &[u8]) -> String {
+        match self.data_type {
+            _ if val.is_empty() => String::new(),
+            DataType::UnsignedByte
+            | DataType::UnsignedShort
+            | DataType::Short
+            | DataType::UnsignedInt
+            | DataType::Bool
+                if val.len() < self.width() =>
+            {
+                format!("Invalid length: {}:{:X}", val.len(), HexFmt(val))
+            }
+            DataType::UnsignedByte => val[0].to_string(),
+            DataType::UnsignedShort => to_u16(val).to_string(),
+            DataType::Short => (to_u16(val) as i16).to_string(),
+            DataType::UnsignedInt => to_u32(val).to_string(),
+            DataType::UnsignedLong if val.len() >= 8 => to_u64(val).to_string(),
+            DataType::Bool => (val[0] == 1).to_string(),
+            DataType::IpAddress if val.len() == 4 => to_ipv4(val).to_string(),
+            DataType::IpAddress if val.len() == 16 => to_ipv6(val).to_string(),
+            DataType::MacAddress if val.len() == 6 => {
+                MacAddress::from_bytes(val).unwrap().to_hex_string()
+            }
+            DataType::String => String::from_utf8_lossy(val)
+                .trim_end_matches('\0')
+                .to_string(),
+            DataType::DateAndTime => to_date_and_time(val),
+            DataType::Enum if !val.is_empty() && val.len() <= 4 => {
+                let n = val.iter().fold(0u32, |n, b| (n << 8) | u32::from(*b));
+                match self.values.get(&n.to_string()) {
+                    Some(v) => format!("({n}) - {}", v.label()),
+                    None => format!("({n}) - Reserved"),
+                }
+            }
+            _ => format!("{:X}", HexFmt(val)),
+        }
+    }
+
+    fn width(&self) -> usize {
+        match self.data_type {
+            DataType::UnsignedByte | DataType::Bool => 1,
+            DataType::UnsignedShort | DataType::Short => 2,
+            DataType::UnsignedInt => 4,
+            DataType::UnsignedLong => 8,
+            _ => 0,
+        }
+    }
+
+    /// TLV name, flagged when the TLV is deprecated or not defined in the selected version.
+    pub fn label(&self) -> String {
+        match spec_note(self.since.as_deref(), self.deprecated.as_deref()) {
+            Some(note) => format!("{} ({note})", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Prints the TLV using this definition. Complex TLVs recurse into `sub`.
+    pub fn print(&self, tlv: &RphyTlv, margin: usize) {
+        let _m = " ".repeat(margin);
+        let name = self.label();
+
+        if let (DataType::Bits, Some(size)) = (self.data_type, self.size) {
+            let field = BitField {
+                name: &name,
+                size: size * 8,
+                flags: &self.flags,
+            };
+            field.print(tlv.val, margin, &hex_view::tlv(tlv, false));
+            return;
+        }
+
+        if self.data_type != DataType::Complex {
+            let hex = hex_view::tlv(tlv, false);
+            match &self.units {
+                _ if tlv.val.is_empty() => println!("{_m}{name}:{hex}"),
+                Some(units) => println!("{_m}{name}: {} {units}{hex}", self.value(tlv.val)),
+                None => println!("{_m}{name}: {}{hex}", self.value(tlv.val)),
+            }
+            return;
+        }
+
+        println!("{_m}{name}:{}", hex_view::tlv(tlv, true));
+        if tlv.val.is_empty() {
+            return;
+        }
+        print_tlvs(
+            &self.sub,
+            &self.name,
+            tlv.val,
+            tlv.value_offset(),
+            margin + 2,
+        );
+    }
+}
+
+/// Prints a stream of TLVs against a list of definitions.
+/// `offset` is the offset of `input` within the frame, if known.
+pub fn print_tlvs(
+    defs: &[TlvDef],
+    parent: &str,
+    input: &[u8],
+    offset: Option<usize>,
+    margin: usize,
+) {
+    let _m = " ".repeat(margin);
+    let tlvs = match parse_tlvs_at(input, offset) {
+        Ok((_, tlvs)) => tlvs,
+        Err(_) => {
+            println!("{_m}Malformed {parent} TLVs: {:X}", HexFmt(input));
+            return;
+        }
+    };
+
+    for t in tlvs.iter() {
+        match defs.iter().find(|d| d.typ == t.typ) {
+            Some(def) => def.print(t, margin),
+            None => println!(
+                "{_m}Unsupported {parent} sub-type: Type:{}, Value:{:X}{}",
+                t.typ,
+                HexFmt(t.val),
+                hex_view::tlv(t, false)
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DefinitionError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    UnknownEnum(String),
+    UnknownSpecVersion(String),
+    InvalidSize(String),
+    AlreadyLoaded,
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefinitionError::Io(e) => write!(f, "{e}"),
+            DefinitionError::Toml(e) => write!(f, "{e}"),
+            DefinitionError::UnknownEnum(name) => write!(f, "unknown enum '{name}'"),
+            DefinitionError::UnknownSpecVersion(v) => {
+                write!(f, "unknown specification version '{v}'")
+            }
+            DefinitionError::InvalidSize(name) => {
+                write!(f, "'{name}' needs a size of 1, 2 or 4 bytes")
+            }
+            DefinitionError::AlreadyLoaded => write!(f, "dictionary already loaded"),
+        }
+    }
+}
+
+/// Copies the shared enum tables into the definitions referencing them with `enum = "Name"`.
+pub fn resolve_enums(
+    defs: &mut [TlvDef],
+    enums: &BTreeMap<String, EnumTable>,
+) -> Result<(), DefinitionError> {
+    for def in defs.iter_mut() {
+        if let Some(name) = &def.enum_name {
+            let table = enums
+                .get(name)
+                .ok_or_else(|| DefinitionError::UnknownEnum(name.clone()))?;
+            // Values listed on the TLV itself take precedence.
+            let mut values = table.clone();
+            values.append(&mut def.values);
+            def.values = values;
+        }
+        resolve_enums(&mut def.sub, enums)?;
+    }
+
+    Ok(())
+}
+
+/// Applies the revisions of the given versions, oldest first, so that each definition is the one
+/// of the nearest version at or before the last one.
+pub fn apply_revisions(defs: &mut [TlvDef], versions: &[String]) {
+    for def in defs.iter_mut() {
+        for revision in versions.iter().filter_map(|v| def.version.get(v).cloned()) {
+            if let Some(name) = revision.name {
+                def.name = name;
+            }
+            if let Some(data_type) = revision.data_type {
+                def.data_type = data_type;
+            }
+            if let Some(size) = revision.size {
+                def.size = Some(size);
+            }
+            if let Some(units) = revision.units {
+                def.units = Some(units);
+            }
+            if let Some(enum_name) = revision.enum_name {
+                def.enum_name = Some(enum_name);
+            }
+            if let Some(values) = revision.values {
+                def.values = values;
+            }
+            if let Some(flags) = revision.flags {
+                def.flags = flags;
+            }
+        }
+        apply_revisions(&mut def.sub, versions);
+    }
+}
+
+/// Checks that every Enum and Bits definition has a size of 1, 2 or 4 bytes holding its flags.
+pub fn check_sizes(defs: &[TlvDef]) -> Result<(), DefinitionError> {
+    for def in defs.iter() {
+        let sized = matches!(def.data_type, DataType::Enum | DataType::Bits);
+        let bits = def.size.unwrap_or(0) * 8;
+        if sized != matches!(def.size, Some(1 | 2 | 4)) || def.flags.iter().any(|f| f.bit >= bits) {
+            return Err(DefinitionError::InvalidSize(def.name.clone()));
+        }
+        check_sizes(&def.sub)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that the `since` and `deprecated` versions of the definitions and enum values are known.
+pub fn check_spec_versions(
+    defs: &[TlvDef],
+    enums: &BTreeMap<String, EnumTable>,
+    versions: &[String],
+) -> Result<(), DefinitionError> {
+    let check = |v: &Option<String>| match v {
+        Some(v) if !versions.contains(v) => Err(DefinitionError::UnknownSpecVersion(v.clone())),
+        _ => Ok(()),
+    };
+    let check_values = |values: &EnumTable| {
+        values.values().try_for_each(|v| match v {
+            EnumValue::Versioned {
+                since, deprecated, ..
+            } => check(since).and(check(deprecated)),
+            EnumValue::Name(_) => Ok(()),
+        })
+    };
+
+    enums.values().try_for_each(check_values)?;
+    for def in defs.iter() {
+        check(&def.since)?;
+        check(&def.deprecated)?;
+        check_values(&def.values)?;
+        for (version, revision) in def.version.iter() {
+            check(&Some(version.clone()))?;
+            revision.values.as_ref().map_or(Ok(()), check_values)?;
+        }
+        check_spec_versions(&def.sub, &BTreeMap::new(), versions)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use hex_fmt::HexFmt;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+use crate::parsers::dictionary::{dictionary, selected_versions};
+use crate::parsers::hex_view;
+use crate::parsers::tlv_def::{
+    apply_revisions, check_sizes, check_spec_versions, resolve_enums, DefinitionError, EnumTable,
+    TlvDef,
+};
+use crate::parsers::tlv_parser::{parse_tlvs_at, RphyTlv};
+
+/// IANA enterprise number of CableLabs, used by GCP for the standard RCP data structures.
+pub const CABLELABS_VENDOR_ID: u32 = 4491;
+
+/// Decoder for the vendor-specific TLVs of one vendor.
+pub trait VendorDecoder: Send + Sync {
+    fn vendor_name(&self) -> &str;
+
+    /// Prints the TLV and returns true, or returns false if the TLV is not known.
+    fn decode(&self, tlv: &RphyTlv, margin: usize) -> bool;
+
+    /// Whether `decode` knows the TLV type, without printing anything.
+    /// Decoders that cannot tell beforehand are assumed to know every type.
+    fn knows(&self, _tlv_type: u8) -> bool {
+        true
+    }
+}
+
+// Registered decoders keyed by IANA enterprise number.
+static REGISTRY: RwLock<BTreeMap<u32, Vec<Box<dyn VendorDecoder>>>> = RwLock::new(BTreeMap::new());
+
+/// Registers a decoder for a vendor. Decoders of the same vendor are tried in order of registration.
+pub fn register_vendor_decoder(vendor_id: u32, decoder: Box<dyn VendorDecoder>) {
+    REGISTRY
+        .write()
+        .unwrap()
+        .entry(vendor_id)
+        .or_default()
+        .push(decoder);
+}
+
+pub fn vendor_name(vendor_id: u32) -> Option<String> {
+    if vendor_id == CABLELABS_VENDOR_ID {
+        return Some("CableLabs".to_string());
+    }
+    let registry = REGISTRY.read().unwrap();
+    let decoders = registry.get(&vendor_id)?;
+
+    decoders.first().map(|d| d.vendor_name().to_string())
+}
+
+/// Vendor id followed by the vendor name when known, e.g. `4491 (CableLabs)`.
+pub fn vendor_label(vendor_id: u32) -> String {
+    match vendor_name(vendor_id) {
+        Some(name) => format!("{vendor_id} ({name})"),
+        None => vendor_id.to_string(),
+    }
+}
+
+pub fn is_vendor_registered(vendor_id: u32) -> bool {
+    REGISTRY.read().unwrap().contains_key(&vendor_id)
+}
+
+/// Tries the decoders registered for the vendor. Returns false if none of them knows the TLV.
+pub fn decode_vendor_tlv(vendor_id: u32, tlv: &RphyTlv, margin: usize) -> bool {
+    let registry = REGISTRY.read().unwrap();
+
+    match registry.get(&vendor_id) {
+        Some(decoders) => decoders.iter().any(|d| d.decode(tlv, margin)),
+        None => false,
+    }
+}
+
+/// Whether a decoder registered for the vendor knows the TLV type.
+pub fn vendor_knows(vendor_id: u32, tlv_type: u8) -> bool {
+    let registry = REGISTRY.read().unwrap();
+
+    match registry.get(&vendor_id) {
+        Some(decoders) => decoders.iter().any(|d| d.knows(tlv_type)),
+        None => false,
+    }
+}
+
+/// Decodes the TLVs of a vendor data structure, e.g. an EDS message with a non CableLabs Vendor ID.
+/// `offset` is the offset of `input` within the frame, if known.
+pub fn parse_vendor_tlvs(vendor_id: u32, input: &[u8], offset: Option<usize>, margin: usize) {
+    let _m = " ".repeat(margin);
+    let tlvs = match parse_tlvs_at(input, offset) {
+        Ok((_, tlvs)) => tlvs,
+        Err(_) => {
+            println!("{_m}Malformed vendor TLVs: {:X}", HexFmt(input));
+            return;
+        }
+    };
+
+    for t in tlvs.iter() {
+        if !decode_vendor_tlv(vendor_id, t, margin) {
+            println!(
+                "{_m}Unsupported vendor TLV: Type:{}, Value:{:X}{}",
+                t.typ,
+                HexFmt(t.val),
+                hex_view::tlv(t, false)
+            );
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VendorInfo {
+    pub id: u32,
+    pub name: String,
+}
+
+/// Declarative vendor TLV definitions, loaded from a TOML file.
+///
+/// ```toml
+/// [vendor]
+/// id = 65000
+/// name = "Example"
+///
+/// [[tlv]]
+/// type = 2
+/// name = "ExampleCounter"
+/// data_type = "UnsignedInt"
+/// units = "packets"
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct VendorDefinitions {
+    pub vendor: VendorInfo,
+    /// Vendor enum tables, in addition to the ones of the R-PHY dictionary.
+    #[serde(default)]
+    pub enums: BTreeMap<String, EnumTable>,
+    #[serde(default)]
+    pub tlv: Vec<TlvDef>,
+}
+
+impl VendorDecoder for VendorDefinitions {
+    fn vendor_name(&self) -> &str {
+        &self.vendor.name
+    }
+
+    fn decode(&self, tlv: &RphyTlv, margin: usize) -> bool {
+        match self.tlv.iter().find(|d| d.typ == tlv.typ) {
+            Some(def) => {
+                def.print(tlv, margin);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn knows(&self, tlv_type: u8) -> bool {
+        self.tlv.iter().any(|d| d.typ == tlv_type)
+    }
+}
+
+/// Loads a vendor definitions file and registers it. Returns the vendor id it was registered for.
+pub fn load_vendor_definitions(path: &Path) -> Result<u32, DefinitionError> {
+    let text = std::fs::read_to_string(path).map_err(DefinitionError::Io)?;
+    let mut defs: VendorDefinitions = toml::from_str(&text).map_err(DefinitionError::Toml)?;
+    let vendor_id = defs.vendor.id;
+
+    check_spec_versions(&defs.tlv, &defs.enums, &dictionary().spec.versions)?;
+
+    let mut enums = dictionary().enums.clone();
+    enums.append(&mut defs.enums);
+    apply_revisions(&mut defs.tlv, selected_versions());
+    check_sizes(&defs.tlv)?;
+    resolve_enums(&mut defs.tlv, &enums)?;
+
+    register_vendor_decoder(vendor_id, Box::new(defs));
+
+    Ok(vendor_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::tlv_parser::extension_vendor_id;
+
+    const DEFINITIONS: &str = r#"
+[vendor]
+id = 65001
+name = "Test"
+
+[[tlv]]
+type = 2
+name = "TestCounter"
+data_type = "UnsignedInt"
+
+[[tlv]]
+type = 3
+name = "TestAdminState"
+data_type = "Enum"
+size = 1
+enum = "AdminStateType"
+"#;
+
+    fn write_definitions(name: &str, text: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}-{}.toml", std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn decodes_a_vendor_specific_extension_with_loaded_definitions() {
+        let path = write_definitions("vendor-test", DEFINITIONS);
+        let loaded = load_vendor_definitions(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), 65001);
+        assert!(is_vendor_registered(65001));
+        assert_eq!(vendor_label(65001), "65001 (Test)");
+        assert!(vendor_knows(65001, 2));
+        assert!(!vendor_knows(65001, 4));
+
+        // VendorSpecificExtension: VendorId 65001, TestCounter 7, TestAdminState 2 and type 4.
+        let extension = [
+            21, 0, 20, 1, 0, 2, 0xfd, 0xe9, 2, 0, 4, 0, 0, 0, 7, 3, 0, 1, 2, 4, 0, 1, 0,
+        ];
+        let (rest, tlvs) = parse_tlvs_at(&extension, Some(0)).unwrap();
+        assert!(rest.is_empty());
+        let (_, subs) = parse_tlvs_at(tlvs[0].val, tlvs[0].value_offset()).unwrap();
+        assert_eq!(subs[1].offset, Some(8));
+        assert_eq!(extension_vendor_id(&subs), Some(65001));
+        assert!(decode_vendor_tlv(65001, &subs[1], 2));
+        assert!(decode_vendor_tlv(65001, &subs[2], 2));
+        assert!(!decode_vendor_tlv(65001, &subs[3], 2));
+        assert!(!decode_vendor_tlv(65002, &subs[1], 2));
+    }
+
+    #[test]
+    fn resolves_enums_of_the_rphy_dictionary() {
+        let mut defs: VendorDefinitions = toml::from_str(DEFINITIONS).unwrap();
+        resolve_enums(&mut defs.tlv, &dictionary().enums).unwrap();
+        assert_eq!(defs.tlv[0].value(&[0, 0, 0, 7]), "7");
+        assert!(defs.tlv[1].value(&[2]).starts_with("(2) - "));
+    }
+
+    #[test]
+    fn rejects_invalid_definitions() {
+        let path = write_definitions(
+            "vendor-test-enum",
+            &DEFINITIONS.replace("AdminStateType", "NoSuchType"),
+        );
+        let loaded = load_vendor_definitions(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(loaded, Err(DefinitionError::UnknownEnum(name)) if name == "NoSuchType"));
+
+        let path = write_definitions("vendor-test-toml", "[vendor]\nid = \"x\"\n");
+        let loaded = load_vendor_definitions(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(loaded, Err(DefinitionError::Toml(_))));
+
+        let missing = std::env::temp_dir().join("vendor-test-missing.toml");
+        assert!(matches!(
+            load_vendor_definitions(&missing),
+            Err(DefinitionError::Io(_))
+        ));
+        assert!(!is_vendor_registered(65002));
+    }
+}
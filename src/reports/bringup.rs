@@ -0,0 +1,337 @@
+use pnet::util::MacAddr;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::parsers::rcp_parser::RcpMessageType;
+use crate::reports::{format_time, read_messages, CapturedMessage, ReportError, ReportTable, Tlv};
+
+/// Bring-up steps of an RPD, in order.
+const STEPS: [&str; 6] = [
+    "Authentication",
+    "PrincipalCoreConnect",
+    "Configuration",
+    "PtpLock",
+    "AuxCoreConnect",
+    "Operational",
+];
+
+const AUTHENTICATION: usize = 0;
+const PRINCIPAL_CONNECT: usize = 1;
+const CONFIGURATION: usize = 2;
+const PTP_LOCK: usize = 3;
+const AUX_CONNECT: usize = 4;
+const OPERATIONAL: usize = 5;
+
+#[derive(Debug, Clone, Copy)]
+struct At {
+    time: i64,
+    frame: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Step {
+    started: Option<At>,
+    completed: Option<At>,
+    /// The state or result that completed the step.
+    detail: Option<String>,
+}
+
+/// A state or notification, in capture order.
+#[derive(Debug, Clone)]
+struct Event {
+    at: At,
+    kind: String,
+    state: String,
+}
+
+#[derive(Debug, Clone)]
+struct RpdBringUp {
+    ip: IpAddr,
+    mac: MacAddr,
+    steps: [Step; 6],
+    events: Vec<Event>,
+}
+
+/// Sub-TLV with a value, the reads of the Core have empty ones.
+fn value<'a>(tlv: &'a Tlv, name: &str) -> Option<&'a Tlv> {
+    tlv.get(name).filter(|t| !t.value.is_empty())
+}
+
+impl RpdBringUp {
+    fn start(&mut self, step: usize, at: At) {
+        if self.steps[step].started.is_none() {
+            self.steps[step].started = Some(at);
+        }
+    }
+
+    fn complete(&mut self, step: usize, at: At, detail: String) {
+        let step = &mut self.steps[step];
+        if step.completed.is_none() {
+            step.completed = Some(at);
+            step.detail = Some(detail);
+        }
+    }
+
+    /// Completes a step seen started, when a later state shows it is over.
+    fn passed(&mut self, step: usize, at: At, detail: &str) {
+        if self.steps[step].started.is_some() {
+            self.complete(step, at, detail.to_string());
+        }
+    }
+
+    fn event(&mut self, at: At, kind: &str, state: String) {
+        // States are read again and again, only the changes are events.
+        let last = self.events.iter().rev().find(|e| e.kind == kind);
+        if last.is_some_and(|e| e.state == state) {
+            return;
+        }
+        self.events.push(Event {
+            at,
+            kind: kind.to_string(),
+            state,
+        });
+    }
+
+    fn rpd_state(&mut self, at: At, tlv: &Tlv) {
+        if let Some(t) = value(tlv, "TopLevelRpdState") {
+            let state = t.label();
+            self.event(at, "TopLevelRpdState", state.clone());
+            // TopLevelRpdState values, in bring-up order.
+            let top_level = t.number();
+            if top_level >= 3 {
+                self.passed(AUTHENTICATION, at, &state);
+            }
+            if top_level >= 6 {
+                self.passed(PRINCIPAL_CONNECT, at, &state);
+            }
+            match top_level {
+                2 => self.start(AUTHENTICATION, at),
+                5 => self.start(PRINCIPAL_CONNECT, at),
+                6 => {
+                    self.complete(CONFIGURATION, at, state);
+                    self.start(OPERATIONAL, at);
+                }
+                7 => {
+                    self.complete(CONFIGURATION, at, state.clone());
+                    self.complete(OPERATIONAL, at, state);
+                }
+                _ => (),
+            }
+        }
+
+        for auth in tlv.all("NetworkAuthenticationState") {
+            if let Some(t) = value(auth, "NetworkAuthenticationRpdState") {
+                let state = t.label();
+                self.event(at, "NetworkAuthenticationRpdState", state.clone());
+                match t.number() {
+                    1..=3 => self.start(AUTHENTICATION, at),
+                    4 | 5 => self.complete(AUTHENTICATION, at, state),
+                    _ => (),
+                }
+            }
+        }
+
+        if let Some(t) = value(tlv, "ConnectPrincipalCoreSubState") {
+            let state = t.label();
+            self.event(at, "ConnectPrincipalCoreSubState", state.clone());
+            // CoreSubState values: AuthenticateToCore and GcpConfigAuxCore connect,
+            // WaitForRcpIraReq and WaitForConfigRexReq configure, then operational.
+            match t.number() {
+                1 | 2 => self.start(PRINCIPAL_CONNECT, at),
+                3 | 4 => {
+                    self.complete(PRINCIPAL_CONNECT, at, state);
+                    self.start(CONFIGURATION, at);
+                }
+                5 | 6 => self.complete(CONFIGURATION, at, state),
+                _ => (),
+            }
+        }
+
+        for aux in tlv.all("AuxCoreState") {
+            if let Some(t) = value(aux, "AuxCoreSubState") {
+                let core = value(aux, "AuxCoreIp")
+                    .or_else(|| value(aux, "AuxCoreId"))
+                    .map_or_else(String::new, |c| format!("{} ", c.text()));
+                let state = format!("{core}{}", t.label());
+                self.event(at, "AuxCoreSubState", state.clone());
+                match t.number() {
+                    6 => self.complete(AUX_CONNECT, at, state),
+                    1..=5 => self.start(AUX_CONNECT, at),
+                    _ => (),
+                }
+            }
+        }
+
+        if let Some(t) = value(tlv, "LocalPtpSyncStatus") {
+            self.event(at, "LocalPtpSyncStatus", t.label());
+            match t.number() {
+                1 => self.complete(PTP_LOCK, at, t.label()),
+                _ => self.start(PTP_LOCK, at),
+            }
+        }
+    }
+
+    fn notification(&mut self, at: At, tlv: &Tlv) {
+        let kind = match value(tlv, "NotificationType") {
+            Some(t) => t.label(),
+            None => return,
+        };
+
+        match kind.as_str() {
+            // Sent to the Principal Core once the GCP connection is up.
+            "StartUpNotification" => {
+                self.event(at, &kind, String::new());
+                self.complete(PRINCIPAL_CONNECT, at, kind);
+            }
+            "PtpResultNotification" => {
+                let result = value(tlv, "PtpResult").map(Tlv::number);
+                let state = result.map_or_else(String::new, |r| format!("PtpResult {r}"));
+                self.event(at, &kind, state.clone());
+                // PtpResult 1 is the RPD synchronized.
+                match result {
+                    Some(1) => self.complete(PTP_LOCK, at, state),
+                    _ => self.start(PTP_LOCK, at),
+                }
+            }
+            "AuxCoreResultNotification" => {
+                let result = value(tlv, "AuxCoreResult")
+                    .map_or_else(String::new, |r| format!("AuxCoreResult {}", r.number()));
+                let core = value(tlv, "AuxCoreIpAddress")
+                    .map_or_else(String::new, |c| format!("{} ", c.text()));
+                let state = format!("{core}{result}");
+                self.event(at, &kind, state.clone());
+                self.complete(AUX_CONNECT, at, state);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Bring-up of every RPD of the captures: authentication, Principal Core connection,
+/// configuration, PTP lock, auxiliary Core connection and operational, from RpdState (87)
+/// and the StartUp, PtpResult and AuxCoreResult GeneralNotifications (86).
+#[derive(Debug, Default)]
+pub struct BringUp {
+    rpds: BTreeMap<(IpAddr, [u8; 6]), RpdBringUp>,
+}
+
+impl BringUp {
+    pub fn add(&mut self, msg: &CapturedMessage) {
+        let (ip, mac) = msg.rpd();
+        let rpd = self
+            .rpds
+            .entry((ip, mac.octets()))
+            .or_insert_with(|| RpdBringUp {
+                ip,
+                mac,
+                steps: Default::default(),
+                events: Vec::new(),
+            });
+        let at = At {
+            time: msg.time,
+            frame: msg.frame,
+        };
+
+        // The IRA and REX of the Principal Core configure the RPD.
+        let rex = msg.rcp_type.is_some_and(|t| {
+            matches!(RcpMessageType(t), RcpMessageType::IRA | RcpMessageType::REX)
+        });
+        if rex && !msg.from_rpd() && rpd.steps[PRINCIPAL_CONNECT].completed.is_some() {
+            rpd.start(CONFIGURATION, at);
+        }
+
+        for tlv in msg.tlvs("RpdState") {
+            rpd.rpd_state(at, tlv);
+        }
+        for tlv in msg.tlvs("GeneralNotification") {
+            rpd.notification(at, tlv);
+        }
+    }
+
+    /// A row per RPD and step. Steps started and never completed are `NOT COMPLETED`.
+    pub fn steps(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "rpd_ip",
+            "rpd_mac",
+            "step",
+            "status",
+            "started",
+            "completed",
+            "duration_ms",
+            "frame",
+            "detail",
+        ]);
+
+        let time = |at: Option<At>| at.map_or(Value::Null, |a| Value::from(format_time(a.time)));
+        for rpd in self.rpds.values() {
+            let mut previous: Option<At> = None;
+            for (name, step) in STEPS.iter().zip(&rpd.steps) {
+                // Without a state for its start, a completed step starts when the previous
+                // one completes, unless that was later.
+                let started = match (step.started, step.completed) {
+                    (Some(s), _) => Some(s),
+                    (None, Some(c)) => previous.filter(|p| p.time <= c.time),
+                    (None, None) => None,
+                };
+                let status = match (step.started, step.completed) {
+                    (_, Some(_)) => "completed",
+                    (Some(_), None) => "NOT COMPLETED",
+                    (None, None) => "not seen",
+                };
+                let duration = match (started, step.completed) {
+                    (Some(s), Some(c)) => Value::from((c.time - s.time) as f64 / 1000.0),
+                    _ => Value::Null,
+                };
+
+                table.push(vec![
+                    Value::from(rpd.ip.to_string()),
+                    Value::from(rpd.mac.to_string()),
+                    Value::from(*name),
+                    Value::from(status),
+                    time(started),
+                    time(step.completed),
+                    duration,
+                    step.completed
+                        .or(step.started)
+                        .map_or(Value::Null, |a| Value::from(a.frame)),
+                    step.detail.clone().map_or(Value::Null, Value::from),
+                ]);
+                previous = step.completed.or(previous);
+            }
+        }
+
+        table
+    }
+
+    /// Every state change and notification, by RPD then in capture order.
+    pub fn events(&self) -> ReportTable {
+        let mut table = ReportTable::new(&["rpd_ip", "rpd_mac", "time", "frame", "event", "state"]);
+
+        for rpd in self.rpds.values() {
+            for event in &rpd.events {
+                table.push(vec![
+                    Value::from(rpd.ip.to_string()),
+                    Value::from(rpd.mac.to_string()),
+                    Value::from(format_time(event.at.time)),
+                    Value::from(event.at.frame),
+                    Value::from(event.kind.clone()),
+                    Value::from(event.state.clone()),
+                ]);
+            }
+        }
+
+        table
+    }
+}
+
+/// Bring-up of the RPDs in `captures`, read in order.
+pub fn bring_up(captures: &[&Path]) -> Result<BringUp, ReportError> {
+    let mut bring_up = BringUp::default();
+    for path in captures {
+        read_messages(path, |msg| bring_up.add(msg))?;
+    }
+
+    Ok(bring_up)
+}
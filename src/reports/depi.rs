@@ -0,0 +1,249 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::parsers::l2tp_parser::L2tpMessage;
+use crate::reports::l2tp::L2tpSessions;
+use crate::reports::pseudowire::Pseudowires;
+use crate::reports::{
+    format_time, read_l2tp, read_messages, CapturedL2tp, ReportError, ReportTable,
+};
+
+// DEPI L2-Specific Sublayer header: flags, reserved, then the 16 bit sequence number.
+const SUBLAYER_LEN: usize = 4;
+// S bit of the sublayer header, the sequence number is valid.
+const SEQUENCE_VALID: u8 = 0x40;
+const TS_PACKET_LEN: usize = 188;
+const TS_SYNC_BYTE: u8 = 0x47;
+
+/// A session ID configured through StaticPwConfig or an L2TPv3 session, with the
+/// addresses its data is expected between.
+#[derive(Debug, Clone)]
+pub(crate) struct ExpectedSession {
+    pub session_id: u64,
+    pub src: Option<IpAddr>,
+    pub dst: Option<IpAddr>,
+    pub pw_type: Option<String>,
+    /// What configured it, e.g. `StaticPwConfig 10.0.0.2 forward 1`.
+    pub label: String,
+}
+
+impl ExpectedSession {
+    fn matches(&self, flow: &FlowKey) -> bool {
+        self.session_id == u64::from(flow.session_id) && self.dst.is_none_or(|d| d == flow.dst)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct FlowKey {
+    src: IpAddr,
+    dst: IpAddr,
+    session_id: u32,
+}
+
+/// Data packets of one session between two addresses.
+#[derive(Debug, Clone, Default)]
+struct Flow {
+    packets: usize,
+    /// Bytes after the Session ID, sublayer header included.
+    bytes: usize,
+    /// Packets carrying whole MPEG-TS packets, and their TS packets.
+    mpt_packets: usize,
+    ts_packets: usize,
+    first: i64,
+    last: i64,
+    sequence: Option<u16>,
+    /// Jumps forward of the sequence number, and the packets they skip.
+    gaps: usize,
+    lost: usize,
+    /// Sequence numbers behind the last one, duplicates included.
+    out_of_order: usize,
+}
+
+impl Flow {
+    fn add(&mut self, time: i64, payload: &[u8]) {
+        if self.packets == 0 {
+            self.first = time;
+        }
+        self.packets += 1;
+        self.bytes += payload.len();
+        self.last = time;
+
+        if payload.len() < SUBLAYER_LEN {
+            return;
+        }
+        // MPT carries whole TS packets after the sublayer header, PSP segments of DOCSIS frames.
+        let data = &payload[SUBLAYER_LEN..];
+        if !data.is_empty()
+            && data.len().is_multiple_of(TS_PACKET_LEN)
+            && data.chunks(TS_PACKET_LEN).all(|ts| ts[0] == TS_SYNC_BYTE)
+        {
+            self.mpt_packets += 1;
+            self.ts_packets += data.len() / TS_PACKET_LEN;
+        }
+
+        if payload[0] & SEQUENCE_VALID == 0 {
+            return;
+        }
+        let sequence = u16::from_be_bytes([payload[2], payload[3]]);
+        let expected = match self.sequence {
+            Some(last) => last.wrapping_add(1),
+            None => sequence,
+        };
+        match sequence.wrapping_sub(expected) {
+            0 => self.sequence = Some(sequence),
+            skipped if skipped < 0x8000 => {
+                self.gaps += 1;
+                self.lost += usize::from(skipped);
+                self.sequence = Some(sequence);
+            }
+            _ => self.out_of_order += 1,
+        }
+    }
+
+    /// `MPT` when every packet carries MPEG-TS, `PSP` when none does.
+    fn payload(&self) -> &'static str {
+        match self.mpt_packets {
+            0 => "PSP",
+            n if n == self.packets => "MPT",
+            _ => "mixed",
+        }
+    }
+}
+
+/// DEPI and UEPI data packets of the captures by L2TPv3 Session ID, with the sessions
+/// configured through StaticPwConfig (58) and L2TPv3 to tell which ones carried traffic.
+#[derive(Debug, Default)]
+pub struct DepiTraffic {
+    flows: BTreeMap<FlowKey, Flow>,
+    pseudowires: Pseudowires,
+    sessions: L2tpSessions,
+}
+
+impl DepiTraffic {
+    pub fn add(&mut self, l2tp: &CapturedL2tp) {
+        match &l2tp.message {
+            L2tpMessage::Data {
+                session_id,
+                payload,
+            } => {
+                let key = FlowKey {
+                    src: l2tp.src,
+                    dst: l2tp.dst,
+                    session_id: *session_id,
+                };
+                self.flows.entry(key).or_default().add(l2tp.time, payload);
+            }
+            L2tpMessage::Control { .. } => self.sessions.add(l2tp),
+        }
+    }
+
+    /// A row per session and direction with data, then a row per configured session
+    /// without any. Sessions losing packets are `LOSS`.
+    pub fn table(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "src",
+            "dst",
+            "session_id",
+            "configured_by",
+            "pw_type",
+            "payload",
+            "packets",
+            "bytes",
+            "ts_packets",
+            "first_seen",
+            "last_seen",
+            "seq_gaps",
+            "lost",
+            "out_of_order",
+            "status",
+        ]);
+
+        let mut expected = self.pseudowires.expected_sessions();
+        expected.extend(self.sessions.expected_sessions());
+
+        for (key, flow) in &self.flows {
+            let matching: Vec<&ExpectedSession> =
+                expected.iter().filter(|e| e.matches(key)).collect();
+            let mut labels: Vec<&str> = matching.iter().map(|e| e.label.as_str()).collect();
+            labels.dedup();
+            let status = if flow.lost > 0 {
+                "LOSS"
+            } else if matching.is_empty() {
+                "not configured"
+            } else {
+                "ok"
+            };
+
+            table.push(vec![
+                Value::from(key.src.to_string()),
+                Value::from(key.dst.to_string()),
+                Value::from(key.session_id),
+                Value::from(labels.join(", ")),
+                matching
+                    .iter()
+                    .find_map(|e| e.pw_type.clone())
+                    .map_or(Value::Null, Value::from),
+                Value::from(flow.payload()),
+                Value::from(flow.packets),
+                Value::from(flow.bytes),
+                Value::from(flow.ts_packets),
+                Value::from(format_time(flow.first)),
+                Value::from(format_time(flow.last)),
+                Value::from(flow.gaps),
+                Value::from(flow.lost),
+                Value::from(flow.out_of_order),
+                Value::from(status),
+            ]);
+        }
+
+        // Configured sessions no data was seen for, in either direction.
+        let mut idle: Vec<&ExpectedSession> = Vec::new();
+        for e in &expected {
+            let label_seen = |e: &ExpectedSession| {
+                expected
+                    .iter()
+                    .filter(|other| other.label == e.label)
+                    .any(|other| self.flows.keys().any(|k| other.matches(k)))
+            };
+            if !label_seen(e) && !idle.iter().any(|i| i.label == e.label) {
+                idle.push(e);
+            }
+        }
+        let address = |a: Option<IpAddr>| a.map_or(Value::Null, |a| Value::from(a.to_string()));
+        for e in idle {
+            table.push(vec![
+                address(e.src),
+                address(e.dst),
+                Value::from(e.session_id),
+                Value::from(e.label.clone()),
+                e.pw_type.clone().map_or(Value::Null, Value::from),
+                Value::Null,
+                Value::from(0),
+                Value::from(0),
+                Value::from(0),
+                Value::Null,
+                Value::Null,
+                Value::from(0),
+                Value::from(0),
+                Value::from(0),
+                Value::from("no traffic"),
+            ]);
+        }
+
+        table
+    }
+}
+
+/// DEPI and UEPI data of `captures`, read in order, with the StaticPwConfig and L2TPv3
+/// sessions they configure.
+pub fn depi_traffic(captures: &[&Path]) -> Result<DepiTraffic, ReportError> {
+    let mut traffic = DepiTraffic::default();
+    for path in captures {
+        read_messages(path, |msg| traffic.pseudowires.add(msg))?;
+        read_l2tp(path, |l2tp| traffic.add(l2tp))?;
+    }
+
+    Ok(traffic)
+}
@@ -0,0 +1,337 @@
+use pnet::util::MacAddr;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::reports::{format_time, read_messages, CapturedMessage, ReportError, ReportTable, Tlv};
+
+/// What an event means for the failover in progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Effect {
+    /// The active Core is lost or a handover starts.
+    Lost,
+    /// A backup Core took over.
+    TakenOver,
+    /// A Core is reported active, which ends a failover in progress only.
+    Active,
+    /// The RPD is back with the same Core.
+    Reconnected,
+    /// The handover did not complete.
+    Failed,
+    None,
+}
+
+/// A handover or failover related event, in capture order.
+#[derive(Debug, Clone)]
+struct Event {
+    frame: usize,
+    time: i64,
+    kind: String,
+    /// CoreId or address of the Core the event is about.
+    core: Option<String>,
+    detail: String,
+    effect: Effect,
+}
+
+/// A failover: from the loss of the active Core to the Core taking over.
+#[derive(Debug, Clone, Default)]
+struct Failover {
+    lost_core: Option<String>,
+    cause: Option<String>,
+    start: Option<(i64, usize)>,
+    new_core: Option<String>,
+    end: Option<(i64, usize)>,
+    outcome: &'static str,
+}
+
+#[derive(Debug, Clone)]
+struct RpdHistory {
+    ip: IpAddr,
+    mac: MacAddr,
+    events: Vec<Event>,
+    /// Last RpdGcpBackupCoreStatus of every backup Core, to report the transitions only.
+    backup_status: BTreeMap<String, String>,
+}
+
+/// Sub-TLV with a value.
+fn value<'a>(tlv: &'a Tlv, name: &str) -> Option<&'a Tlv> {
+    tlv.get(name).filter(|t| !t.value.is_empty())
+}
+
+impl RpdHistory {
+    fn push(
+        &mut self,
+        msg: &CapturedMessage,
+        kind: &str,
+        core: Option<String>,
+        detail: String,
+        effect: Effect,
+    ) {
+        self.events.push(Event {
+            frame: msg.frame,
+            time: msg.time,
+            kind: kind.to_string(),
+            core,
+            detail,
+            effect,
+        });
+    }
+
+    fn notification(&mut self, msg: &CapturedMessage, tlv: &Tlv) {
+        let kind = match value(tlv, "NotificationType") {
+            Some(t) => t.label(),
+            None => return,
+        };
+
+        match kind.as_str() {
+            "TimeOutNotification" => {
+                let core = value(tlv, "CoreTimedOutIpAddress").map(Tlv::text);
+                let detail = value(tlv, "SpecificTimeOut")
+                    .map_or_else(String::new, |t| format!("SpecificTimeOut {}", t.text()));
+                self.push(msg, &kind, core, detail, Effect::Lost);
+            }
+            "ReconnectNotification" => {
+                let core = Some(msg.core().to_string());
+                self.push(msg, &kind, core, String::new(), Effect::Reconnected);
+            }
+            "HandoverNotification" => {
+                let core = Some(msg.core().to_string());
+                self.push(msg, &kind, core, String::new(), Effect::TakenOver);
+            }
+            "AuxCoreGcpStatusNotification" => {
+                let status = match value(tlv, "AuxCoreGcpConnectionStatus") {
+                    Some(t) => t,
+                    None => return,
+                };
+                let core = value(tlv, "AuxCoreId")
+                    .or_else(|| value(tlv, "AuxCoreIpAddress"))
+                    .map(Tlv::text);
+                // AuxCoreGcpConnectionStatus values, see the enum of the dictionary.
+                let effect = match status.number() {
+                    0 | 2 | 3 | 8 => Effect::Lost,
+                    4 => Effect::TakenOver,
+                    1 => Effect::Reconnected,
+                    5..=7 => Effect::Failed,
+                    _ => Effect::None,
+                };
+                self.push(msg, &kind, core, status.label(), effect);
+            }
+            _ => (),
+        }
+    }
+
+    fn handover_control(&mut self, msg: &CapturedMessage, tlv: &Tlv) {
+        // Only the writes of the Core initiating the handover are events.
+        if value(tlv, "GcpHandoverControlAction").is_none_or(|t| t.number() != 1) {
+            return;
+        }
+
+        let oos_core = value(tlv, "OosCore").map(Tlv::text);
+        let mut detail = Vec::new();
+        if let Some(t) = value(tlv, "NewActiveCore") {
+            detail.push(format!("NewActiveCore {}", t.text()));
+        }
+        if let Some(t) = value(tlv, "L2tpv3") {
+            detail.push(format!("L2tpv3 {}", t.label()));
+        }
+        self.push(
+            msg,
+            "GcpHandoverControl",
+            oos_core,
+            detail.join(", "),
+            Effect::Lost,
+        );
+    }
+
+    fn backup_core_status(&mut self, msg: &CapturedMessage, tlv: &Tlv) {
+        let (core_id, status) = match (value(tlv, "CoreId"), value(tlv, "RpdGcpBackupCoreStatus")) {
+            (Some(id), Some(status)) => (id.text(), status),
+            _ => return,
+        };
+
+        let label = status.label();
+        let previous = self.backup_status.insert(core_id.clone(), label.clone());
+        if previous.as_ref() == Some(&label) {
+            return;
+        }
+
+        // RpdGcpBackupCoreStatusType values: 2 Active, 5 Handover. The first status seen
+        // can be the state before the capture started.
+        let effect = match status.number() {
+            2 if previous.is_none() => Effect::Active,
+            2 => Effect::TakenOver,
+            5 => Effect::Lost,
+            _ => Effect::None,
+        };
+        let detail = match previous {
+            Some(previous) => format!("{previous} -> {label}"),
+            None => label,
+        };
+        self.push(msg, "RpdBackupCoreStatus", Some(core_id), detail, effect);
+    }
+
+    /// Pairs the losses with the Core taking over, in event order.
+    fn failovers(&self) -> Vec<Failover> {
+        let mut failovers = Vec::new();
+        let mut current: Option<Failover> = None;
+
+        for event in &self.events {
+            let at = Some((event.time, event.frame));
+            match event.effect {
+                Effect::Lost => {
+                    let failover = current.get_or_insert_with(|| Failover {
+                        lost_core: event.core.clone(),
+                        cause: Some(event.kind.clone()),
+                        start: at,
+                        ..Failover::default()
+                    });
+                    failover.lost_core = failover.lost_core.clone().or(event.core.clone());
+                }
+                Effect::Active if current.is_none() => (),
+                Effect::TakenOver | Effect::Active | Effect::Reconnected | Effect::Failed => {
+                    let mut failover = current.take().unwrap_or_default();
+                    failover.new_core = event.core.clone();
+                    failover.end = at;
+                    failover.outcome = match event.effect {
+                        Effect::TakenOver | Effect::Active => "taken over",
+                        Effect::Reconnected => "reconnected",
+                        _ => "failed",
+                    };
+                    if event.effect == Effect::Failed {
+                        failover.cause = Some(format!(
+                            "{}{}",
+                            failover
+                                .cause
+                                .map_or_else(String::new, |c| format!("{c}, ")),
+                            event.detail
+                        ));
+                    }
+                    failovers.push(failover);
+                }
+                Effect::None => (),
+            }
+        }
+
+        if let Some(mut failover) = current {
+            failover.outcome = "unresolved";
+            failovers.push(failover);
+        }
+
+        failovers
+    }
+}
+
+/// Handover and failover events of every RPD of the captures: GcpHandoverControl (109),
+/// the Handover, Reconnect, TimeOut and AuxCoreGcpStatus GeneralNotifications (86) and the
+/// RpdGcpBackupCoreStatus (107.3) transitions.
+#[derive(Debug, Default)]
+pub struct FailoverTracker {
+    rpds: BTreeMap<(IpAddr, [u8; 6]), RpdHistory>,
+}
+
+impl FailoverTracker {
+    pub fn add(&mut self, msg: &CapturedMessage) {
+        let (ip, mac) = msg.rpd();
+        let rpd = self
+            .rpds
+            .entry((ip, mac.octets()))
+            .or_insert_with(|| RpdHistory {
+                ip,
+                mac,
+                events: Vec::new(),
+                backup_status: BTreeMap::new(),
+            });
+
+        for tlv in msg.tlvs("GeneralNotification") {
+            rpd.notification(msg, tlv);
+        }
+        for tlv in msg.tlvs("GcpHandoverControl") {
+            rpd.handover_control(msg, tlv);
+        }
+        for tlv in msg.tlvs("RpdBackupCoreStatus") {
+            rpd.backup_core_status(msg, tlv);
+        }
+    }
+
+    /// Every event, by RPD then in capture order.
+    pub fn events(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "rpd_ip", "rpd_mac", "time", "frame", "event", "core", "detail",
+        ]);
+
+        for rpd in self.rpds.values() {
+            for event in &rpd.events {
+                table.push(vec![
+                    Value::from(rpd.ip.to_string()),
+                    Value::from(rpd.mac.to_string()),
+                    Value::from(format_time(event.time)),
+                    Value::from(event.frame),
+                    Value::from(event.kind.clone()),
+                    event.core.clone().map_or(Value::Null, Value::from),
+                    Value::from(event.detail.clone()),
+                ]);
+            }
+        }
+
+        table
+    }
+
+    /// A row per failover: the Core lost and when, the Core taking over and when, and the gap.
+    pub fn failovers(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "rpd_ip",
+            "rpd_mac",
+            "lost_core",
+            "cause",
+            "lost_at",
+            "lost_frame",
+            "new_core",
+            "recovered_at",
+            "recovered_frame",
+            "gap_ms",
+            "outcome",
+        ]);
+
+        let time =
+            |at: Option<(i64, usize)>| at.map_or(Value::Null, |(t, _)| Value::from(format_time(t)));
+        let frame = |at: Option<(i64, usize)>| at.map_or(Value::Null, |(_, f)| Value::from(f));
+        let text = |s: &Option<String>| s.clone().map_or(Value::Null, Value::from);
+
+        for rpd in self.rpds.values() {
+            for failover in rpd.failovers() {
+                let gap = match (failover.start, failover.end) {
+                    (Some((start, _)), Some((end, _))) => {
+                        Value::from((end - start) as f64 / 1000.0)
+                    }
+                    _ => Value::Null,
+                };
+                table.push(vec![
+                    Value::from(rpd.ip.to_string()),
+                    Value::from(rpd.mac.to_string()),
+                    text(&failover.lost_core),
+                    text(&failover.cause),
+                    time(failover.start),
+                    frame(failover.start),
+                    text(&failover.new_core),
+                    time(failover.end),
+                    frame(failover.end),
+                    gap,
+                    Value::from(failover.outcome),
+                ]);
+            }
+        }
+
+        table
+    }
+}
+
+/// Failover events of the RPDs in `captures`, read in order.
+pub fn track_failovers(captures: &[&Path]) -> Result<FailoverTracker, ReportError> {
+    let mut tracker = FailoverTracker::default();
+    for path in captures {
+        read_messages(path, |msg| tracker.add(msg))?;
+    }
+
+    Ok(tracker)
+}
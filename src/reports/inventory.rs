@@ -0,0 +1,173 @@
+use pnet::util::MacAddr;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::parsers::tlv_def::DataType;
+use crate::reports::{format_time, read_messages, CapturedMessage, ReportError, ReportTable, Tlv};
+
+/// RpdIdentification (50.19) sub-TLVs reported, with their column.
+const IDENTIFICATION: [(&str, &str); 9] = [
+    ("vendor", "VendorName"),
+    ("vendor_id", "VendorId"),
+    ("model", "ModelNumber"),
+    ("serial_number", "SerialNumber"),
+    ("hw_revision", "HwRevision"),
+    ("sw_version", "CurrentSwVersion"),
+    ("sw_image", "CurrentSwImageName"),
+    ("boot_rom", "BootRomVersion"),
+    ("rcp_version", "RpdRcpProtocolVersion"),
+];
+
+/// DeviceLocation (50.24) sub-TLVs reported, with their column.
+const LOCATION: [(&str, &str); 3] = [
+    ("location", "DeviceLocationDescription"),
+    ("latitude", "GeoLocationLatitude"),
+    ("longitude", "GeoLocationLongitude"),
+];
+
+/// RpdCapabilities port and channel counts reported, with their column.
+const CAPACITY: [(&str, &str); 12] = [
+    ("ds_rf_ports", "NumDsRfPorts"),
+    ("us_rf_ports", "NumUsRfPorts"),
+    ("ten_ge_ports", "NumTenGeNsPorts"),
+    ("one_ge_ports", "NumOneGeNsPorts"),
+    ("ds_scqam_channels", "NumDsScQamChannels"),
+    ("ds_ofdm_channels", "NumDsOfdmChannels"),
+    ("us_scqam_channels", "NumUsScQamChannels"),
+    ("us_ofdma_channels", "NumUsOfdmaChannels"),
+    ("ds_oob_channels", "NumDsOob55d1Channels"),
+    ("us_oob_channels", "NumUsOob55d1Channels"),
+    ("ndf_channels", "NumNdfChannels"),
+    ("ndr_channels", "NumNdrChannels"),
+];
+
+/// What the captures tell about an RPD. Values reported more than once keep the latest.
+#[derive(Debug, Clone)]
+struct RpdRecord {
+    ip: IpAddr,
+    mac: MacAddr,
+    /// DeviceMacAddress (50.19.4), preferred to the Ethernet address, which can be a router's.
+    device_mac: Option<String>,
+    values: BTreeMap<&'static str, Value>,
+    first_seen: i64,
+    last_seen: i64,
+}
+
+/// Identification, location and capacity of every RPD of the captures, from the
+/// RpdCapabilities the RPDs report. RPDs are keyed by IP and MAC address.
+#[derive(Debug, Default)]
+pub struct Inventory {
+    rpds: BTreeMap<(IpAddr, [u8; 6]), RpdRecord>,
+}
+
+impl Inventory {
+    /// Reads the RpdCapabilities of the message. An RPD gets a row once it reports its
+    /// identification, location or capacity, so Cores and read requests add none.
+    pub fn add(&mut self, msg: &CapturedMessage) {
+        let (ip, mac) = msg.rpd();
+        let key = (ip, mac.octets());
+        if let Some(rpd) = self.rpds.get_mut(&key) {
+            rpd.last_seen = msg.time;
+        }
+
+        // The Core reads RpdCapabilities with empty values, those are skipped.
+        for caps in msg.tlvs("RpdCapabilities").filter(|c| reports_values(c)) {
+            let rpd = self.rpds.entry(key).or_insert_with(|| RpdRecord {
+                ip,
+                mac,
+                device_mac: None,
+                values: BTreeMap::new(),
+                first_seen: msg.time,
+                last_seen: msg.time,
+            });
+            if let Some(id) = caps.get("RpdIdentification") {
+                if let Some(mac) = id.get("DeviceMacAddress") {
+                    rpd.device_mac = Some(mac.text());
+                }
+                rpd.set_values(&IDENTIFICATION, id);
+            }
+            if let Some(location) = caps.get("DeviceLocation") {
+                rpd.set_values(&LOCATION, location);
+            }
+            for (column, name) in CAPACITY {
+                if let Some(t) = caps.get(name).filter(|t| !t.value.is_empty()) {
+                    rpd.values.insert(column, Value::from(t.number()));
+                }
+            }
+        }
+    }
+
+    pub fn table(&self) -> ReportTable {
+        let mut columns = vec!["ip", "mac"];
+        columns.extend(IDENTIFICATION.iter().map(|(c, _)| *c));
+        columns.extend(LOCATION.iter().map(|(c, _)| *c));
+        columns.extend(CAPACITY.iter().map(|(c, _)| *c));
+        columns.extend(["first_seen", "last_seen"]);
+
+        let mut table = ReportTable::new(&columns);
+        for rpd in self.rpds.values() {
+            let mut row = vec![
+                Value::from(rpd.ip.to_string()),
+                Value::from(
+                    rpd.device_mac
+                        .clone()
+                        .unwrap_or_else(|| rpd.mac.to_string()),
+                ),
+            ];
+            row.extend(
+                columns[2..columns.len() - 2]
+                    .iter()
+                    .map(|c| rpd.values.get(c).cloned().unwrap_or(Value::Null)),
+            );
+            row.push(Value::from(format_time(rpd.first_seen)));
+            row.push(Value::from(format_time(rpd.last_seen)));
+            table.push(row);
+        }
+
+        table
+    }
+}
+
+impl RpdRecord {
+    fn set_values(&mut self, fields: &[(&'static str, &str)], parent: &Tlv) {
+        for (column, name) in fields {
+            if let Some(t) = parent.get(name).filter(|t| !t.value.is_empty()) {
+                let value = match t.def.map(|d| &d.data_type) {
+                    Some(
+                        DataType::UnsignedByte | DataType::UnsignedShort | DataType::UnsignedInt,
+                    ) => Value::from(t.number()),
+                    _ => Value::from(t.text()),
+                };
+                self.values.insert(column, value);
+            }
+        }
+    }
+}
+
+/// Whether RpdCapabilities hold a value the inventory reports, rather than the empty TLVs
+/// of a read.
+fn reports_values(caps: &Tlv) -> bool {
+    let filled = |parent: Option<&Tlv>, fields: &[(&str, &str)]| {
+        parent.is_some_and(|p| {
+            fields
+                .iter()
+                .any(|(_, name)| p.get(name).is_some_and(|t| !t.value.is_empty()))
+        })
+    };
+
+    filled(caps.get("RpdIdentification"), &IDENTIFICATION)
+        || filled(caps.get("DeviceLocation"), &LOCATION)
+        || filled(Some(caps), &CAPACITY)
+}
+
+/// Inventory of the RPDs in `captures`, read in order.
+pub fn inventory(captures: &[&Path]) -> Result<Inventory, ReportError> {
+    let mut inventory = Inventory::default();
+    for path in captures {
+        read_messages(path, |msg| inventory.add(msg))?;
+    }
+
+    Ok(inventory)
+}
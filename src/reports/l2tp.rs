@@ -0,0 +1,402 @@
+use serde_json::Value;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::parsers::l2tp_parser::{
+    L2tpMessage, ASSIGNED_CONNECTION_ID, CABLELABS_AVP_VENDOR, CIRCUIT_STATUS, DEPI_LOCAL_MTU,
+    DEPI_PW_SUBTYPE, DEPI_REMOTE_MTU, DEPI_REMOTE_MULTICAST_JOIN, HOST_NAME, IETF_VENDOR,
+    L2_SPECIFIC_SUBLAYER, LOCAL_SESSION_ID, PSEUDOWIRE_TYPE, REMOTE_SESSION_ID, RESULT_CODE,
+};
+use crate::reports::depi::ExpectedSession;
+use crate::reports::{format_time, read_l2tp, CapturedL2tp, ReportError, ReportTable};
+
+// Control message types.
+const SCCRQ: u16 = 1;
+const SCCRP: u16 = 2;
+const SCCCN: u16 = 3;
+const STOP_CCN: u16 = 4;
+const HELLO: u16 = 6;
+const ICRQ: u16 = 10;
+const ICRP: u16 = 11;
+const ICCN: u16 = 12;
+const CDN: u16 = 14;
+const SLI: u16 = 16;
+
+/// A control connection between two LCCEs, from its SCCRQ to its StopCCN.
+#[derive(Debug, Clone)]
+struct Connection {
+    /// Sender of the SCCRQ, usually the Core.
+    initiator: IpAddr,
+    responder: IpAddr,
+    /// Control Connection IDs assigned by the initiator and by the responder.
+    ids: [Option<u64>; 2],
+    host_names: [Option<String>; 2],
+    state: &'static str,
+    started: Option<i64>,
+    established: Option<i64>,
+    stopped: Option<i64>,
+    /// Result Code of the StopCCN.
+    result: Option<String>,
+    hellos: usize,
+}
+
+/// A session of a control connection, from its ICRQ to its CDN.
+#[derive(Debug, Clone)]
+struct Session {
+    /// Index of the control connection in `L2tpSessions::connections`.
+    connection: usize,
+    /// Session IDs assigned by the initiator and by the responder of the control connection.
+    ids: [Option<u64>; 2],
+    pw_type: Option<String>,
+    subtype: Option<String>,
+    sublayer: Option<String>,
+    local_mtu: Option<u64>,
+    remote_mtu: Option<u64>,
+    /// DepiRemoteMulticastJoin source and group of every join.
+    multicast: Vec<String>,
+    circuit_status: Option<String>,
+    state: &'static str,
+    requested: Option<i64>,
+    established: Option<i64>,
+    closed: Option<i64>,
+    /// Result Code of the CDN, or of the StopCCN closing the control connection.
+    result: Option<String>,
+}
+
+impl Session {
+    fn update(&mut self, message: &L2tpMessage) {
+        let label = |vendor, typ| message.avp(vendor, typ).map(|a| a.label());
+        let number = |vendor, typ| message.avp(vendor, typ).and_then(|a| a.number());
+
+        if let Some(t) = label(IETF_VENDOR, PSEUDOWIRE_TYPE) {
+            self.pw_type = Some(t);
+        }
+        if let Some(t) = label(CABLELABS_AVP_VENDOR, DEPI_PW_SUBTYPE) {
+            self.subtype = Some(t);
+        }
+        if let Some(t) = label(IETF_VENDOR, L2_SPECIFIC_SUBLAYER) {
+            self.sublayer = Some(t);
+        }
+        if let Some(t) = label(IETF_VENDOR, CIRCUIT_STATUS) {
+            self.circuit_status = Some(t);
+        }
+        if let Some(n) = number(CABLELABS_AVP_VENDOR, DEPI_LOCAL_MTU) {
+            self.local_mtu = Some(n);
+        }
+        if let Some(n) = number(CABLELABS_AVP_VENDOR, DEPI_REMOTE_MTU) {
+            self.remote_mtu = Some(n);
+        }
+        for join in message.avps(CABLELABS_AVP_VENDOR, DEPI_REMOTE_MULTICAST_JOIN) {
+            let join = join.text();
+            if !self.multicast.contains(&join) {
+                self.multicast.push(join);
+            }
+        }
+    }
+}
+
+/// L2TPv3 control connections and sessions of the captures, DEPI and UEPI included, from
+/// the SCCRQ/SCCRP/SCCCN/StopCCN and ICRQ/ICRP/ICCN/CDN exchanges.
+#[derive(Debug, Default)]
+pub struct L2tpSessions {
+    connections: Vec<Connection>,
+    sessions: Vec<Session>,
+}
+
+impl L2tpSessions {
+    /// Control connection in progress between the two ends of the message.
+    fn connection(&self, l2tp: &CapturedL2tp) -> Option<usize> {
+        self.connections.iter().rposition(|c| {
+            c.state != "stopped"
+                && ((c.initiator == l2tp.src && c.responder == l2tp.dst)
+                    || (c.initiator == l2tp.dst && c.responder == l2tp.src))
+        })
+    }
+
+    fn new_connection(&mut self, initiator: IpAddr, responder: IpAddr) -> usize {
+        self.connections.push(Connection {
+            initiator,
+            responder,
+            ids: [None, None],
+            host_names: [None, None],
+            state: "seen",
+            started: None,
+            established: None,
+            stopped: None,
+            result: None,
+            hellos: 0,
+        });
+        self.connections.len() - 1
+    }
+
+    /// Session of the message: the sender assigned its LocalSessionId, the peer its
+    /// RemoteSessionId.
+    fn session(&self, connection: usize, sender: usize, message: &L2tpMessage) -> Option<usize> {
+        let id = |typ| {
+            message
+                .avp(IETF_VENDOR, typ)
+                .and_then(|a| a.number())
+                .filter(|id| *id != 0)
+        };
+        let (local, remote) = (id(LOCAL_SESSION_ID), id(REMOTE_SESSION_ID));
+
+        self.sessions.iter().rposition(|s| {
+            s.connection == connection
+                && s.state != "closed"
+                && ((local.is_some() && s.ids[sender] == local)
+                    || (remote.is_some() && s.ids[1 - sender] == remote))
+        })
+    }
+
+    pub fn add(&mut self, l2tp: &CapturedL2tp) {
+        let message = &l2tp.message;
+        let typ = match message.message_type() {
+            Some(typ) => typ,
+            None => return,
+        };
+        let at = l2tp.time;
+        let number = |typ| message.avp(IETF_VENDOR, typ).and_then(|a| a.number());
+        let result = message.avp(IETF_VENDOR, RESULT_CODE).map(|a| a.text());
+
+        let connection = match (typ, self.connection(l2tp)) {
+            // A retransmitted SCCRQ has the Control Connection ID of the first one.
+            (SCCRQ, Some(i))
+                if self.connections[i].ids[0].is_some()
+                    && self.connections[i].ids[0] == number(ASSIGNED_CONNECTION_ID) =>
+            {
+                i
+            }
+            (SCCRQ, _) => self.new_connection(l2tp.src, l2tp.dst),
+            (_, Some(i)) => i,
+            // The capture missed the SCCRQ, the initiator is the sender of the requests.
+            (ICRQ | ICCN, None) => self.new_connection(l2tp.src, l2tp.dst),
+            (_, None) => self.new_connection(l2tp.dst, l2tp.src),
+        };
+
+        let conn = &mut self.connections[connection];
+        let sender = usize::from(conn.initiator != l2tp.src);
+        if let Some(id) = number(ASSIGNED_CONNECTION_ID) {
+            conn.ids[sender] = Some(id);
+        }
+        if let Some(name) = message.avp(IETF_VENDOR, HOST_NAME) {
+            conn.host_names[sender] = Some(name.text());
+        }
+
+        match typ {
+            SCCRQ => {
+                conn.state = "requested";
+                conn.started.get_or_insert(at);
+            }
+            SCCRP => conn.state = "replied",
+            SCCCN => {
+                conn.state = "established";
+                conn.established.get_or_insert(at);
+            }
+            STOP_CCN => {
+                conn.state = "stopped";
+                conn.stopped = Some(at);
+                conn.result = result.clone();
+                for session in self.sessions.iter_mut() {
+                    if session.connection == connection && session.state != "closed" {
+                        session.state = "closed";
+                        session.closed = Some(at);
+                        session.result = Some(match &result {
+                            Some(result) => format!("StopCCN {result}"),
+                            None => "StopCCN".to_string(),
+                        });
+                    }
+                }
+            }
+            HELLO => conn.hellos += 1,
+            ICRQ | ICRP | ICCN | CDN | SLI => {
+                let index = match (typ, self.session(connection, sender, message)) {
+                    (_, Some(i)) => i,
+                    (ICRQ, None) => {
+                        self.sessions.push(Session {
+                            connection,
+                            ids: [None, None],
+                            pw_type: None,
+                            subtype: None,
+                            sublayer: None,
+                            local_mtu: None,
+                            remote_mtu: None,
+                            multicast: Vec::new(),
+                            circuit_status: None,
+                            state: "requested",
+                            requested: Some(at),
+                            established: None,
+                            closed: None,
+                            result: None,
+                        });
+                        self.sessions.len() - 1
+                    }
+                    _ => return,
+                };
+
+                let session = &mut self.sessions[index];
+                if let Some(id) = number(LOCAL_SESSION_ID).filter(|id| *id != 0) {
+                    session.ids[sender] = Some(id);
+                }
+                session.update(message);
+                match typ {
+                    ICRP => session.state = "replied",
+                    ICCN => {
+                        session.state = "established";
+                        session.established.get_or_insert(at);
+                    }
+                    CDN => {
+                        session.state = "closed";
+                        session.closed = Some(at);
+                        session.result = result;
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// A row per session, with its pseudowire, DEPI MTU and multicast groups and the time
+    /// from its ICRQ to its ICCN.
+    pub fn sessions(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "initiator",
+            "responder",
+            "connection_ids",
+            "local_session_id",
+            "remote_session_id",
+            "pw_type",
+            "depi_pw_subtype",
+            "l2_sublayer",
+            "local_mtu",
+            "remote_mtu",
+            "multicast",
+            "circuit_status",
+            "state",
+            "requested_at",
+            "established_at",
+            "setup_ms",
+            "closed_at",
+            "result",
+        ]);
+
+        let time = |at: Option<i64>| at.map_or(Value::Null, |t| Value::from(format_time(t)));
+        let text = |s: &Option<String>| s.clone().map_or(Value::Null, Value::from);
+        let number = |n: Option<u64>| n.map_or(Value::Null, Value::from);
+        for session in &self.sessions {
+            let conn = &self.connections[session.connection];
+            let setup = match (session.requested, session.established) {
+                (Some(r), Some(e)) => Value::from((e - r) as f64 / 1000.0),
+                _ => Value::Null,
+            };
+            table.push(vec![
+                Value::from(conn.initiator.to_string()),
+                Value::from(conn.responder.to_string()),
+                Value::from(connection_ids(conn)),
+                number(session.ids[0]),
+                number(session.ids[1]),
+                text(&session.pw_type),
+                text(&session.subtype),
+                text(&session.sublayer),
+                number(session.local_mtu),
+                number(session.remote_mtu),
+                Value::from(session.multicast.join(" ")),
+                text(&session.circuit_status),
+                Value::from(session.state),
+                time(session.requested),
+                time(session.established),
+                setup,
+                time(session.closed),
+                text(&session.result),
+            ]);
+        }
+
+        table
+    }
+
+    /// A row per control connection.
+    pub fn connections(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "initiator",
+            "responder",
+            "connection_ids",
+            "initiator_host",
+            "responder_host",
+            "state",
+            "started_at",
+            "established_at",
+            "stopped_at",
+            "hellos",
+            "sessions",
+            "result",
+        ]);
+
+        let time = |at: Option<i64>| at.map_or(Value::Null, |t| Value::from(format_time(t)));
+        let text = |s: &Option<String>| s.clone().map_or(Value::Null, Value::from);
+        for (i, conn) in self.connections.iter().enumerate() {
+            let sessions = self.sessions.iter().filter(|s| s.connection == i).count();
+            table.push(vec![
+                Value::from(conn.initiator.to_string()),
+                Value::from(conn.responder.to_string()),
+                Value::from(connection_ids(conn)),
+                text(&conn.host_names[0]),
+                text(&conn.host_names[1]),
+                Value::from(conn.state),
+                time(conn.started),
+                time(conn.established),
+                time(conn.stopped),
+                Value::from(conn.hellos),
+                Value::from(sessions),
+                text(&conn.result),
+            ]);
+        }
+
+        table
+    }
+
+    /// Session IDs of every session, each with the LCCE it was assigned by: the data sent
+    /// to an LCCE carries the Session ID it assigned.
+    pub(crate) fn expected_sessions(&self) -> Vec<ExpectedSession> {
+        let mut expected = Vec::new();
+
+        for session in &self.sessions {
+            let conn = &self.connections[session.connection];
+            let ends = [conn.initiator, conn.responder];
+            let label = format!(
+                "L2TP {} {}/{}",
+                conn.initiator,
+                session.ids[0].unwrap_or(0),
+                session.ids[1].unwrap_or(0)
+            );
+            for (side, id) in session.ids.iter().enumerate() {
+                if let Some(id) = id {
+                    expected.push(ExpectedSession {
+                        session_id: *id,
+                        src: Some(ends[1 - side]),
+                        dst: Some(ends[side]),
+                        pw_type: session.pw_type.clone(),
+                        label: label.clone(),
+                    });
+                }
+            }
+        }
+
+        expected
+    }
+}
+
+/// `initiator/responder` Control Connection IDs, `?` when not seen.
+fn connection_ids(conn: &Connection) -> String {
+    let id = |id: Option<u64>| id.map_or_else(|| "?".to_string(), |id| id.to_string());
+    format!("{}/{}", id(conn.ids[0]), id(conn.ids[1]))
+}
+
+/// L2TPv3 control connections and sessions of `captures`, read in order.
+pub fn l2tp_sessions(captures: &[&Path]) -> Result<L2tpSessions, ReportError> {
+    let mut sessions = L2tpSessions::default();
+    for path in captures {
+        read_l2tp(path, |l2tp| sessions.add(l2tp))?;
+    }
+
+    Ok(sessions)
+}
@@ -0,0 +1,642 @@
+use chrono::{DateTime, SecondsFormat};
+use nom::multi::many0;
+use pcap_parser::data::{get_packetdata, PacketData};
+use pcap_parser::{Linktype, PcapBlockOwned};
+use pnet::util::MacAddr;
+use serde_json::Value;
+use std::fmt;
+use std::fs::File;
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::encoders::pcap_builder::GCP_PORT;
+use crate::parsers::date_and_time::{pcap_time, pcap_time_nanos};
+use crate::parsers::dictionary::dictionary;
+use crate::parsers::filter::ip_frame;
+use crate::parsers::gcp_parser::{rcp_offset, MessageId};
+use crate::parsers::l2tp_parser::{parse_l2tp, L2tpMessage, L2tpTransport};
+use crate::parsers::pcap_reader::{read_pcap, PcapReadError};
+use crate::parsers::ptp_parser::{parse_ptp, PtpMessage, PtpTransport, PTP_ETHERTYPE};
+use crate::parsers::reassembly::Reassembly;
+use crate::parsers::tlv_def::{DataType, TlvDef};
+use crate::parsers::tlv_parser::{parse_tlvs, BitField, FlagState};
+
+pub mod bringup;
+pub mod depi;
+pub mod failover;
+pub mod inventory;
+pub mod l2tp;
+pub mod perf;
+pub mod pseudowire;
+pub mod ptp;
+pub mod throttle;
+pub mod topology;
+
+#[derive(Debug)]
+pub enum ReportError {
+    Io(std::io::Error),
+    Pcap(String),
+    UnknownFormat(String),
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::Io(e) => write!(f, "{e}"),
+            ReportError::Pcap(e) => write!(f, "{e}"),
+            ReportError::UnknownFormat(s) => write!(f, "unknown report format '{s}'"),
+        }
+    }
+}
+
+impl From<PcapReadError> for ReportError {
+    fn from(e: PcapReadError) -> Self {
+        match e {
+            PcapReadError::Io(e) => ReportError::Io(e),
+            PcapReadError::Pcap(e) => ReportError::Pcap(format!("{e:?}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Aligned columns, for the terminal.
+    Table,
+    Csv,
+    /// An array with an object per row.
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = ReportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(ReportFormat::Table),
+            "csv" => Ok(ReportFormat::Csv),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(ReportError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Rows of a report, rendered as a table, CSV or JSON.
+/// Cells are JSON values so that numbers stay numbers in JSON; Null is an empty cell.
+/// The flags of a bit field are an array of `FlagState`, booleans in JSON and the names
+/// of the flags set otherwise.
+#[derive(Debug, Clone)]
+pub struct ReportTable {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl ReportTable {
+    pub fn new(columns: &[&'static str]) -> Self {
+        ReportTable {
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds a row, with a cell per column.
+    pub fn push(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    fn cell(value: &Value) -> String {
+        match value {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            // Flags of a bit field, the names of those set as in `Tlv::label`.
+            Value::Array(flags)
+                if !flags.is_empty() && flags.iter().all(|f| f["set"].is_boolean()) =>
+            {
+                flags
+                    .iter()
+                    .filter(|f| f["set"] == Value::Bool(true))
+                    .filter_map(|f| f["name"].as_str())
+                    .collect::<Vec<_>>()
+                    .join("+")
+            }
+            v => v.to_string(),
+        }
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Table => self.table(),
+            ReportFormat::Csv => self.csv(),
+            ReportFormat::Json => self.json(),
+        }
+    }
+
+    fn table(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(ReportTable::cell).collect())
+            .collect();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                rows.iter()
+                    .map(|r| r[i].chars().count())
+                    .fold(c.len(), usize::max)
+            })
+            .collect();
+
+        let line = |cells: Vec<&str>| {
+            let cells: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(c, w)| format!("{c:w$}"))
+                .collect();
+            format!("{}\n", cells.join("  ").trim_end())
+        };
+
+        let mut out = line(self.columns.clone());
+        let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        out.push_str(&line(rule.iter().map(String::as_str).collect()));
+        for row in &rows {
+            out.push_str(&line(row.iter().map(String::as_str).collect()));
+        }
+        out
+    }
+
+    fn csv(&self) -> String {
+        let escape = |s: String| {
+            if s.contains([',', '"', '\r', '\n']) {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            } else {
+                s
+            }
+        };
+
+        let mut out = format!("{}\n", self.columns.join(","));
+        for row in &self.rows {
+            let cells: Vec<String> = row.iter().map(|v| escape(ReportTable::cell(v))).collect();
+            out.push_str(&format!("{}\n", cells.join(",")));
+        }
+        out
+    }
+
+    fn json(&self) -> String {
+        let rows: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(c, v)| (c.to_string(), v.clone()))
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+
+        format!("{}\n", serde_json::to_string_pretty(&rows).unwrap())
+    }
+}
+
+/// `2021-10-05T13:04:05.123456Z` for a capture time in microseconds.
+pub fn format_time(micros: i64) -> String {
+    DateTime::from_timestamp_micros(micros).map_or_else(String::new, |t| {
+        t.to_rfc3339_opts(SecondsFormat::Micros, true)
+    })
+}
+
+/// A decoded RCP TLV, with its sub-TLVs when it is Complex.
+#[derive(Debug, Clone)]
+pub struct Tlv {
+    pub typ: u8,
+    pub value: Vec<u8>,
+    /// Dictionary definition, None for unknown TLVs.
+    pub def: Option<&'static TlvDef>,
+    pub sub: Vec<Tlv>,
+}
+
+impl Tlv {
+    fn parse(defs: &'static [TlvDef], input: &[u8]) -> Vec<Tlv> {
+        let (_, tlvs) = many0(parse_tlvs)(input).unwrap_or((&[], Vec::new()));
+
+        tlvs.iter()
+            .map(|t| {
+                let def = defs.iter().find(|d| d.typ == t.typ);
+                let sub = match def {
+                    Some(d) if d.data_type == DataType::Complex => Tlv::parse(&d.sub, t.val),
+                    _ => Vec::new(),
+                };
+                Tlv {
+                    typ: t.typ,
+                    value: t.val.to_vec(),
+                    def,
+                    sub,
+                }
+            })
+            .collect()
+    }
+
+    pub fn name(&self) -> String {
+        self.def
+            .map_or_else(|| self.typ.to_string(), |d| d.name.clone())
+    }
+
+    /// First sub-TLV with this name.
+    pub fn get(&self, name: &str) -> Option<&Tlv> {
+        self.sub
+            .iter()
+            .find(|t| t.def.is_some_and(|d| d.name == name))
+    }
+
+    /// All the sub-TLVs with this name, e.g. the entries of a table.
+    pub fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Tlv> {
+        self.sub
+            .iter()
+            .filter(move |t| t.def.is_some_and(|d| d.name == name))
+    }
+
+    /// Sub-TLV found by following names, e.g. `["RpdIdentification", "SerialNumber"]`.
+    pub fn find(&self, path: &[&str]) -> Option<&Tlv> {
+        path.iter().try_fold(self, |tlv, name| tlv.get(name))
+    }
+
+    /// Value as the decoder prints it.
+    pub fn text(&self) -> String {
+        match self.def {
+            Some(def) => def.value(&self.value),
+            None => self.value.iter().map(|b| format!("{b:02X}")).collect(),
+        }
+    }
+
+    /// Value without the enum number, and the names of the flags set for Bits,
+    /// e.g. `Active` and `Principal+DOCSIS`.
+    pub fn label(&self) -> String {
+        let def = match self.def {
+            Some(def) if !self.value.is_empty() && self.value.len() <= 4 => def,
+            _ => return self.text(),
+        };
+        let n = self.number();
+
+        match def.data_type {
+            DataType::Enum => def
+                .values
+                .get(&n.to_string())
+                .map_or_else(|| n.to_string(), |v| v.label()),
+            DataType::Bits => def
+                .flags
+                .iter()
+                .filter(|f| n & (1 << f.bit) != 0)
+                .map(|f| f.name.as_str())
+                .collect::<Vec<_>>()
+                .join("+"),
+            _ => self.text(),
+        }
+    }
+
+    /// Every flag of a Bits TLV with its state, None for the other TLVs.
+    pub fn flags(&self) -> Option<Vec<FlagState<'static>>> {
+        let def = self.def.filter(|d| d.data_type == DataType::Bits)?;
+        let field = BitField {
+            name: &def.name,
+            size: def.size? * 8,
+            flags: &def.flags,
+        };
+        field.flags(&self.value)
+    }
+
+    /// Value as an unsigned number, for values of up to 8 bytes.
+    pub fn number(&self) -> u64 {
+        self.value
+            .iter()
+            .take(8)
+            .fold(0, |n, b| (n << 8) | u64::from(*b))
+    }
+}
+
+/// One RCP sequence of a message: its Operation and TLVs.
+#[derive(Debug, Clone)]
+pub struct Sequence {
+    pub operation: Option<u8>,
+    pub tlvs: Vec<Tlv>,
+}
+
+impl Sequence {
+    pub fn get(&self, name: &str) -> Option<&Tlv> {
+        self.tlvs
+            .iter()
+            .find(|t| t.def.is_some_and(|d| d.name == name))
+    }
+
+    pub fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Tlv> {
+        self.tlvs
+            .iter()
+            .filter(move |t| t.def.is_some_and(|d| d.name == name))
+    }
+}
+
+/// A GCP message of a capture, with the frame it was in.
+#[derive(Debug, Clone)]
+pub struct CapturedMessage {
+    pub frame: usize,
+    /// Capture time in microseconds since epoch.
+    pub time: i64,
+    pub src: SocketAddr,
+    pub dst: SocketAddr,
+    /// MAC addresses are zero in captures without an Ethernet header, e.g. Linux cooked ones.
+    pub src_mac: MacAddr,
+    pub dst_mac: MacAddr,
+    pub message_id: MessageId,
+    pub transaction_id: u16,
+    /// RCP message type, for the messages carrying RCP.
+    pub rcp_type: Option<u8>,
+    pub sequences: Vec<Sequence>,
+}
+
+impl CapturedMessage {
+    /// Whether the RPD sent the message. The RPD is the client of the GCP connection,
+    /// the Core listens on port 8190.
+    pub fn from_rpd(&self) -> bool {
+        self.dst.port() == GCP_PORT || self.src.port() != GCP_PORT
+    }
+
+    /// Address and MAC of the RPD end of the connection.
+    pub fn rpd(&self) -> (IpAddr, MacAddr) {
+        match self.from_rpd() {
+            true => (self.src.ip(), self.src_mac),
+            false => (self.dst.ip(), self.dst_mac),
+        }
+    }
+
+    /// Address of the Core end of the connection.
+    pub fn core(&self) -> IpAddr {
+        match self.from_rpd() {
+            true => self.dst.ip(),
+            false => self.src.ip(),
+        }
+    }
+
+    /// TLVs with this name in every sequence of the message.
+    pub fn tlvs<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Tlv> {
+        self.sequences.iter().flat_map(move |s| s.all(name))
+    }
+}
+
+fn parse_message(
+    frame: usize,
+    time: i64,
+    addresses: ([SocketAddr; 2], [MacAddr; 2]),
+    msg: &[u8],
+) -> CapturedMessage {
+    let message_id = MessageId(msg[7]);
+    let body = &msg[10..];
+    let rcp = rcp_offset(message_id, body).map(|off| &body[off..]);
+
+    let sequences = match rcp.and_then(|r| r.get(3..)) {
+        Some(input) => {
+            let (_, seqs) = many0(parse_tlvs)(input).unwrap_or((&[], Vec::new()));
+            seqs.iter()
+                .map(|seq| {
+                    let tlvs = Tlv::parse(&dictionary().tlv, seq.val);
+                    let operation = tlvs
+                        .iter()
+                        .find(|t| t.typ == 11 && t.value.len() == 1)
+                        .map(|t| t.value[0]);
+                    Sequence { operation, tlvs }
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    CapturedMessage {
+        frame,
+        time,
+        src: addresses.0[0],
+        dst: addresses.0[1],
+        src_mac: addresses.1[0],
+        dst_mac: addresses.1[1],
+        message_id,
+        transaction_id: u16::from_be_bytes([body[0], body[1]]),
+        rcp_type: rcp.map(|r| r[0]),
+        sequences,
+    }
+}
+
+/// Network layer data of a frame, with the Ethernet header fields when the capture has them.
+struct Frame<'a> {
+    /// Source and destination MAC addresses, zero for link types without them.
+    macs: [MacAddr; 2],
+    ethertype: u16,
+    data: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// `None` for link types not carrying Ethernet or IP.
+    fn new(data: &'a [u8], linktype: Linktype, caplen: usize) -> Option<Self> {
+        match get_packetdata(data, linktype, caplen)? {
+            PacketData::L2(data) if data.len() >= 14 => Some(Frame {
+                macs: [mac(&data[6..12]), mac(&data[0..6])],
+                ethertype: u16::from_be_bytes([data[12], data[13]]),
+                data: &data[14..],
+            }),
+            PacketData::L3(ethertype, data) => Some(Frame {
+                macs: [MacAddr::zero(); 2],
+                ethertype,
+                data,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Calls `f` for every frame of a capture, in capture order, with its number and capture
+/// time in microseconds. The link type and the timestamp precision are the ones of the
+/// capture header. Returns the number of frames read.
+fn read_frames<F>(path: &Path, mut f: F) -> Result<usize, ReportError>
+where
+    F: FnMut(usize, i64, &Frame),
+{
+    let file = File::open(path).map_err(ReportError::Io)?;
+    let mut linktype = Linktype::ETHERNET;
+    let mut nanosecond = false;
+    let mut frames = 0;
+
+    read_pcap(file, false, |block| match block {
+        PcapBlockOwned::LegacyHeader(hdr) => {
+            linktype = hdr.network;
+            nanosecond = hdr.is_nanosecond_precision();
+        }
+        PcapBlockOwned::Legacy(b) => {
+            frames += 1;
+            let time = match nanosecond {
+                true => pcap_time_nanos(b.ts_sec, b.ts_usec),
+                false => pcap_time(b.ts_sec, b.ts_usec),
+            };
+            let caplen = (b.caplen as usize).min(b.data.len());
+            if let Some(frame) = Frame::new(b.data, linktype, caplen) {
+                f(frames, time.timestamp_micros(), &frame);
+            }
+        }
+        PcapBlockOwned::NG(_) => unreachable!(),
+    })?;
+
+    Ok(frames)
+}
+
+/// Calls `f` for every whole GCP message of a capture, in capture order.
+/// Messages split over TCP segments are reported with the frame completing them.
+/// Returns the number of frames read.
+pub fn read_messages<F>(path: &Path, mut f: F) -> Result<usize, ReportError>
+where
+    F: FnMut(&CapturedMessage),
+{
+    let mut reassembly = Reassembly::default();
+    read_frames(path, |frame, time, data| {
+        messages_of_frame(frame, time, data, &mut reassembly, &mut f)
+    })
+}
+
+/// An L2TPv3 control or data message of a capture, with the frame it was in.
+#[derive(Debug, Clone)]
+pub struct CapturedL2tp<'a> {
+    pub frame: usize,
+    /// Capture time in microseconds since epoch.
+    pub time: i64,
+    pub src: IpAddr,
+    pub dst: IpAddr,
+    pub src_mac: MacAddr,
+    pub dst_mac: MacAddr,
+    pub transport: L2tpTransport,
+    pub message: L2tpMessage<'a>,
+}
+
+/// Calls `f` for every L2TPv3 message of a capture, over IP or UDP, in capture order.
+/// Returns the number of frames read.
+pub fn read_l2tp<F>(path: &Path, mut f: F) -> Result<usize, ReportError>
+where
+    F: FnMut(&CapturedL2tp),
+{
+    read_frames(path, |frame, time, data| {
+        let ip = match ip_frame(data.data) {
+            Some(ip) => ip,
+            None => return,
+        };
+        let (transport, packet) = match &ip.l2tp {
+            Some(l2tp) => l2tp,
+            None => return,
+        };
+
+        if let Ok((_, message)) = parse_l2tp(packet, *transport) {
+            f(&CapturedL2tp {
+                frame,
+                time,
+                src: ip.addresses[0],
+                dst: ip.addresses[1],
+                src_mac: data.macs[0],
+                dst_mac: data.macs[1],
+                transport: *transport,
+                message,
+            });
+        }
+    })
+}
+
+/// A PTP message of a capture, with the frame it was in. Over Ethernet it has no addresses.
+#[derive(Debug, Clone)]
+pub struct CapturedPtp<'a> {
+    pub frame: usize,
+    /// Capture time in microseconds since epoch.
+    pub time: i64,
+    pub src: Option<IpAddr>,
+    pub dst: Option<IpAddr>,
+    pub src_mac: MacAddr,
+    pub dst_mac: MacAddr,
+    pub transport: PtpTransport,
+    pub message: PtpMessage<'a>,
+}
+
+/// Calls `f` for every PTP version 2 message of a capture, over Ethernet or UDP, in capture
+/// order. Returns the number of frames read.
+pub fn read_ptp<F>(path: &Path, mut f: F) -> Result<usize, ReportError>
+where
+    F: FnMut(&CapturedPtp),
+{
+    read_frames(path, |frame, time, data| {
+        let macs = data.macs;
+
+        if data.ethertype == PTP_ETHERTYPE {
+            if let Ok((_, message)) = parse_ptp(data.data) {
+                f(&CapturedPtp {
+                    frame,
+                    time,
+                    src: None,
+                    dst: None,
+                    src_mac: macs[0],
+                    dst_mac: macs[1],
+                    transport: PtpTransport::Ethernet,
+                    message,
+                });
+            }
+            return;
+        }
+
+        let ip = match ip_frame(data.data) {
+            Some(ip) => ip,
+            None => return,
+        };
+        let packet = match &ip.ptp {
+            Some(packet) => packet,
+            None => return,
+        };
+        if let Ok((_, message)) = parse_ptp(packet) {
+            f(&CapturedPtp {
+                frame,
+                time,
+                src: Some(ip.addresses[0]),
+                dst: Some(ip.addresses[1]),
+                src_mac: macs[0],
+                dst_mac: macs[1],
+                transport: PtpTransport::Udp,
+                message,
+            });
+        }
+    })
+}
+
+fn mac(b: &[u8]) -> MacAddr {
+    MacAddr::new(b[0], b[1], b[2], b[3], b[4], b[5])
+}
+
+fn messages_of_frame<F>(
+    frame: usize,
+    time: i64,
+    data: &Frame,
+    reassembly: &mut Reassembly,
+    f: &mut F,
+) where
+    F: FnMut(&CapturedMessage),
+{
+    let ip = match ip_frame(data.data) {
+        Some(ip) => ip,
+        None => return,
+    };
+    let sockets = match ip.sockets() {
+        Some(sockets) => sockets,
+        None => return,
+    };
+
+    // Handshake and pure ACK segments carry no GCP data.
+    let seq = match ip.seq {
+        Some(seq) if !ip.payload.is_empty() => seq,
+        _ => return,
+    };
+
+    for msg in reassembly.messages(sockets, seq, &ip.payload) {
+        f(&parse_message(frame, time, (sockets, data.macs), &msg));
+    }
+}
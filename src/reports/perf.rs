@@ -0,0 +1,310 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::parsers::tlv_def::DataType;
+use crate::reports::{format_time, read_messages, CapturedMessage, ReportError, ReportTable, Tlv};
+
+/// Performance TLVs of an RfChannel (16) with counters.
+const PERF_TLVS: [&str; 8] = [
+    "DsScQamChannelPerf",
+    "DsOfdmChannelPerf",
+    "DsOob551Perf",
+    "DsOob552Perf",
+    "NdfPerf",
+    "UsScQamChannelPerf",
+    "UsOfdmaChannelPerf",
+    "NdrPerf",
+];
+
+/// Values that are levels rather than counters, with the divisor to their unit.
+const GAUGES: [(&str, f64, &str); 2] = [
+    ("ChannelSnr", 10.0, "dB"),
+    ("AverageMer", 1.0, "as reported"),
+];
+
+/// UnsignedLong values that are not counters.
+const NOT_COUNTERS: [&str; 1] = ["PlcFrameTimeAlignment"];
+
+/// Sub-TLVs whose value names the entry of a stats table, with the prefix of its counters.
+const ENTRY_INDEXES: [(&str, &str); 2] = [("UsIuc", "Iuc"), ("ProfileIndex", "Profile")];
+
+/// One read of a performance TLV. Counters of table entries are prefixed with the
+/// entry, e.g. `Iuc5 UnicastOpErrors` or `Profile0 outCodewords`.
+#[derive(Debug, Clone, Default)]
+struct Sample {
+    time: i64,
+    frame: usize,
+    counters: BTreeMap<String, u64>,
+    gauges: BTreeMap<String, f64>,
+    /// discontinuityTime by counter prefix, `` for the channel and `Plc ` for the PLC.
+    discontinuities: BTreeMap<String, String>,
+}
+
+impl Sample {
+    fn collect(&mut self, prefix: &str, tlv: &Tlv) {
+        for sub in &tlv.sub {
+            let def = match sub.def {
+                Some(def) if !sub.value.is_empty() => def,
+                _ => continue,
+            };
+
+            match def.data_type {
+                DataType::Complex => {
+                    let entry = ENTRY_INDEXES.iter().find_map(|(index, label)| {
+                        sub.get(index).map(|i| format!("{label}{} ", i.number()))
+                    });
+                    let prefix = match entry {
+                        Some(entry) => format!("{prefix}{entry}"),
+                        // DsOfdmPlcPerf counters are `Plc outPackets`.
+                        None => {
+                            let name = def.name.trim_start_matches("DsOfdm");
+                            format!("{prefix}{} ", name.trim_end_matches("Perf"))
+                        }
+                    };
+                    self.collect(&prefix, sub);
+                }
+                DataType::UnsignedLong if !NOT_COUNTERS.contains(&def.name.as_str()) => {
+                    self.counters
+                        .insert(format!("{prefix}{}", def.name), sub.number());
+                }
+                DataType::DateAndTime if def.name == "discontinuityTime" => {
+                    self.discontinuities.insert(prefix.to_string(), sub.text());
+                }
+                _ => {
+                    if let Some((_, divisor, _)) = GAUGES.iter().find(|(g, _, _)| *g == def.name) {
+                        self.gauges.insert(
+                            format!("{prefix}{}", def.name),
+                            sub.number() as f64 / divisor,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A rate, ratio or level over an interval.
+#[derive(Debug, Clone)]
+struct Metric {
+    name: String,
+    value: Option<f64>,
+    unit: &'static str,
+    note: &'static str,
+}
+
+impl Metric {
+    fn new(name: String, value: Option<f64>, unit: &'static str) -> Self {
+        Metric {
+            name,
+            value,
+            unit,
+            note: "",
+        }
+    }
+}
+
+/// Channel and performance TLV a series of samples is for.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct SeriesKey {
+    rpd: IpAddr,
+    port: u64,
+    channel_type: String,
+    channel: u64,
+    perf: String,
+}
+
+/// Successive reads of the channel performance TLVs of every RPD of the captures, for
+/// rates per second, codeword error ratios and SNR/MER trends.
+#[derive(Debug, Default)]
+pub struct PerfSeries {
+    series: BTreeMap<SeriesKey, Vec<Sample>>,
+}
+
+impl PerfSeries {
+    pub fn add(&mut self, msg: &CapturedMessage) {
+        let rpd = msg.rpd().0;
+
+        for channel in msg.tlvs("RfChannel") {
+            let selector = match channel.get("RfChannelSelector") {
+                Some(selector) => selector,
+                None => continue,
+            };
+            let field = |name| selector.get(name).filter(|t| !t.value.is_empty());
+
+            for perf in PERF_TLVS.iter().filter_map(|name| channel.get(name)) {
+                let mut sample = Sample {
+                    time: msg.time,
+                    frame: msg.frame,
+                    ..Sample::default()
+                };
+                sample.collect("", perf);
+                // The reads of the Core have empty values.
+                if sample.counters.is_empty() && sample.gauges.is_empty() {
+                    continue;
+                }
+
+                let key = SeriesKey {
+                    rpd,
+                    port: field("RfPortIndex").map_or(0, Tlv::number),
+                    channel_type: field("RfChannelType").map_or_else(String::new, Tlv::label),
+                    channel: field("RfChannelIndex").map_or(0, Tlv::number),
+                    perf: perf.name(),
+                };
+                self.series.entry(key).or_default().push(sample);
+            }
+        }
+    }
+
+    /// A row per channel, interval between two reads and metric: every counter per
+    /// second, the codeword ratios per IUC and the SNR and MER with their change.
+    /// Counters going back and discontinuityTime changes leave the rate empty with a note.
+    pub fn rates(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "rpd_ip",
+            "rf_port",
+            "channel_type",
+            "channel_index",
+            "perf",
+            "from",
+            "to",
+            "frame",
+            "interval_s",
+            "metric",
+            "value",
+            "unit",
+            "note",
+        ]);
+
+        for (key, samples) in &self.series {
+            for pair in samples.windows(2) {
+                let (from, to) = (&pair[0], &pair[1]);
+                let interval = (to.time - from.time) as f64 / 1_000_000.0;
+                if interval <= 0.0 {
+                    continue;
+                }
+
+                for metric in PerfSeries::metrics(from, to, interval) {
+                    table.push(vec![
+                        Value::from(key.rpd.to_string()),
+                        Value::from(key.port),
+                        Value::from(key.channel_type.clone()),
+                        Value::from(key.channel),
+                        Value::from(key.perf.clone()),
+                        Value::from(format_time(from.time)),
+                        Value::from(format_time(to.time)),
+                        Value::from(to.frame),
+                        Value::from(interval),
+                        Value::from(metric.name),
+                        metric.value.map_or(Value::Null, Value::from),
+                        Value::from(metric.unit),
+                        Value::from(metric.note),
+                    ]);
+                }
+            }
+        }
+
+        table
+    }
+
+    fn metrics(from: &Sample, to: &Sample, interval: f64) -> Vec<Metric> {
+        let mut metrics = Vec::new();
+        let mut deltas = BTreeMap::new();
+
+        for (name, end) in &to.counters {
+            let start = match from.counters.get(name) {
+                Some(start) => *start,
+                None => continue,
+            };
+            let discontinuity = from.discontinuities.iter().any(|(prefix, time)| {
+                name.starts_with(prefix.as_str())
+                    && to.discontinuities.get(prefix).is_some_and(|t| t != time)
+            });
+
+            let (rate, note) = if discontinuity {
+                (None, "discontinuity")
+            } else if *end < start {
+                (None, "counter reset")
+            } else {
+                deltas.insert(name.clone(), end - start);
+                (Some((end - start) as f64 / interval), "")
+            };
+            metrics.push(Metric {
+                note,
+                ..Metric::new(format!("{name}/s"), rate, "1/s")
+            });
+        }
+
+        // Codewords of an IUC: good, corrected and uncorrectable for SC-QAM, passing before
+        // decoding, passing after decoding and failing for OFDMA.
+        let prefixes: Vec<&str> = deltas
+            .keys()
+            .filter_map(|k| {
+                k.strip_suffix("UncorrectFecCw")
+                    .or_else(|| k.strip_suffix("NumPostdecodeFail"))
+            })
+            .collect();
+        for prefix in prefixes {
+            let delta = |name: &str| deltas.get(&format!("{prefix}{name}")).copied();
+            let counts = match (
+                delta("GoodFecCw"),
+                delta("CorrectedFecCw"),
+                delta("UncorrectFecCw"),
+            ) {
+                (Some(good), Some(corrected), Some(uncorrectable)) => {
+                    Some((good, corrected, uncorrectable))
+                }
+                _ => match (
+                    delta("NumPredecodePass"),
+                    delta("NumPostdecodePass"),
+                    delta("NumPostdecodeFail"),
+                ) {
+                    (Some(good), Some(corrected), Some(failed)) => Some((good, corrected, failed)),
+                    _ => None,
+                },
+            };
+            if let Some((good, corrected, uncorrectable)) = counts {
+                let total = (good + corrected + uncorrectable) as f64;
+                let ratio = |n: u64| (total > 0.0).then(|| n as f64 / total);
+                metrics.push(Metric::new(
+                    format!("{prefix}CorrectedCwRatio"),
+                    ratio(corrected),
+                    "ratio",
+                ));
+                metrics.push(Metric::new(
+                    format!("{prefix}UncorrectableCwRatio"),
+                    ratio(uncorrectable),
+                    "ratio",
+                ));
+            }
+        }
+
+        for (name, level) in &to.gauges {
+            let unit = GAUGES
+                .iter()
+                .find(|(g, _, _)| name.ends_with(g))
+                .map_or("", |(_, _, unit)| *unit);
+            metrics.push(Metric::new(name.clone(), Some(*level), unit));
+            if let Some(previous) = from.gauges.get(name) {
+                metrics.push(Metric::new(
+                    format!("{name} change"),
+                    Some(level - previous),
+                    unit,
+                ));
+            }
+        }
+
+        metrics
+    }
+}
+
+/// Performance reads of the RPDs in `captures`, read in order.
+pub fn perf_series(captures: &[&Path]) -> Result<PerfSeries, ReportError> {
+    let mut series = PerfSeries::default();
+    for path in captures {
+        read_messages(path, |msg| series.add(msg))?;
+    }
+
+    Ok(series)
+}
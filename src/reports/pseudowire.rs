@@ -0,0 +1,268 @@
+use pnet::util::MacAddr;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::reports::depi::ExpectedSession;
+use crate::reports::{read_messages, CapturedMessage, ReportError, ReportTable, Tlv};
+
+/// Direction (58.3.1) values.
+const DIRECTIONS: [&str; 2] = ["forward", "return"];
+
+/// A static pseudowire as configured by the Core and reported by the RPD.
+#[derive(Debug, Clone, Default)]
+struct Pseudowire {
+    core_owner: Option<String>,
+    group: Option<String>,
+    source: Option<String>,
+    /// DestAddress of a return pseudowire.
+    destination: Option<String>,
+    pw_type: Option<String>,
+    subtype: Option<String>,
+    session_id: Option<u64>,
+    /// RF channels of the PwAssociation entries, as `port/type/index`.
+    channels: Vec<String>,
+    circuit_status: Option<String>,
+    selected_session_id: Option<u64>,
+}
+
+impl Pseudowire {
+    /// FwdStaticPwConfig or RetStaticPwConfig.
+    fn direction_config(&mut self, tlv: &Tlv) {
+        if let Some(t) = value(tlv, "CcapCoreOwner") {
+            self.core_owner = Some(t.text());
+        }
+        if let Some(t) = value(tlv, "GroupAddress") {
+            self.group = Some(t.text());
+        }
+        if let Some(t) = value(tlv, "SourceAddress") {
+            self.source = Some(t.text());
+        }
+        if let Some(t) = value(tlv, "DestAddress") {
+            self.destination = Some(t.text());
+        }
+    }
+
+    fn common_config(&mut self, tlv: &Tlv) {
+        if let Some(t) = value(tlv, "PwType") {
+            self.pw_type = Some(t.label());
+        }
+        if let Some(t) = value(tlv, "DepiPwSubtype") {
+            self.subtype = Some(t.label());
+        }
+        if let Some(t) = value(tlv, "SessionId") {
+            self.session_id = Some(t.number());
+        }
+
+        let channels: Vec<String> = tlv
+            .all("PwAssociation")
+            .filter_map(|a| a.get("ChannelSelector"))
+            .map(|selector| {
+                let field =
+                    |name| value(selector, name).map_or_else(|| "?".to_string(), Tlv::label);
+                format!(
+                    "{}/{}/{}",
+                    field("RfPortIndex"),
+                    field("ChannelType"),
+                    field("ChannelIndex")
+                )
+            })
+            .collect();
+        if !channels.is_empty() {
+            self.channels = channels;
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        match (self.session_id, self.selected_session_id) {
+            (Some(expected), Some(selected)) if expected == selected => "ok",
+            (Some(_), Some(_)) => "SESSION MISMATCH",
+            (Some(_), None) => "no status",
+            (None, Some(_)) => "no config",
+            (None, None) => "no session",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RpdPseudowires {
+    ip: IpAddr,
+    mac: MacAddr,
+    /// Pseudowires keyed by Direction and Index.
+    pws: BTreeMap<(u64, u64), Pseudowire>,
+}
+
+/// Sub-TLV with a value, the reads of the Core have empty ones.
+fn value<'a>(tlv: &'a Tlv, name: &str) -> Option<&'a Tlv> {
+    tlv.get(name).filter(|t| !t.value.is_empty())
+}
+
+impl RpdPseudowires {
+    fn config(&mut self, tlv: &Tlv) {
+        let fwd = tlv.get("FwdStaticPwConfig");
+        let ret = tlv.get("RetStaticPwConfig");
+        let common = tlv.get("CommonStaticPwConfig");
+
+        // The Direction and Index of the common config, else those of the direction config.
+        let direction = common
+            .and_then(|c| value(c, "Direction"))
+            .map(Tlv::number)
+            .or_else(|| fwd.map(|_| 0))
+            .or_else(|| ret.map(|_| 1));
+        let index = common
+            .and_then(|c| value(c, "Index"))
+            .or_else(|| fwd.or(ret).and_then(|d| value(d, "Index")))
+            .map(Tlv::number);
+        let (direction, index) = match (direction, index) {
+            (Some(direction), Some(index)) => (direction, index),
+            _ => return,
+        };
+
+        let pw = self.pws.entry((direction, index)).or_default();
+        if let Some(config) = fwd.or(ret) {
+            pw.direction_config(config);
+        }
+        if let Some(common) = common {
+            pw.common_config(common);
+        }
+    }
+
+    fn status(&mut self, tlv: &Tlv) {
+        let common = match tlv.get("CommonStaticPwStatus") {
+            Some(common) => common,
+            None => return,
+        };
+        let (direction, index) = match (value(common, "Direction"), value(common, "Index")) {
+            (Some(direction), Some(index)) => (direction.number(), index.number()),
+            _ => return,
+        };
+
+        let pw = self.pws.entry((direction, index)).or_default();
+        if let Some(t) = value(common, "RpdCircuitStatus") {
+            pw.circuit_status = Some(t.label());
+        }
+        if let Some(t) = value(common, "RpdSelectedSessionId") {
+            pw.selected_session_id = Some(t.number());
+        }
+    }
+}
+
+/// Static pseudowires of every RPD of the captures, the StaticPwConfig (58) written by the
+/// Core joined with the StaticPwStatus (59) of the RPD by direction and index.
+#[derive(Debug, Default)]
+pub struct Pseudowires {
+    rpds: BTreeMap<(IpAddr, [u8; 6]), RpdPseudowires>,
+}
+
+impl Pseudowires {
+    pub fn add(&mut self, msg: &CapturedMessage) {
+        let (ip, mac) = msg.rpd();
+        let rpd = self
+            .rpds
+            .entry((ip, mac.octets()))
+            .or_insert_with(|| RpdPseudowires {
+                ip,
+                mac,
+                pws: BTreeMap::new(),
+            });
+
+        for tlv in msg.tlvs("StaticPwConfig") {
+            rpd.config(tlv);
+        }
+        for tlv in msg.tlvs("StaticPwStatus") {
+            rpd.status(tlv);
+        }
+    }
+
+    /// A row per RPD and pseudowire. The status tells whether the session the RPD
+    /// selected is the SessionId of the config.
+    pub fn table(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "rpd_ip",
+            "rpd_mac",
+            "direction",
+            "index",
+            "core_owner",
+            "group_address",
+            "source_address",
+            "dest_address",
+            "pw_type",
+            "depi_pw_subtype",
+            "session_id",
+            "channels",
+            "circuit_status",
+            "selected_session_id",
+            "status",
+        ]);
+
+        let text = |s: &Option<String>| s.clone().map_or(Value::Null, Value::from);
+        let number = |n: Option<u64>| n.map_or(Value::Null, Value::from);
+        for rpd in self.rpds.values() {
+            for ((direction, index), pw) in &rpd.pws {
+                let direction = DIRECTIONS
+                    .get(*direction as usize)
+                    .map_or_else(|| direction.to_string(), |d| d.to_string());
+                table.push(vec![
+                    Value::from(rpd.ip.to_string()),
+                    Value::from(rpd.mac.to_string()),
+                    Value::from(direction),
+                    Value::from(*index),
+                    text(&pw.core_owner),
+                    text(&pw.group),
+                    text(&pw.source),
+                    text(&pw.destination),
+                    text(&pw.pw_type),
+                    text(&pw.subtype),
+                    number(pw.session_id),
+                    Value::from(pw.channels.join(" ")),
+                    text(&pw.circuit_status),
+                    number(pw.selected_session_id),
+                    Value::from(pw.status()),
+                ]);
+            }
+        }
+
+        table
+    }
+
+    /// SessionId of every configured pseudowire, with the addresses its data is sent to and
+    /// from: the GroupAddress and SourceAddress forward, the DestAddress and the RPD return.
+    pub(crate) fn expected_sessions(&self) -> Vec<ExpectedSession> {
+        let mut expected = Vec::new();
+        let ip = |s: &Option<String>| s.as_ref().and_then(|s| s.parse().ok());
+
+        for rpd in self.rpds.values() {
+            for ((direction, index), pw) in &rpd.pws {
+                let session_id = match pw.session_id {
+                    Some(id) => id,
+                    None => continue,
+                };
+                let (src, dst) = match direction {
+                    0 => (ip(&pw.source), ip(&pw.group)),
+                    _ => (Some(rpd.ip), ip(&pw.destination)),
+                };
+                let direction = DIRECTIONS.get(*direction as usize).unwrap_or(&"");
+                expected.push(ExpectedSession {
+                    session_id,
+                    src,
+                    dst,
+                    pw_type: pw.pw_type.clone(),
+                    label: format!("StaticPwConfig {} {direction} {index}", rpd.ip),
+                });
+            }
+        }
+
+        expected
+    }
+}
+
+/// Static pseudowires of the RPDs in `captures`, read in order.
+pub fn pseudowires(captures: &[&Path]) -> Result<Pseudowires, ReportError> {
+    let mut pseudowires = Pseudowires::default();
+    for path in captures {
+        read_messages(path, |msg| pseudowires.add(msg))?;
+    }
+
+    Ok(pseudowires)
+}
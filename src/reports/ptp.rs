@@ -0,0 +1,473 @@
+use pnet::util::MacAddr;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::parsers::ptp_parser::{
+    time_source_name, Announce, PortIdentity, PtpBody, PtpTransport, ANNOUNCE, DELAY_REQ,
+    DELAY_RESP, FOLLOW_UP, SIGNALING, SYNC,
+};
+use crate::reports::{
+    format_time, read_messages, read_ptp, CapturedMessage, CapturedPtp, ReportError, ReportTable,
+    Tlv,
+};
+
+/// Message types counted by the reports, in column order.
+const COUNTED: [u8; 6] = [SYNC, FOLLOW_UP, DELAY_REQ, DELAY_RESP, ANNOUNCE, SIGNALING];
+
+/// Sender and destination of a stream of messages. Addresses are IP over UDP, MAC over
+/// Ethernet.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct StreamKey {
+    source: PortIdentity,
+    src: String,
+    dst: String,
+}
+
+/// PTP messages of one port to one destination.
+#[derive(Debug, Clone)]
+struct Stream {
+    transport: PtpTransport,
+    domain: u8,
+    counts: BTreeMap<u8, usize>,
+    first: i64,
+    last: i64,
+    log_sync_interval: Option<i8>,
+    /// Capture time and Sequence ID of the last Sync.
+    last_sync: Option<(i64, u16)>,
+    sync_intervals: usize,
+    sync_interval_total: i64,
+    max_sync_interval: i64,
+    /// Sync Sequence IDs that are not the next one.
+    sync_gaps: usize,
+    /// Delay_Req answered, and the time their Delay_Resp took.
+    answered: usize,
+    response_total: i64,
+    announce: Option<Announce>,
+}
+
+impl Stream {
+    fn new(transport: PtpTransport, domain: u8, time: i64) -> Self {
+        Stream {
+            transport,
+            domain,
+            counts: BTreeMap::new(),
+            first: time,
+            last: time,
+            log_sync_interval: None,
+            last_sync: None,
+            sync_intervals: 0,
+            sync_interval_total: 0,
+            max_sync_interval: 0,
+            sync_gaps: 0,
+            answered: 0,
+            response_total: 0,
+            announce: None,
+        }
+    }
+
+    fn count(&self, typ: u8) -> usize {
+        self.counts.get(&typ).copied().unwrap_or(0)
+    }
+
+    fn sync(&mut self, time: i64, sequence_id: u16, log_interval: i8) {
+        if let Some((last, last_sequence)) = self.last_sync {
+            self.sync_intervals += 1;
+            self.sync_interval_total += time - last;
+            self.max_sync_interval = self.max_sync_interval.max(time - last);
+            if sequence_id != last_sequence.wrapping_add(1) {
+                self.sync_gaps += 1;
+            }
+        }
+        self.last_sync = Some((time, sequence_id));
+        self.log_sync_interval = Some(log_interval);
+    }
+
+    /// `master` for the ports sending Sync, Announce or Delay_Resp, `slave` for those
+    /// sending Delay_Req.
+    fn role(&self) -> &'static str {
+        let master = [SYNC, ANNOUNCE, DELAY_RESP]
+            .iter()
+            .any(|t| self.count(*t) > 0);
+        match (master, self.count(DELAY_REQ) > 0) {
+            (true, true) => "master+slave",
+            (true, false) => "master",
+            (false, true) => "slave",
+            (false, false) => "",
+        }
+    }
+}
+
+/// A PTP message, for the correlation with the notifications.
+#[derive(Debug, Clone)]
+struct Event {
+    time: i64,
+    transport: PtpTransport,
+    src: Option<IpAddr>,
+    dst: Option<IpAddr>,
+    src_mac: MacAddr,
+    dst_mac: MacAddr,
+    message_type: u8,
+    source: PortIdentity,
+    /// Requesting port of a Delay_Resp.
+    requesting: Option<PortIdentity>,
+    announce: Option<Announce>,
+}
+
+impl Event {
+    /// Whether the RPD sent the message, by IP over UDP and by MAC over Ethernet. The MAC of
+    /// a routed RPD is the one of the router.
+    fn sent_by_rpd(&self, ip: IpAddr, mac: MacAddr) -> bool {
+        match self.transport {
+            PtpTransport::Udp => self.src == Some(ip),
+            PtpTransport::Ethernet => self.src_mac == mac,
+        }
+    }
+
+    fn sent_to_rpd(&self, ip: IpAddr, mac: MacAddr) -> bool {
+        match self.transport {
+            PtpTransport::Udp => self.dst == Some(ip),
+            PtpTransport::Ethernet => self.dst_mac == mac,
+        }
+    }
+
+    fn multicast(&self) -> bool {
+        match self.dst {
+            Some(dst) => dst.is_multicast(),
+            None => self.dst_mac.0 & 0x01 != 0,
+        }
+    }
+}
+
+/// A PtpResultNotification of a GeneralNotification (86).
+#[derive(Debug, Clone)]
+struct PtpResult {
+    frame: usize,
+    time: i64,
+    rpd_ip: IpAddr,
+    rpd_mac: MacAddr,
+    result: Option<u64>,
+    enet_port: Option<u64>,
+    ptp_port: Option<u64>,
+    clock_source: Option<u64>,
+}
+
+/// Sub-TLV with a value, the reads of the Core have empty ones.
+fn value<'a>(tlv: &'a Tlv, name: &str) -> Option<&'a Tlv> {
+    tlv.get(name).filter(|t| !t.value.is_empty())
+}
+
+fn address(ip: Option<IpAddr>, mac: MacAddr) -> String {
+    ip.map_or_else(|| mac.to_string(), |ip| ip.to_string())
+}
+
+/// PTP (IEEE 1588) messages of the captures, by sending port and destination, with the
+/// PtpResultNotifications the RPDs sent over GCP.
+#[derive(Debug, Default)]
+pub struct PtpTiming {
+    streams: BTreeMap<StreamKey, Stream>,
+    /// Delay_Req waiting for their Delay_Resp, by requesting port and Sequence ID.
+    pending: BTreeMap<(PortIdentity, u16), (StreamKey, i64)>,
+    events: Vec<Event>,
+    results: Vec<PtpResult>,
+}
+
+impl PtpTiming {
+    pub fn add_ptp(&mut self, ptp: &CapturedPtp) {
+        let header = &ptp.message.header;
+        let key = StreamKey {
+            source: header.source,
+            src: address(ptp.src, ptp.src_mac),
+            dst: address(ptp.dst, ptp.dst_mac),
+        };
+        let stream = self
+            .streams
+            .entry(key.clone())
+            .or_insert_with(|| Stream::new(ptp.transport, header.domain, ptp.time));
+        *stream.counts.entry(header.message_type).or_default() += 1;
+        stream.last = ptp.time;
+
+        let mut requesting = None;
+        let mut announce = None;
+        match &ptp.message.body {
+            PtpBody::Sync { .. } => {
+                stream.sync(ptp.time, header.sequence_id, header.log_message_interval)
+            }
+            PtpBody::DelayReq { .. } => {
+                self.pending
+                    .insert((header.source, header.sequence_id), (key, ptp.time));
+            }
+            PtpBody::DelayResp { requesting: r, .. } => {
+                requesting = Some(*r);
+                if let Some((req, time)) = self.pending.remove(&(*r, header.sequence_id)) {
+                    if let Some(req) = self.streams.get_mut(&req) {
+                        req.answered += 1;
+                        req.response_total += ptp.time - time;
+                    }
+                }
+            }
+            PtpBody::Announce(a) => {
+                stream.announce = Some(*a);
+                announce = Some(*a);
+            }
+            _ => (),
+        }
+
+        self.events.push(Event {
+            time: ptp.time,
+            transport: ptp.transport,
+            src: ptp.src,
+            dst: ptp.dst,
+            src_mac: ptp.src_mac,
+            dst_mac: ptp.dst_mac,
+            message_type: header.message_type,
+            source: header.source,
+            requesting,
+            announce,
+        });
+    }
+
+    pub fn add(&mut self, msg: &CapturedMessage) {
+        if !msg.from_rpd() {
+            return;
+        }
+        let (rpd_ip, rpd_mac) = msg.rpd();
+
+        for tlv in msg.tlvs("GeneralNotification") {
+            let kind = value(tlv, "NotificationType").map(Tlv::label);
+            if kind.as_deref() != Some("PtpResultNotification") {
+                continue;
+            }
+            let number = |name| value(tlv, name).map(Tlv::number);
+            self.results.push(PtpResult {
+                frame: msg.frame,
+                time: msg.time,
+                rpd_ip,
+                rpd_mac,
+                result: number("PtpResult"),
+                enet_port: number("PtpRpdEnetPortIndex"),
+                ptp_port: number("PtpRpdPtpPortIndex"),
+                clock_source: number("PtpClockSource"),
+            });
+        }
+    }
+
+    /// A row per port and destination, with the messages sent, the Sync interval and the
+    /// Sequence ID gaps, the time the Delay_Req waited for their Delay_Resp and the
+    /// grandmaster of the last Announce.
+    pub fn clocks(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "clock",
+            "src",
+            "dst",
+            "transport",
+            "domain",
+            "role",
+            "sync",
+            "follow_up",
+            "delay_req",
+            "delay_resp",
+            "announce",
+            "signaling",
+            "first_seen",
+            "last_seen",
+            "log_sync_interval",
+            "sync_interval_ms",
+            "max_sync_interval_ms",
+            "sync_seq_gaps",
+            "delay_resp_ms",
+            "unanswered",
+            "grandmaster",
+            "clock_class",
+            "steps_removed",
+            "time_source",
+        ]);
+
+        let ms = |micros: i64| Value::from(micros as f64 / 1000.0);
+        for (key, stream) in &self.streams {
+            let unanswered = self.pending.values().filter(|(k, _)| k == key).count();
+            let announce = stream.announce.as_ref();
+
+            let mut row = vec![
+                Value::from(key.source.to_string()),
+                Value::from(key.src.clone()),
+                Value::from(key.dst.clone()),
+                Value::from(stream.transport.to_string()),
+                Value::from(stream.domain),
+                Value::from(stream.role()),
+            ];
+            row.extend(COUNTED.iter().map(|t| Value::from(stream.count(*t))));
+            row.extend([
+                Value::from(format_time(stream.first)),
+                Value::from(format_time(stream.last)),
+                stream.log_sync_interval.map_or(Value::Null, Value::from),
+                match stream.sync_intervals {
+                    0 => Value::Null,
+                    n => ms(stream.sync_interval_total / n as i64),
+                },
+                match stream.sync_intervals {
+                    0 => Value::Null,
+                    _ => ms(stream.max_sync_interval),
+                },
+                Value::from(stream.sync_gaps),
+                match stream.answered {
+                    0 => Value::Null,
+                    n => ms(stream.response_total / n as i64),
+                },
+                Value::from(unanswered),
+                announce.map_or(Value::Null, |a| Value::from(a.grandmaster.to_string())),
+                announce.map_or(Value::Null, |a| Value::from(a.quality.class)),
+                announce.map_or(Value::Null, |a| Value::from(a.steps_removed)),
+                announce.map_or(Value::Null, |a| {
+                    Value::from(time_source_name(a.time_source))
+                }),
+            ]);
+            table.push(row);
+        }
+
+        table
+    }
+
+    /// A row per PtpResultNotification, with the PTP exchange of the RPD since its previous
+    /// one: the messages it sent and received, its master, the longest time between two
+    /// Sync and how long before the notification the last one came.
+    pub fn results(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "rpd_ip",
+            "rpd_mac",
+            "time",
+            "frame",
+            "ptp_result",
+            "enet_port",
+            "ptp_port",
+            "clock_source",
+            "rpd_clock",
+            "master_clock",
+            "grandmaster",
+            "sync",
+            "follow_up",
+            "delay_req",
+            "delay_resp",
+            "announce",
+            "signaling",
+            "first_ptp",
+            "max_sync_gap_ms",
+            "last_sync_ms",
+            "status",
+        ]);
+
+        let mut previous: BTreeMap<IpAddr, i64> = BTreeMap::new();
+        for result in &self.results {
+            let (ip, mac) = (result.rpd_ip, result.rpd_mac);
+            let since = previous.insert(ip, result.time);
+            let window: Vec<&Event> = self
+                .events
+                .iter()
+                .filter(|e| e.time <= result.time && since.is_none_or(|s| e.time > s))
+                .collect();
+
+            // The ports of the RPD, and the masters answering their Delay_Req or sending
+            // to the RPD.
+            let mut rpd_clocks: Vec<PortIdentity> = window
+                .iter()
+                .filter(|e| e.sent_by_rpd(ip, mac))
+                .map(|e| e.source)
+                .collect();
+            rpd_clocks.sort();
+            rpd_clocks.dedup();
+            let mut masters: Vec<PortIdentity> = window
+                .iter()
+                .filter(|e| {
+                    e.sent_to_rpd(ip, mac) || e.requesting.is_some_and(|r| rpd_clocks.contains(&r))
+                })
+                .map(|e| e.source)
+                .collect();
+            masters.sort();
+            masters.dedup();
+
+            // Unicast messages to and from the RPD, and the multicast ones of its masters.
+            let exchange: Vec<&Event> = window
+                .into_iter()
+                .filter(|e| {
+                    e.sent_by_rpd(ip, mac)
+                        || e.sent_to_rpd(ip, mac)
+                        || (e.multicast() && masters.contains(&e.source))
+                })
+                .collect();
+            let count = |typ: u8| exchange.iter().filter(|e| e.message_type == typ).count();
+            let syncs: Vec<i64> = exchange
+                .iter()
+                .filter(|e| e.message_type == SYNC && !e.sent_by_rpd(ip, mac))
+                .map(|e| e.time)
+                .collect();
+            let max_gap = syncs.windows(2).map(|w| w[1] - w[0]).max();
+            // Announces are rare, the last one of a master may come before the window.
+            let grandmaster = self
+                .events
+                .iter()
+                .rev()
+                .filter(|e| e.time <= result.time && masters.contains(&e.source))
+                .find_map(|e| e.announce)
+                .map(|a| a.grandmaster.to_string());
+
+            let status = match result.result {
+                _ if exchange.is_empty() => "no PTP captured",
+                // PtpResult 1 is the RPD synchronized.
+                Some(1) => "synchronized",
+                _ if syncs.is_empty() => "NOT SYNCHRONIZED, no Sync",
+                _ if count(DELAY_REQ) > 0 && count(DELAY_RESP) == 0 => {
+                    "NOT SYNCHRONIZED, no Delay_Resp"
+                }
+                _ => "NOT SYNCHRONIZED",
+            };
+
+            let ports = |ports: &[PortIdentity]| {
+                ports
+                    .iter()
+                    .map(PortIdentity::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            let number = |n: Option<u64>| n.map_or(Value::Null, Value::from);
+            let mut row = vec![
+                Value::from(ip.to_string()),
+                Value::from(mac.to_string()),
+                Value::from(format_time(result.time)),
+                Value::from(result.frame),
+                number(result.result),
+                number(result.enet_port),
+                number(result.ptp_port),
+                number(result.clock_source),
+                Value::from(ports(&rpd_clocks)),
+                Value::from(ports(&masters)),
+                grandmaster.map_or(Value::Null, Value::from),
+            ];
+            row.extend(COUNTED.iter().map(|t| Value::from(count(*t))));
+            row.extend([
+                exchange
+                    .first()
+                    .map_or(Value::Null, |e| Value::from(format_time(e.time))),
+                max_gap.map_or(Value::Null, |g| Value::from(g as f64 / 1000.0)),
+                syncs.last().map_or(Value::Null, |t| {
+                    Value::from((result.time - t) as f64 / 1000.0)
+                }),
+                Value::from(status),
+            ]);
+            table.push(row);
+        }
+
+        table
+    }
+}
+
+/// PTP messages and PtpResultNotifications of `captures`, read in order.
+pub fn ptp_timing(captures: &[&Path]) -> Result<PtpTiming, ReportError> {
+    let mut timing = PtpTiming::default();
+    for path in captures {
+        read_messages(path, |msg| timing.add(msg))?;
+        read_ptp(path, |ptp| timing.add_ptp(ptp))?;
+    }
+
+    Ok(timing)
+}
@@ -0,0 +1,233 @@
+use pnet::util::MacAddr;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::parsers::gcp_parser::MessageId;
+use crate::reports::{format_time, read_messages, CapturedMessage, ReportError, ReportTable, Tlv};
+
+// EvThrottleAdminStatus values.
+const UNCONSTRAINED: u64 = 1;
+const INHIBITED: u64 = 4;
+
+/// EvCfg (15.1) of an RPD, as written by its Core or read back.
+#[derive(Debug, Clone, Default)]
+struct EvConfig {
+    admin_status: Option<Tlv>,
+    threshold: Option<u64>,
+    /// EvThrottleInterval, in seconds.
+    interval: Option<u64>,
+    notify_enable: Option<u64>,
+}
+
+impl EvConfig {
+    fn update(&mut self, ev_cfg: &Tlv) {
+        let value = |name| ev_cfg.get(name).filter(|t| !t.value.is_empty());
+
+        if let Some(t) = value("EvThrottleAdminStatus") {
+            self.admin_status = Some(t.clone());
+        }
+        if let Some(t) = value("EvThrottleThreshold") {
+            self.threshold = Some(t.number());
+        }
+        if let Some(t) = value("EvThrottleInterval") {
+            self.interval = Some(t.number());
+        }
+        if let Some(t) = value("NotifyEnable") {
+            self.notify_enable = Some(t.number());
+        }
+    }
+
+    fn is_known(&self) -> bool {
+        self.admin_status.is_some() || self.notify_enable.is_some()
+    }
+}
+
+/// A Notify sent by the RPD, with the EvCfg in effect when it was sent.
+#[derive(Debug, Clone)]
+struct Notify {
+    time: i64,
+    frame: usize,
+    config: EvConfig,
+}
+
+#[derive(Debug, Clone)]
+struct RpdEvents {
+    ip: IpAddr,
+    mac: MacAddr,
+    config: EvConfig,
+    notifies: Vec<Notify>,
+}
+
+/// A Notify the EvCfg did not allow.
+#[derive(Debug, Clone)]
+struct Violation {
+    time: i64,
+    frame: usize,
+    reason: String,
+}
+
+impl RpdEvents {
+    /// Notifies sent while NotifyEnable was 0, while events were inhibited, or beyond the
+    /// EvThrottleThreshold within an EvThrottleInterval, with the most Notifies seen
+    /// within an interval.
+    fn check(&self) -> (Vec<Violation>, usize) {
+        let mut violations = Vec::new();
+        let mut max_per_interval = 0;
+
+        for (i, notify) in self.notifies.iter().enumerate() {
+            let config = &notify.config;
+            let admin_status = config.admin_status.as_ref().map(Tlv::number);
+
+            let reason = if config.notify_enable == Some(0) {
+                Some("sent with NotifyEnable 0".to_string())
+            } else if admin_status == Some(INHIBITED) {
+                Some("sent with EvThrottleAdminStatus inhibited".to_string())
+            } else {
+                match (config.threshold, config.interval) {
+                    (Some(threshold), Some(interval)) if interval > 0 => {
+                        let since = notify.time - interval as i64 * 1_000_000;
+                        let count = self.notifies[..=i]
+                            .iter()
+                            .filter(|n| n.time > since)
+                            .count();
+                        max_per_interval = max_per_interval.max(count);
+
+                        let throttled = admin_status.is_some_and(|s| s != UNCONSTRAINED);
+                        (throttled && count as u64 > threshold).then(|| {
+                            format!("{count} Notifies within {interval} s, threshold {threshold}")
+                        })
+                    }
+                    _ => None,
+                }
+            };
+
+            if let Some(reason) = reason {
+                violations.push(Violation {
+                    time: notify.time,
+                    frame: notify.frame,
+                    reason,
+                });
+            }
+        }
+
+        (violations, max_per_interval)
+    }
+}
+
+/// The Notify messages every RPD of the captures sent, checked against the EvCfg of
+/// RpdGlobal its Core wrote: NotifyEnable, EvThrottleAdminStatus, EvThrottleThreshold
+/// and EvThrottleInterval.
+#[derive(Debug, Default)]
+pub struct ThrottleCheck {
+    rpds: BTreeMap<(IpAddr, [u8; 6]), RpdEvents>,
+}
+
+impl ThrottleCheck {
+    pub fn add(&mut self, msg: &CapturedMessage) {
+        let (ip, mac) = msg.rpd();
+        let rpd = self
+            .rpds
+            .entry((ip, mac.octets()))
+            .or_insert_with(|| RpdEvents {
+                ip,
+                mac,
+                config: EvConfig::default(),
+                notifies: Vec::new(),
+            });
+
+        for global in msg.tlvs("RpdGlobal") {
+            for ev_cfg in global.all("EvCfg") {
+                rpd.config.update(ev_cfg);
+            }
+        }
+
+        if msg.message_id == MessageId::Req_Notify && msg.from_rpd() {
+            rpd.notifies.push(Notify {
+                time: msg.time,
+                frame: msg.frame,
+                config: rpd.config.clone(),
+            });
+        }
+    }
+
+    /// A row per RPD: its last EvCfg, the Notifies it sent and the violations.
+    pub fn summary(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "rpd_ip",
+            "rpd_mac",
+            "notify_enable",
+            "throttle_admin_status",
+            "threshold",
+            "interval_s",
+            "notifies",
+            "max_per_interval",
+            "violations",
+            "first_violation",
+            "status",
+        ]);
+
+        let number = |n: Option<u64>| n.map_or(Value::Null, Value::from);
+        for rpd in self.rpds.values() {
+            let (violations, max_per_interval) = rpd.check();
+            let status = if !violations.is_empty() {
+                "VIOLATION"
+            } else if !rpd.config.is_known() {
+                "no EvCfg seen"
+            } else {
+                "ok"
+            };
+
+            table.push(vec![
+                Value::from(rpd.ip.to_string()),
+                Value::from(rpd.mac.to_string()),
+                number(rpd.config.notify_enable),
+                rpd.config
+                    .admin_status
+                    .as_ref()
+                    .map_or(Value::Null, |t| Value::from(t.label())),
+                number(rpd.config.threshold),
+                number(rpd.config.interval),
+                Value::from(rpd.notifies.len()),
+                Value::from(max_per_interval),
+                Value::from(violations.len()),
+                violations
+                    .first()
+                    .map_or(Value::Null, |v| Value::from(format_time(v.time))),
+                Value::from(status),
+            ]);
+        }
+
+        table
+    }
+
+    /// A row per Notify the EvCfg did not allow.
+    pub fn violations(&self) -> ReportTable {
+        let mut table = ReportTable::new(&["rpd_ip", "rpd_mac", "time", "frame", "reason"]);
+
+        for rpd in self.rpds.values() {
+            for violation in rpd.check().0 {
+                table.push(vec![
+                    Value::from(rpd.ip.to_string()),
+                    Value::from(rpd.mac.to_string()),
+                    Value::from(format_time(violation.time)),
+                    Value::from(violation.frame),
+                    Value::from(violation.reason),
+                ]);
+            }
+        }
+
+        table
+    }
+}
+
+/// Notify throttling of the RPDs in `captures`, read in order.
+pub fn check_throttling(captures: &[&Path]) -> Result<ThrottleCheck, ReportError> {
+    let mut check = ThrottleCheck::default();
+    for path in captures {
+        read_messages(path, |msg| check.add(msg))?;
+    }
+
+    Ok(check)
+}
@@ -0,0 +1,321 @@
+use pnet::util::MacAddr;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::reports::{read_messages, CapturedMessage, ReportError, ReportTable, Tlv};
+
+/// A Core as the RPD and the Cores describe it, joined by CoreId.
+#[derive(Debug, Clone, Default)]
+struct CoreRecord {
+    core_id: Option<String>,
+    ip: Option<IpAddr>,
+    name: Option<String>,
+    is_principal: bool,
+    /// CoreMode (60.7), e.g. Active or Backup.
+    mode: Option<String>,
+    /// CoreFunction (60.10) flags set, e.g. `Principal+DOCSIS`.
+    functions: Option<String>,
+    /// Every CoreFunction flag with its state.
+    function_flags: Option<Value>,
+    resource_set: Option<u64>,
+    /// RpdGcpConnectionStatus (105.3).
+    connection: Option<String>,
+    /// RpdGcpBackupCoreStatus (107.3).
+    backup_status: Option<String>,
+    /// Response (106.2) to the last connection request.
+    response: Option<String>,
+    /// BackupCoreIpAddress of the CandidateBackupCoreTable (60.14).
+    candidates: BTreeSet<IpAddr>,
+}
+
+impl CoreRecord {
+    fn role(&self) -> &'static str {
+        let backup = self.mode.as_deref() == Some("Backup") || self.backup_status.is_some();
+        if self.is_principal {
+            "principal"
+        } else if backup {
+            "backup"
+        } else if self.core_id.is_some() {
+            "auxiliary"
+        } else {
+            "unidentified"
+        }
+    }
+
+    fn label(&self) -> String {
+        let name = self.name.clone().or_else(|| self.core_id.clone());
+        match (name, self.ip) {
+            (Some(name), Some(ip)) => format!("{name} {ip}"),
+            (Some(name), None) => name,
+            (None, Some(ip)) => ip.to_string(),
+            (None, None) => "unknown".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RpdTopology {
+    ip: IpAddr,
+    mac: MacAddr,
+    /// Cores keyed by CoreId, or by address until their CoreId is known.
+    cores: BTreeMap<String, CoreRecord>,
+    /// Cores the RPD has a GCP connection with.
+    connected: BTreeSet<IpAddr>,
+}
+
+impl RpdTopology {
+    /// Record of the Core with this CoreId, merging the one only known by its address.
+    fn core(&mut self, core_id: Option<String>, ip: Option<IpAddr>) -> &mut CoreRecord {
+        let ip_key = ip.map(|ip| format!("ip {ip}"));
+        let key = match (&core_id, &ip_key) {
+            (Some(id), _) => id.clone(),
+            (None, Some(key)) => self
+                .cores
+                .iter()
+                .find(|(_, c)| c.ip == ip)
+                .map_or_else(|| key.clone(), |(k, _)| k.clone()),
+            (None, None) => "unknown".to_string(),
+        };
+
+        if core_id.is_some() {
+            if let Some(known) = ip_key.and_then(|k| self.cores.remove(&k)) {
+                self.cores.insert(key.clone(), known);
+            }
+        }
+        let core = self.cores.entry(key).or_default();
+        if core_id.is_some() {
+            core.core_id = core_id;
+        }
+        if ip.is_some() {
+            core.ip = ip;
+        }
+        core
+    }
+
+    fn identification(&mut self, tlv: &Tlv) {
+        let core_id = value(tlv, "CoreId").map(|t| t.text());
+        let ip = value(tlv, "CoreIpAddress").and_then(|t| t.text().parse().ok());
+        if core_id.is_none() && ip.is_none() {
+            return;
+        }
+
+        let core = self.core(core_id, ip);
+        if let Some(t) = value(tlv, "IsPrincipal") {
+            core.is_principal = t.number() == 1;
+        }
+        if let Some(t) = value(tlv, "CoreName") {
+            core.name = Some(t.text());
+        }
+        if let Some(t) = value(tlv, "CoreMode") {
+            core.mode = Some(t.label());
+        }
+        if let Some(t) = value(tlv, "CoreFunction") {
+            core.functions = Some(t.label());
+            core.function_flags = t.flags().and_then(|f| serde_json::to_value(f).ok());
+            // Bit 0 of the CoreFunction is the Principal Core.
+            core.is_principal |= t.number() & 1 != 0;
+        }
+        if let Some(t) = value(tlv, "ResourceSetIndex") {
+            core.resource_set = Some(t.number());
+        }
+        for candidate in tlv.all("CandidateBackupCoreTable") {
+            if let Some(ip) = value(candidate, "BackupCoreIpAddress") {
+                if let Ok(ip) = ip.text().parse() {
+                    core.candidates.insert(ip);
+                }
+            }
+        }
+    }
+
+    /// RpdConnectionStatus, CoreGcpConnectionResponse and RpdBackupCoreStatus entries.
+    fn status(&mut self, tlv: &Tlv, field: &str) {
+        let core_id = match value(tlv, "CoreId") {
+            Some(t) => t.text(),
+            None => return,
+        };
+        let status = match value(tlv, field) {
+            Some(t) => t.label(),
+            None => return,
+        };
+
+        let core = self.core(Some(core_id), None);
+        match field {
+            "RpdGcpConnectionStatus" => core.connection = Some(status),
+            "RpdGcpBackupCoreStatus" => core.backup_status = Some(status),
+            _ => core.response = Some(status),
+        }
+    }
+}
+
+/// Sub-TLV with a value, the reads of the Core have empty ones.
+fn value<'a>(tlv: &'a Tlv, name: &str) -> Option<&'a Tlv> {
+    tlv.get(name).filter(|t| !t.value.is_empty())
+}
+
+/// Principal, auxiliary and backup Cores of every RPD of the captures, from the
+/// CcapCoreIdentification (60), RpdConnectionStatus (105), CoreGcpConnectionResponse (106)
+/// and RpdBackupCoreStatus (107) TLVs, and the GCP connections seen.
+#[derive(Debug, Default)]
+pub struct Topology {
+    rpds: BTreeMap<(IpAddr, [u8; 6]), RpdTopology>,
+}
+
+impl Topology {
+    pub fn add(&mut self, msg: &CapturedMessage) {
+        let (ip, mac) = msg.rpd();
+        let rpd = self
+            .rpds
+            .entry((ip, mac.octets()))
+            .or_insert_with(|| RpdTopology {
+                ip,
+                mac,
+                cores: BTreeMap::new(),
+                connected: BTreeSet::new(),
+            });
+        rpd.connected.insert(msg.core());
+
+        for tlv in msg.tlvs("CcapCoreIdentification") {
+            rpd.identification(tlv);
+        }
+        for tlv in msg.tlvs("RpdConnectionStatus") {
+            rpd.status(tlv, "RpdGcpConnectionStatus");
+        }
+        for tlv in msg.tlvs("CoreGcpConnectionResponse") {
+            rpd.status(tlv, "Response");
+        }
+        for tlv in msg.tlvs("RpdBackupCoreStatus") {
+            rpd.status(tlv, "RpdGcpBackupCoreStatus");
+        }
+    }
+
+    /// Cores of the RPD, principal first, the connected ones without any identification included.
+    fn cores(rpd: &RpdTopology) -> Vec<CoreRecord> {
+        let mut cores: Vec<CoreRecord> = rpd.cores.values().cloned().collect();
+        for ip in &rpd.connected {
+            if !cores.iter().any(|c| c.ip == Some(*ip)) {
+                cores.push(CoreRecord {
+                    ip: Some(*ip),
+                    ..CoreRecord::default()
+                });
+            }
+        }
+
+        let rank = |c: &CoreRecord| match c.role() {
+            "principal" => 0,
+            "auxiliary" => 1,
+            "backup" => 2,
+            _ => 3,
+        };
+        cores.sort_by_key(rank);
+        cores
+    }
+
+    pub fn table(&self) -> ReportTable {
+        let mut table = ReportTable::new(&[
+            "rpd_ip",
+            "rpd_mac",
+            "role",
+            "core_id",
+            "core_ip",
+            "core_name",
+            "core_mode",
+            "functions",
+            "resource_set",
+            "gcp_connected",
+            "connection_status",
+            "backup_status",
+            "connection_response",
+            "backup_candidates",
+        ]);
+
+        let text = |s: &Option<String>| s.clone().map_or(Value::Null, Value::from);
+        for rpd in self.rpds.values() {
+            for core in Topology::cores(rpd) {
+                let candidates: Vec<String> =
+                    core.candidates.iter().map(IpAddr::to_string).collect();
+                table.push(vec![
+                    Value::from(rpd.ip.to_string()),
+                    Value::from(rpd.mac.to_string()),
+                    Value::from(core.role()),
+                    text(&core.core_id),
+                    text(&core.ip.map(|ip| ip.to_string())),
+                    text(&core.name),
+                    text(&core.mode),
+                    core.function_flags
+                        .clone()
+                        .unwrap_or_else(|| text(&core.functions)),
+                    core.resource_set.map_or(Value::Null, Value::from),
+                    Value::from(core.ip.is_some_and(|ip| rpd.connected.contains(&ip))),
+                    text(&core.connection),
+                    text(&core.backup_status),
+                    text(&core.response),
+                    Value::from(candidates.join(" ")),
+                ]);
+            }
+        }
+
+        table
+    }
+
+    /// Graphviz graph: an edge from every RPD to its Cores, labelled with the role and
+    /// functions, dashed for backup Cores and dotted to the backup candidates.
+    pub fn dot(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('"', "\\\"").replace('\n', "\\n"));
+        let mut out = String::from("digraph topology {\n    rankdir=LR;\n");
+        let mut nodes = BTreeSet::new();
+
+        for rpd in self.rpds.values() {
+            let rpd_node = quote(&format!("RPD {}\n{}", rpd.ip, rpd.mac));
+            out.push_str(&format!("    {rpd_node} [shape=box];\n"));
+
+            for core in Topology::cores(rpd) {
+                let core_node = quote(&core.label());
+                if nodes.insert(core_node.clone()) {
+                    out.push_str(&format!("    {core_node} [shape=ellipse];\n"));
+                }
+
+                let mut label = core.role().to_string();
+                if let Some(functions) = &core.functions {
+                    label.push_str(&format!("\n{functions}"));
+                }
+                if let Some(set) = core.resource_set {
+                    label.push_str(&format!("\nresource set {set}"));
+                }
+                let style = match core.role() {
+                    "backup" => "dashed",
+                    "principal" => "bold",
+                    _ => "solid",
+                };
+                out.push_str(&format!(
+                    "    {rpd_node} -> {core_node} [label={}, style={style}];\n",
+                    quote(&label)
+                ));
+
+                for ip in &core.candidates {
+                    let candidate = quote(&ip.to_string());
+                    if nodes.insert(candidate.clone()) {
+                        out.push_str(&format!("    {candidate} [shape=ellipse];\n"));
+                    }
+                    out.push_str(&format!(
+                        "    {core_node} -> {candidate} [label=\"backup candidate\", style=dotted];\n"
+                    ));
+                }
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Topology of the RPDs in `captures`, read in order.
+pub fn topology(captures: &[&Path]) -> Result<Topology, ReportError> {
+    let mut topology = Topology::default();
+    for path in captures {
+        read_messages(path, |msg| topology.add(msg))?;
+    }
+
+    Ok(topology)
+}
@@ -0,0 +1,1463 @@
+//! End to end tests: captures built with `CaptureBuilder` run through the decoder and reports.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use pcap_rparser::anonymizer::{anonymize_file, Anonymizer};
+use pcap_rparser::encoders::gcp_encoder::{Eds, GcpMessage};
+use pcap_rparser::encoders::pcap_builder::{
+    pcap_file, CaptureBuilder, Direction, PcapRecord, TcpFlow,
+};
+use pcap_rparser::encoders::rcp_encoder::{RcpMessage, Sequence};
+use pcap_rparser::encoders::tlv_encoder::TlvNode;
+use pcap_rparser::parsers::byte_input::Layer;
+use pcap_rparser::parsers::gcp_parser::{decode_bytes, DecodeError};
+use pcap_rparser::parsers::rcp_parser::Operation;
+use pcap_rparser::reports::bringup::bring_up;
+use pcap_rparser::reports::depi::depi_traffic;
+use pcap_rparser::reports::failover::track_failovers;
+use pcap_rparser::reports::inventory::inventory;
+use pcap_rparser::reports::l2tp::l2tp_sessions;
+use pcap_rparser::reports::perf::perf_series;
+use pcap_rparser::reports::pseudowire::pseudowires;
+use pcap_rparser::reports::ptp::ptp_timing;
+use pcap_rparser::reports::throttle::check_throttling;
+use pcap_rparser::reports::topology::topology;
+use pcap_rparser::reports::{read_messages, ReportFormat, ReportTable};
+use pnet::packet::ipv4::Ipv4Packet;
+use pnet::packet::tcp::{self, TcpFlags, TcpPacket};
+use pnet::packet::Packet;
+use serde_json::Value;
+
+fn rpd() -> SocketAddr {
+    "10.0.0.2:40000".parse().unwrap()
+}
+
+fn core() -> SocketAddr {
+    "10.0.0.1:8190".parse().unwrap()
+}
+
+/// EDS message carrying a REX with one sequence.
+fn eds(transaction_id: u16, response: bool, operation: Operation, tlvs: Vec<TlvNode>) -> Vec<u8> {
+    let eds = Eds {
+        transaction_id,
+        mode: 0,
+        port: 0,
+        channel: 0,
+        vendor_id: 4491,
+        vendor_index: 0,
+        data: RcpMessage::rex(vec![Sequence::new(transaction_id, operation, tlvs)])
+            .encode()
+            .unwrap(),
+    };
+    let message = match response {
+        true => GcpMessage::EdsResponse(eds),
+        false => GcpMessage::EdsRequest(eds),
+    };
+    message.encode(0).unwrap()
+}
+
+/// Notify message carrying an NTF with one sequence, as the RPD sends them.
+fn notify(transaction_id: u16, tlvs: Vec<TlvNode>) -> Vec<u8> {
+    let ntf = RcpMessage::ntf(vec![Sequence::new(transaction_id, Operation::Write, tlvs)]);
+    let message = GcpMessage::NotifyRequest {
+        transaction_id,
+        mode: 0,
+        status: 0,
+        event_code: 0,
+        event_data: ntf.encode().unwrap(),
+    };
+    message.encode(0).unwrap()
+}
+
+/// RpdCapabilities (50) with the RpdIdentification (50.19) of an RPD.
+fn capabilities(vendor: &str, model: &str) -> Vec<TlvNode> {
+    vec![TlvNode::complex(
+        50,
+        vec![
+            TlvNode::u16(2, 2),
+            TlvNode::complex(
+                19,
+                vec![TlvNode::string(1, vendor), TlvNode::string(3, model)],
+            ),
+        ],
+    )]
+}
+
+fn read_capabilities() -> Vec<u8> {
+    eds(
+        1,
+        false,
+        Operation::Read,
+        vec![TlvNode::complex(50, vec![TlvNode::bytes(2, &[])])],
+    )
+}
+
+/// Capture file removed when the test ends.
+struct TempCapture(PathBuf);
+
+impl TempCapture {
+    fn new(name: &str, pcap: &[u8]) -> Self {
+        let file = format!("pcap_rparser-{}-{name}.pcap", std::process::id());
+        let path = std::env::temp_dir().join(file);
+        std::fs::write(&path, pcap).unwrap();
+        TempCapture(path)
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        std::fs::read(&self.0).unwrap()
+    }
+
+    /// Decoder output for the capture.
+    fn decode(&self) -> String {
+        self.decode_with(&[])
+    }
+
+    fn decode_with(&self, args: &[&str]) -> String {
+        let out = Command::new(env!("CARGO_BIN_EXE_pcap_rparser"))
+            .args(args)
+            .arg(&self.0)
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        String::from_utf8(out.stdout).unwrap()
+    }
+
+    /// Frame and Transaction ID of every message the reports read.
+    fn messages(&self) -> Vec<(usize, u16)> {
+        let mut messages = Vec::new();
+        read_messages(&self.0, |m| messages.push((m.frame, m.transaction_id))).unwrap();
+        messages
+    }
+
+    /// Inventory report rows, as JSON.
+    fn inventory(&self) -> Vec<Value> {
+        rows(&inventory(&[&self.0]).unwrap().table())
+    }
+}
+
+/// Rows of a report, as JSON.
+fn rows(table: &ReportTable) -> Vec<Value> {
+    serde_json::from_str(&table.render(ReportFormat::Json)).unwrap()
+}
+
+impl Drop for TempCapture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn decodes_a_request_and_response() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let response = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let pcap = CaptureBuilder::new()
+        .handshake(&mut flow)
+        .send(&mut flow, Direction::FromServer, &read_capabilities())
+        .send(&mut flow, Direction::FromClient, &response)
+        .build();
+    let capture = TempCapture::new("single", &pcap);
+
+    let out = capture.decode();
+    assert!(out.contains("Frame: 4, Arrival: 1.3000, sIP: 10.0.0.1, dIP: 10.0.0.2"));
+    assert!(out.contains("Operation: (1) - Read\n"));
+    assert!(out.contains("Frame: 5, Arrival: 1.4000, sIP: 10.0.0.2, dIP: 10.0.0.1"));
+    assert!(out.contains("Operation: (4) - ReadResponse\n"));
+    assert!(out.contains("VendorName: Acme\n"));
+    assert!(out.contains("ModelNumber: R1\n"));
+
+    assert_eq!(capture.messages(), [(4, 1), (5, 1)]);
+    let rows = capture.inventory();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["ip"], "10.0.0.2");
+    assert_eq!(rows[0]["vendor"], "Acme");
+    assert_eq!(rows[0]["model"], "R1");
+    assert_eq!(rows[0]["ds_rf_ports"], 2);
+}
+
+#[test]
+fn packed_and_split_messages() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let first = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let second = eds(2, true, Operation::ReadResponse, capabilities("Acme", "R2"));
+    let third = eds(3, true, Operation::ReadResponse, capabilities("Acme", "R3"));
+    let pcap = CaptureBuilder::new()
+        .handshake(&mut flow)
+        .send_packed(&mut flow, Direction::FromClient, &[&first, &second])
+        .send_segmented(&mut flow, Direction::FromClient, &third, 20)
+        .build();
+    let capture = TempCapture::new("packed", &pcap);
+
+    // The third message is 60 bytes, in frames 5 to 7.
+    assert_eq!(third.len(), 60);
+    let out = capture.decode();
+    let frame = |n: usize| {
+        let start = out.find(&format!("Frame: {n}, ")).unwrap();
+        let end = out[start + 1..]
+            .find("Frame: ")
+            .map_or(out.len(), |e| start + 1 + e);
+        &out[start..end]
+    };
+    assert!(frame(4).contains("ModelNumber: R1\n"));
+    assert!(frame(4).contains("ModelNumber: R2\n"));
+    assert!(frame(5).contains("20 bytes of a GCP message continued in the next segment"));
+    assert!(frame(6).contains("40 bytes of a GCP message continued in the next segment"));
+    assert!(frame(7).contains("ModelNumber: R3\n"));
+
+    // The filter matches the packed messages and the frame completing a split one.
+    let filtered = capture.decode_with(&["--message", "EdsResponse"]);
+    assert!(filtered.contains("Frame: 4, ") && filtered.contains("Frame: 7, "));
+    assert!(!filtered.contains("Frame: 5, ") && !filtered.contains("Frame: 6, "));
+    let filtered = capture.decode_with(&["--tlv", "ModelNumber"]);
+    assert!(filtered.contains("Frame: 7, "));
+    let written = TempCapture::new("packed-written", &[]);
+    let path = written.0.to_str().unwrap();
+    capture.decode_with(&["--message", "EdsResponse", "--write", path]);
+    assert_eq!(frames(&written.bytes()).len(), 2);
+    // The summary counts the split message with the frame completing it.
+    let summary = capture.decode_with(&["--summary"]);
+    assert!(summary.contains("* Summary: 7 frames, 2 with GCP messages\n"));
+    assert!(summary.contains("(7) ResponseExchangeDataStructures: 3\n"));
+    // The reports too.
+    assert_eq!(capture.messages(), [(4, 1), (4, 2), (7, 3)]);
+    assert_eq!(capture.inventory()[0]["model"], "R3");
+}
+
+#[test]
+fn reports_skip_a_message_missing_a_segment() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let first = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let second = eds(2, true, Operation::ReadResponse, capabilities("Acme", "R2"));
+    let head = flow.segment(Direction::FromClient, TcpFlags::ACK, &first[..30]);
+    // The segment carrying the end of the first message was not captured.
+    flow.segment(Direction::FromClient, TcpFlags::ACK, &first[30..]);
+    let pcap = CaptureBuilder::new()
+        .segment(&head)
+        .send(&mut flow, Direction::FromClient, &second)
+        .build();
+    let capture = TempCapture::new("gap", &pcap);
+
+    assert_eq!(capture.messages(), [(2, 2)]);
+    assert!(capture.decode().contains("ModelNumber: R2\n"));
+}
+
+#[test]
+fn reports_read_linux_cooked_captures_with_nanosecond_timestamps() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let response = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let builder = CaptureBuilder::new().send(&mut flow, Direction::FromClient, &response);
+    // Linux cooked header in place of the Ethernet one, half a second in nanoseconds.
+    let mut data = vec![0, 0, 0, 1, 0, 6, 2, 0, 0, 0, 0, 2, 0, 0, 0x08, 0x00];
+    data.extend_from_slice(&builder.records()[0].data[14..]);
+    let record = PcapRecord {
+        ts_usec: 500_000_000,
+        data,
+        ..builder.records()[0].clone()
+    };
+    let mut pcap = pcap_file(&[record]);
+    pcap[..4].copy_from_slice(&0xa1b2_3c4du32.to_le_bytes());
+    pcap[20..24].copy_from_slice(&113u32.to_le_bytes());
+    let capture = TempCapture::new("cooked", &pcap);
+
+    let mut messages = Vec::new();
+    read_messages(&capture.0, |m| {
+        messages.push((m.frame, m.time, m.src, m.src_mac.to_string()))
+    })
+    .unwrap();
+    assert_eq!(
+        messages,
+        [(1, 1_500_000, rpd(), "00:00:00:00:00:00".to_string())]
+    );
+    let rows = capture.inventory();
+    assert_eq!(rows[0]["model"], "R1");
+    assert_eq!(rows[0]["first_seen"], "1970-01-01T00:00:01.500000Z");
+}
+
+#[test]
+fn reports_stop_at_a_truncated_record() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let first = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let second = eds(2, true, Operation::ReadResponse, capabilities("Acme", "R2"));
+    let pcap = CaptureBuilder::new()
+        .send(&mut flow, Direction::FromClient, &first)
+        .send(&mut flow, Direction::FromClient, &second)
+        .build();
+    let capture = TempCapture::new("truncated", &pcap[..pcap.len() - 20]);
+
+    assert_eq!(capture.messages(), [(1, 1)]);
+    assert_eq!(capture.inventory()[0]["model"], "R1");
+}
+
+#[test]
+fn inventory_lists_only_the_rpds_reporting_their_capabilities() {
+    let mut reported = TcpFlow::new(rpd(), core());
+    let mut unanswered = TcpFlow::new("10.0.0.3:40000".parse().unwrap(), core());
+    let response = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let pcap = CaptureBuilder::new()
+        .send(&mut reported, Direction::FromServer, &read_capabilities())
+        .send(&mut unanswered, Direction::FromServer, &read_capabilities())
+        .send(&mut reported, Direction::FromClient, &response)
+        .build();
+    let capture = TempCapture::new("inventory", &pcap);
+
+    assert_eq!(capture.messages(), [(1, 1), (2, 1), (3, 1)]);
+    let rows = capture.inventory();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["ip"], "10.0.0.2");
+    assert_eq!(rows[0]["first_seen"], "1970-01-01T00:00:01.002000Z");
+}
+
+#[test]
+fn filter_uses_the_capture_timestamp_precision() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let response = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let builder = CaptureBuilder::new().send(&mut flow, Direction::FromClient, &response);
+    // Half a second in nanoseconds, 500 seconds if read as microseconds.
+    let record = PcapRecord {
+        ts_usec: 500_000_000,
+        ..builder.records()[0].clone()
+    };
+    let mut pcap = pcap_file(&[record]);
+    pcap[..4].copy_from_slice(&0xa1b2_3c4du32.to_le_bytes());
+    let capture = TempCapture::new("nanosecond", &pcap);
+
+    assert!(capture
+        .decode_with(&["--from", "1.4", "--to", "1.6"])
+        .contains("Frame: 1, "));
+    assert!(!capture.decode_with(&["--to", "1.4"]).contains("Frame: 1, "));
+}
+
+#[test]
+fn decodes_a_capture_from_a_pipe_as_it_arrives() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let response = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let builder = CaptureBuilder::new()
+        .send(&mut flow, Direction::FromServer, &read_capabilities())
+        .send(&mut flow, Direction::FromClient, &response)
+        .send(&mut flow, Direction::FromClient, &response);
+    let pcap = builder.build();
+    let second = pcap.len() - 2 * (16 + builder.records()[2].data.len());
+
+    let mut decoder = Command::new(env!("CARGO_BIN_EXE_pcap_rparser"))
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = decoder.stdin.take().unwrap();
+    let stdout = BufReader::new(decoder.stdout.take().unwrap());
+    let (send, lines) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in stdout.lines() {
+            send.send(line.unwrap()).unwrap();
+        }
+    });
+    // Waits for the decoder to print the frame.
+    let decoded = |n: usize| {
+        let start = format!("Frame: {n}, ");
+        while let Ok(line) = lines.recv_timeout(Duration::from_secs(10)) {
+            if line.starts_with(&start) {
+                return true;
+            }
+        }
+        false
+    };
+
+    // The first frame is decoded while the second one is still being written.
+    stdin.write_all(&pcap[..second + 30]).unwrap();
+    assert!(decoded(1));
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(lines.try_iter().all(|l| !l.starts_with("Frame: 2, ")));
+
+    // The rest of the second frame, then a third one cut short by the end of the input.
+    stdin
+        .write_all(&pcap[second + 30..pcap.len() - 20])
+        .unwrap();
+    drop(stdin);
+    let status = decoder.wait().unwrap();
+    assert!(status.success());
+    let rest: Vec<String> = lines.iter().collect();
+    assert!(rest.iter().any(|l| l.starts_with("Frame: 2, ")));
+    assert!(!rest.iter().any(|l| l.starts_with("Frame: 3, ")));
+}
+
+#[test]
+fn decoder_reports_malformed_ip_packets() {
+    let ethernet = |ethertype: [u8; 2], ip: &[u8]| {
+        let mut frame = vec![0; 12];
+        frame.extend_from_slice(&ethertype);
+        frame.extend_from_slice(ip);
+        frame
+    };
+    let pcap = CaptureBuilder::new()
+        .frame(ethernet([0x08, 0x00], &[0x45, 0, 0]))
+        .frame(ethernet([0x86, 0xdd], &[0x60, 0, 0, 0]))
+        .build();
+    let capture = TempCapture::new("malformed-ip", &pcap);
+
+    let out = capture.decode();
+    assert!(out.contains("Frame: 1, Arrival: 1.0, Malformed IPv4 packet, Size: 3\n"));
+    assert!(out.contains("Frame: 2, Arrival: 1.1000, Malformed IPv6 packet, Size: 4\n"));
+}
+
+#[test]
+fn anonymizer_keeps_the_capture_format() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let response = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let mut pcap = CaptureBuilder::new()
+        .handshake(&mut flow)
+        .send(&mut flow, Direction::FromClient, &response)
+        .build();
+    // Nanosecond timestamps, a frame longer on the wire than captured, and a last record
+    // cut short.
+    pcap[..4].copy_from_slice(&0xa1b2_3c4du32.to_le_bytes());
+    let last = pcap.len() - response.len() - 54 - 16;
+    pcap[last + 12..last + 16].copy_from_slice(&1500u32.to_le_bytes());
+    pcap.extend_from_within(24..60);
+    let input = TempCapture::new("anonymizer-in", &pcap);
+    let output = TempCapture::new("anonymizer-out", &[]);
+
+    let mut anonymizer = Anonymizer::new(b"key");
+    let frames = anonymize_file(&mut anonymizer, &input.0, &output.0).unwrap();
+    assert_eq!(frames, 4);
+
+    let anonymized = output.bytes();
+    assert_eq!(anonymized[..24], pcap[..24]);
+    assert_eq!(anonymized.len(), last + 16 + response.len() + 54);
+    assert_eq!(anonymized[last + 12..last + 16], 1500u32.to_le_bytes());
+    assert_ne!(anonymized[last + 16..], pcap[last + 16..anonymized.len()]);
+    assert_eq!(output.messages(), [(4, 1)]);
+}
+
+/// RpdCapabilities (50) with the DeviceAlias (50.19.8) and SerialNumber (50.19.9) of an RPD.
+fn identification(alias: &str, serial: &str) -> Vec<TlvNode> {
+    vec![TlvNode::complex(
+        50,
+        vec![TlvNode::complex(
+            19,
+            vec![TlvNode::string(8, alias), TlvNode::string(9, serial)],
+        )],
+    )]
+}
+
+/// Frames of a little-endian capture.
+fn frames(pcap: &[u8]) -> Vec<Vec<u8>> {
+    let mut frames = Vec::new();
+    let mut rest = &pcap[24..];
+    while rest.len() >= 16 {
+        let len = u32::from_le_bytes(rest[8..12].try_into().unwrap()) as usize;
+        frames.push(rest[16..16 + len].to_vec());
+        rest = &rest[16 + len..];
+    }
+    frames
+}
+
+/// Anonymized frames of the capture, and the anonymizer that wrote them.
+fn anonymize(name: &str, pcap: &[u8]) -> (Vec<Vec<u8>>, Anonymizer) {
+    let input = TempCapture::new(&format!("{name}-in"), pcap);
+    let output = TempCapture::new(&format!("{name}-out"), &[]);
+    let mut anonymizer = Anonymizer::new(b"key");
+    anonymize_file(&mut anonymizer, &input.0, &output.0).unwrap();
+    (frames(&output.bytes()), anonymizer)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// Whether the TCP checksum of an Ethernet/IPv4 frame is right.
+fn tcp_checksum_ok(frame: &[u8]) -> bool {
+    let ip = Ipv4Packet::new(&frame[14..]).unwrap();
+    let tcp = TcpPacket::new(ip.payload()).unwrap();
+    tcp::ipv4_checksum(&tcp, &ip.get_source(), &ip.get_destination()) == tcp.get_checksum()
+}
+
+#[test]
+fn anonymizer_rewrites_messages_split_over_segments() {
+    let response = eds(
+        1,
+        true,
+        Operation::ReadResponse,
+        identification("R1", "SN-0001"),
+    );
+    let mut flow = TcpFlow::new(rpd(), core());
+    let whole = CaptureBuilder::new()
+        .handshake(&mut flow)
+        .send(&mut flow, Direction::FromClient, &response)
+        .build();
+    let mut flow = TcpFlow::new(rpd(), core());
+    let split = CaptureBuilder::new()
+        .handshake(&mut flow)
+        .send_segmented(&mut flow, Direction::FromClient, &response, 20)
+        .build();
+
+    let (whole, _) = anonymize("anonymizer-whole", &whole);
+    let (split, anonymizer) = anonymize("anonymizer-split", &split);
+    assert_eq!(split.len(), 3 + response.len().div_ceil(20));
+    let payload: Vec<u8> = split[3..].iter().flat_map(|f| f[54..].to_vec()).collect();
+    assert_eq!(payload, whole[3][54..]);
+    assert_ne!(payload, response);
+    assert!(!split.iter().any(|f| contains(f, b"SN-0001")));
+    assert!(split.iter().all(|f| tcp_checksum_ok(f)));
+    assert_eq!(anonymizer.blanked(), 0);
+}
+
+#[test]
+fn anonymizer_zeros_a_message_missing_a_segment() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let first = eds(
+        1,
+        true,
+        Operation::ReadResponse,
+        identification("R1", "SN-0001"),
+    );
+    let second = eds(
+        2,
+        true,
+        Operation::ReadResponse,
+        identification("R2", "SN-0002"),
+    );
+    let head = flow.segment(Direction::FromClient, TcpFlags::ACK, &first[..30]);
+    // The segment carrying the end of the first message was not captured.
+    flow.segment(Direction::FromClient, TcpFlags::ACK, &first[30..]);
+    let pcap = CaptureBuilder::new()
+        .segment(&head)
+        .send(&mut flow, Direction::FromClient, &second)
+        .build();
+
+    let (frames, anonymizer) = anonymize("anonymizer-gap", &pcap);
+    assert_eq!(frames[0][54..], [0; 30]);
+    assert!(tcp_checksum_ok(&frames[0]));
+    assert_eq!(frames[1].len(), 54 + second.len());
+    assert!(!contains(&frames[1], b"SN-0002"));
+    assert_eq!(anonymizer.blanked(), 30);
+}
+
+#[test]
+fn anonymizer_keeps_vlan_tags() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let response = eds(
+        1,
+        true,
+        Operation::ReadResponse,
+        identification("R1", "SN-0001"),
+    );
+    let builder = CaptureBuilder::new().handshake(&mut flow).send(
+        &mut flow,
+        Direction::FromClient,
+        &response,
+    );
+    // 802.1Q tag, VLAN 100, after the MAC addresses.
+    let tag = [0x81, 0x00, 0x00, 0x64];
+    let tagged: Vec<PcapRecord> = builder
+        .records()
+        .iter()
+        .map(|r| PcapRecord {
+            data: [&r.data[..12], &tag, &r.data[12..]].concat(),
+            ..r.clone()
+        })
+        .collect();
+
+    let (untagged, _) = anonymize("anonymizer-untagged", &builder.build());
+    let (tagged, _) = anonymize("anonymizer-tagged", &pcap_file(&tagged));
+    for (tagged, untagged) in tagged.iter().zip(&untagged) {
+        assert_eq!(tagged[12..16], tag);
+        assert_eq!([&tagged[..12], &tagged[16..]].concat(), *untagged);
+    }
+    assert!(!contains(&tagged[3], b"SN-0001"));
+}
+
+#[test]
+fn anonymizer_mapping_is_csv() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let response = eds(
+        1,
+        true,
+        Operation::ReadResponse,
+        identification("Rack 1, \"A\"", "SN"),
+    );
+    let pcap = CaptureBuilder::new()
+        .send(&mut flow, Direction::FromClient, &response)
+        .build();
+
+    let (_, anonymizer) = anonymize("anonymizer-csv", &pcap);
+    let csv = anonymizer.mapping_csv();
+    assert!(csv.starts_with("kind,original,anonymized\n"));
+    assert!(csv.contains("\ntext,\"Rack 1, \"\"A\"\"\","));
+    assert!(csv.contains("\nip,10.0.0.2,10."));
+}
+
+#[test]
+fn malformed_bytes_are_errors() {
+    let message = eds(1, false, Operation::Read, vec![TlvNode::u8(1, 0)]);
+    assert_eq!(decode_bytes(&message, Layer::Gcp), Ok(()));
+    assert_eq!(
+        decode_bytes(&message[..message.len() - 1], Layer::Gcp),
+        Err(DecodeError::Truncated(Layer::Gcp))
+    );
+    // A whole header announcing a body too short for the message.
+    assert_eq!(
+        decode_bytes(&[0, 6, 0, 0, 0, 5, 0, 70, 0, 1, 0], Layer::Gcp),
+        Err(DecodeError::Truncated(Layer::Gcp))
+    );
+    assert_eq!(
+        decode_bytes(&[2], Layer::Rcp),
+        Err(DecodeError::Truncated(Layer::Rcp))
+    );
+}
+
+#[test]
+fn hex_view_counts_offsets_from_the_frame() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let first = eds(1, true, Operation::ReadResponse, capabilities("Acme", "R1"));
+    let second = eds(2, true, Operation::ReadResponse, capabilities("Acme", "R2"));
+    let third = eds(3, true, Operation::ReadResponse, capabilities("Acme", "R3"));
+    let pcap = CaptureBuilder::new()
+        .handshake(&mut flow)
+        .send_packed(&mut flow, Direction::FromClient, &[&first, &second])
+        .send_segmented(&mut flow, Direction::FromClient, &third, 40)
+        .build();
+    let capture = TempCapture::new("hex", &pcap);
+
+    // ModelNumber (50.19.3) of the second message, after the Ethernet, IPv4 and TCP headers.
+    let model = [3, 0, 2, b'R', b'2'];
+    let at = second.windows(5).position(|w| w == model).unwrap();
+    let offset = 54 + first.len() + at;
+    let out = capture.decode_with(&["--hex"]);
+    assert!(out.contains(&format!(
+        "ModelNumber: R2  [{offset:#06x}] 03 00 02 | 52 32\n"
+    )));
+    let offset = 54 + first.len();
+    assert!(out.contains(&format!(
+        "Transaction Identifier: 2  [{offset:#06x}] 00 02\n"
+    )));
+    // The message completed by the last segment is not all in its frame.
+    assert!(out.contains("Transaction Identifier: 3  00 03\n"));
+    assert!(out.contains("ModelNumber: R3  03 00 02 | 52 33\n"));
+}
+
+#[test]
+fn topology_joins_the_cores_by_core_id() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let aux: IpAddr = "10.0.0.5".parse().unwrap();
+    let identification = |index: u8, id: u8, ip: IpAddr, functions: u16| {
+        TlvNode::complex(
+            60,
+            vec![
+                TlvNode::u8(1, index),
+                TlvNode::bytes(2, &[id]),
+                TlvNode::ip(3, ip),
+                TlvNode::u16(10, functions),
+            ],
+        )
+    };
+    let cores = eds(
+        1,
+        true,
+        Operation::ReadResponse,
+        vec![
+            identification(0, 0xa, core().ip(), 0b11),
+            identification(1, 0xb, aux, 0b100),
+        ],
+    );
+    // The connection status only names the Cores by CoreId.
+    let status = eds(
+        2,
+        true,
+        Operation::ReadResponse,
+        vec![TlvNode::complex(
+            105,
+            vec![
+                TlvNode::u8(1, 1),
+                TlvNode::bytes(2, &[0xb]),
+                TlvNode::u8(3, 1),
+            ],
+        )],
+    );
+    let pcap = CaptureBuilder::new()
+        .send(&mut flow, Direction::FromClient, &cores)
+        .send(&mut flow, Direction::FromClient, &status)
+        .build();
+    let capture = TempCapture::new("topology", &pcap);
+
+    let topology = topology(&[&capture.0]).unwrap();
+    let rows = rows(&topology.table());
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["role"], "principal");
+    assert_eq!(rows[0]["core_ip"], "10.0.0.1");
+    assert_eq!(rows[0]["gcp_connected"], true);
+    assert_eq!(rows[0]["functions"][1]["name"], "DOCSIS");
+    assert_eq!(rows[0]["functions"][1]["set"], true);
+    assert_eq!(rows[1]["role"], "auxiliary");
+    assert_eq!(rows[1]["core_id"], "0B");
+    assert_eq!(rows[1]["core_ip"], "10.0.0.5");
+    assert_eq!(rows[1]["gcp_connected"], false);
+    assert!(!rows[1]["connection_status"].is_null());
+    assert!(topology
+        .dot()
+        .contains("-> \"0B 10.0.0.5\" [label=\"auxiliary\\nBroadcast Video\", style=solid];"));
+}
+
+/// RpdBackupCoreStatus (107) of the backup Core with this CoreId.
+fn backup_core_status(core_id: u8, status: u8) -> Vec<TlvNode> {
+    vec![TlvNode::complex(
+        107,
+        vec![
+            TlvNode::u8(1, 0),
+            TlvNode::bytes(2, &[core_id]),
+            TlvNode::u8(3, status),
+        ],
+    )]
+}
+
+#[test]
+fn failover_pairs_the_lost_core_with_the_backup_taking_over() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let timeout = vec![TlvNode::complex(
+        86,
+        vec![TlvNode::u8(1, 5), TlvNode::ip(10, core().ip())],
+    )];
+    // RpdGcpBackupCoreStatus 3 Backup, then 2 Active.
+    let pcap = CaptureBuilder::new()
+        .step(100_000)
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &eds(1, true, Operation::ReadResponse, backup_core_status(0xb, 3)),
+        )
+        .send(&mut flow, Direction::FromClient, &notify(2, timeout))
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &eds(3, true, Operation::ReadResponse, backup_core_status(0xb, 3)),
+        )
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &eds(4, true, Operation::ReadResponse, backup_core_status(0xb, 2)),
+        )
+        .build();
+    let capture = TempCapture::new("failover", &pcap);
+
+    let tracker = track_failovers(&[&capture.0]).unwrap();
+    // The repeated Backup status is not an event.
+    let events = rows(&tracker.events());
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0]["detail"], "Backup");
+    assert_eq!(events[1]["event"], "TimeOutNotification");
+    assert_eq!(events[2]["detail"], "Backup -> Active");
+
+    let failovers = rows(&tracker.failovers());
+    assert_eq!(failovers.len(), 1);
+    assert_eq!(failovers[0]["lost_core"], "10.0.0.1");
+    assert_eq!(failovers[0]["cause"], "TimeOutNotification");
+    assert_eq!(failovers[0]["lost_frame"], 2);
+    assert_eq!(failovers[0]["new_core"], "0B");
+    assert_eq!(failovers[0]["recovered_frame"], 4);
+    assert_eq!(failovers[0]["gap_ms"], 200.0);
+    assert_eq!(failovers[0]["outcome"], "taken over");
+}
+
+#[test]
+fn failover_is_unresolved_without_a_core_taking_over() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    // A backup Core first seen Active took over before the capture started, then hands over.
+    let pcap = CaptureBuilder::new()
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &eds(1, true, Operation::ReadResponse, backup_core_status(0xb, 2)),
+        )
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &eds(2, true, Operation::ReadResponse, backup_core_status(0xb, 5)),
+        )
+        .build();
+    let capture = TempCapture::new("failover-unresolved", &pcap);
+
+    let failovers = rows(&track_failovers(&[&capture.0]).unwrap().failovers());
+    assert_eq!(failovers.len(), 1);
+    assert_eq!(failovers[0]["lost_core"], "0B");
+    assert_eq!(failovers[0]["cause"], "RpdBackupCoreStatus");
+    assert!(failovers[0]["recovered_at"].is_null());
+    assert_eq!(failovers[0]["outcome"], "unresolved");
+
+    // The HandoverNotification names the Core the RPD sends it to.
+    let handover = vec![TlvNode::complex(86, vec![TlvNode::u8(1, 10)])];
+    let pcap = CaptureBuilder::new()
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &eds(3, true, Operation::ReadResponse, backup_core_status(0xb, 5)),
+        )
+        .send(&mut flow, Direction::FromClient, &notify(4, handover))
+        .build();
+    let capture = TempCapture::new("failover-handover", &pcap);
+    let failovers = rows(&track_failovers(&[&capture.0]).unwrap().failovers());
+    assert_eq!(failovers.len(), 1);
+    assert_eq!(failovers[0]["new_core"], "10.0.0.1");
+    assert_eq!(failovers[0]["outcome"], "taken over");
+}
+
+#[test]
+fn bring_up_steps_follow_the_rpd_states() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let top_level = |state: u8| vec![TlvNode::complex(87, vec![TlvNode::u8(1, state)])];
+    let notification = |tlvs: Vec<TlvNode>| vec![TlvNode::complex(86, tlvs)];
+    let configuration = eds(4, false, Operation::Write, capabilities("Acme", "R1"));
+    // TopLevelRpdState 2 NetworkAuthentication, 5 ConnectPrincipalCore and
+    // 7 OperationalPrincipalCore, a StartUpNotification and a PtpResult 0, not synchronized.
+    let pcap = CaptureBuilder::new()
+        .step(100_000)
+        .send(&mut flow, Direction::FromClient, &notify(1, top_level(2)))
+        .send(&mut flow, Direction::FromClient, &notify(2, top_level(5)))
+        .send(&mut flow, Direction::FromClient, &notify(3, top_level(5)))
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &notify(4, notification(vec![TlvNode::u8(1, 1)])),
+        )
+        .send(&mut flow, Direction::FromServer, &configuration)
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &notify(5, notification(vec![TlvNode::u8(1, 3), TlvNode::u8(5, 0)])),
+        )
+        .send(&mut flow, Direction::FromClient, &notify(6, top_level(7)))
+        .build();
+    let capture = TempCapture::new("bringup", &pcap);
+
+    let bring_up = bring_up(&[&capture.0]).unwrap();
+    // The repeated ConnectPrincipalCore is not an event.
+    let events: Vec<(Value, Value)> = rows(&bring_up.events())
+        .iter()
+        .map(|e| (e["frame"].clone(), e["event"].clone()))
+        .collect();
+    assert_eq!(events.len(), 5);
+    assert_eq!(events[1], (Value::from(2), Value::from("TopLevelRpdState")));
+    assert_eq!(
+        events[2],
+        (Value::from(4), Value::from("StartUpNotification"))
+    );
+
+    let steps = rows(&bring_up.steps());
+    let step = |name: &str| {
+        let step = steps.iter().find(|s| s["step"] == name).unwrap();
+        (
+            step["status"].clone(),
+            step["duration_ms"].clone(),
+            step["frame"].clone(),
+        )
+    };
+    let names: Vec<&Value> = steps.iter().map(|s| &s["step"]).collect();
+    assert_eq!(
+        names,
+        [
+            "Authentication",
+            "PrincipalCoreConnect",
+            "Configuration",
+            "PtpLock",
+            "AuxCoreConnect",
+            "Operational"
+        ]
+    );
+    assert_eq!(
+        step("Authentication"),
+        ("completed".into(), 100.0.into(), 2.into())
+    );
+    assert_eq!(
+        step("PrincipalCoreConnect"),
+        ("completed".into(), 200.0.into(), 4.into())
+    );
+    assert_eq!(
+        step("Configuration"),
+        ("completed".into(), 200.0.into(), 7.into())
+    );
+    // The PTP lock never completes, its start is the frame reported.
+    assert_eq!(
+        step("PtpLock"),
+        ("NOT COMPLETED".into(), Value::Null, 6.into())
+    );
+    assert_eq!(
+        step("AuxCoreConnect"),
+        ("not seen".into(), Value::Null, Value::Null)
+    );
+    assert_eq!(
+        step("Operational"),
+        ("completed".into(), 0.0.into(), 7.into())
+    );
+}
+
+/// RfChannel (16) of an upstream SC-QAM channel with its UsScQamChannelPerf (16.78): the
+/// HcsErrors, the IUC 5 codewords good, corrected and uncorrectable, and the SNR in tenths of dB.
+fn us_channel_perf(hcs_errors: u64, codewords: [u64; 3], snr: u16) -> Vec<TlvNode> {
+    let iuc = TlvNode::complex(
+        1,
+        vec![
+            TlvNode::u8(1, 5),
+            TlvNode::u64(10, codewords[0]),
+            TlvNode::u64(11, codewords[1]),
+            TlvNode::u64(12, codewords[2]),
+        ],
+    );
+    vec![TlvNode::complex(
+        16,
+        vec![
+            TlvNode::complex(
+                12,
+                vec![TlvNode::u8(1, 0), TlvNode::u8(2, 5), TlvNode::u8(3, 2)],
+            ),
+            TlvNode::complex(
+                78,
+                vec![iuc, TlvNode::u64(3, hcs_errors), TlvNode::u16(7, snr)],
+            ),
+        ],
+    )]
+}
+
+#[test]
+fn perf_computes_rates_ratios_and_trends_between_reads() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let read = |n: u16, hcs_errors, codewords, snr| {
+        eds(
+            n,
+            true,
+            Operation::ReadResponse,
+            us_channel_perf(hcs_errors, codewords, snr),
+        )
+    };
+    // Reads two seconds apart, the HcsErrors counter reset before the last one.
+    let pcap = CaptureBuilder::new()
+        .step(2_000_000)
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &read(1, 100, [1000, 10, 0], 300),
+        )
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &read(2, 110, [1910, 90, 10], 285),
+        )
+        .send(
+            &mut flow,
+            Direction::FromClient,
+            &read(3, 4, [2000, 90, 10], 285),
+        )
+        .build();
+    let capture = TempCapture::new("perf", &pcap);
+
+    let rows = rows(&perf_series(&[&capture.0]).unwrap().rates());
+    let metric = |frame: usize, name: &str| {
+        let row = rows
+            .iter()
+            .find(|r| r["frame"] == frame && r["metric"] == name)
+            .unwrap();
+        (row["value"].clone(), row["note"].clone())
+    };
+    assert!(rows
+        .iter()
+        .all(|r| r["channel_type"] == "UsAtdma" && r["channel_index"] == 2));
+    assert_eq!(metric(2, "HcsErrors/s"), (5.0.into(), "".into()));
+    assert_eq!(metric(2, "Iuc5 GoodFecCw/s"), (455.0.into(), "".into()));
+    assert_eq!(metric(2, "Iuc5 CorrectedCwRatio"), (0.08.into(), "".into()));
+    assert_eq!(
+        metric(2, "Iuc5 UncorrectableCwRatio"),
+        (0.01.into(), "".into())
+    );
+    assert_eq!(metric(2, "ChannelSnr"), (28.5.into(), "".into()));
+    assert_eq!(metric(2, "ChannelSnr change"), ((-1.5).into(), "".into()));
+    assert_eq!(
+        metric(3, "HcsErrors/s"),
+        (Value::Null, "counter reset".into())
+    );
+    assert_eq!(
+        metric(3, "Iuc5 UncorrectableCwRatio"),
+        (0.0.into(), "".into())
+    );
+}
+
+/// RpdGlobal (15) EvCfg (15.1) write of the Core.
+fn ev_cfg(transaction_id: u16, tlvs: Vec<TlvNode>) -> Vec<u8> {
+    let ev_cfg = TlvNode::complex(15, vec![TlvNode::complex(1, tlvs)]);
+    eds(transaction_id, false, Operation::Write, vec![ev_cfg])
+}
+
+#[test]
+fn throttle_counts_the_notifies_within_the_interval() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    // EvThrottleAdminStatus 3 stopAtThreshold, at most two Notifies within 10 seconds.
+    let throttled = ev_cfg(
+        1,
+        vec![
+            TlvNode::u8(2, 3),
+            TlvNode::u32(3, 2),
+            TlvNode::u32(4, 10),
+            TlvNode::u8(5, 1),
+        ],
+    );
+    let disabled = ev_cfg(2, vec![TlvNode::u8(5, 0)]);
+    let pcap = CaptureBuilder::new()
+        .send(&mut flow, Direction::FromServer, &throttled)
+        .at(10, 0)
+        .send(&mut flow, Direction::FromClient, &notify(1, Vec::new()))
+        .at(11, 0)
+        .send(&mut flow, Direction::FromClient, &notify(2, Vec::new()))
+        // Ten seconds after the first Notify, which is out of the interval.
+        .at(20, 0)
+        .send(&mut flow, Direction::FromClient, &notify(3, Vec::new()))
+        .at(20, 500_000)
+        .send(&mut flow, Direction::FromClient, &notify(4, Vec::new()))
+        .send(&mut flow, Direction::FromServer, &disabled)
+        .at(40, 0)
+        .send(&mut flow, Direction::FromClient, &notify(5, Vec::new()))
+        .build();
+    let capture = TempCapture::new("throttle", &pcap);
+
+    let check = check_throttling(&[&capture.0]).unwrap();
+    let violations = rows(&check.violations());
+    assert_eq!(violations.len(), 2);
+    assert_eq!(violations[0]["frame"], 5);
+    assert_eq!(
+        violations[0]["reason"],
+        "3 Notifies within 10 s, threshold 2"
+    );
+    assert_eq!(violations[1]["frame"], 7);
+    assert_eq!(violations[1]["reason"], "sent with NotifyEnable 0");
+
+    let summary = rows(&check.summary());
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0]["throttle_admin_status"], "stopAtThreshold");
+    assert_eq!(summary[0]["notifies"], 5);
+    assert_eq!(summary[0]["max_per_interval"], 3);
+    assert_eq!(summary[0]["first_violation"], "1970-01-01T00:00:20.500000Z");
+    assert_eq!(summary[0]["status"], "VIOLATION");
+}
+
+#[test]
+fn throttle_allows_any_count_unconstrained() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    // EvThrottleAdminStatus 1 unconstrained.
+    let unconstrained = ev_cfg(
+        1,
+        vec![TlvNode::u8(2, 1), TlvNode::u32(3, 1), TlvNode::u32(4, 10)],
+    );
+    let mut builder = CaptureBuilder::new().send(&mut flow, Direction::FromServer, &unconstrained);
+    for n in 1..=3 {
+        builder = builder.send(&mut flow, Direction::FromClient, &notify(n, Vec::new()));
+    }
+    let capture = TempCapture::new("throttle-unconstrained", &builder.build());
+
+    let summary = rows(&check_throttling(&[&capture.0]).unwrap().summary());
+    assert_eq!(summary[0]["max_per_interval"], 3);
+    assert_eq!(summary[0]["violations"], 0);
+    assert_eq!(summary[0]["status"], "ok");
+}
+
+/// StaticPwConfig (58) of a pseudowire: the direction config, then the CommonStaticPwConfig
+/// (58.3) with an MPT PwType and the SessionId.
+fn static_pw_config(direction: u8, index: u16, config: Vec<TlvNode>, session_id: u32) -> TlvNode {
+    let mut common = vec![
+        TlvNode::u8(1, direction),
+        TlvNode::u16(2, index),
+        TlvNode::u16(4, 12),
+        TlvNode::u32(8, session_id),
+    ];
+    if direction == 0 {
+        // DS-SCQAM channel 5 of RF port 0.
+        let selector = TlvNode::complex(
+            2,
+            vec![TlvNode::u8(1, 0), TlvNode::u8(2, 3), TlvNode::u8(3, 5)],
+        );
+        common.push(TlvNode::complex(11, vec![TlvNode::u8(1, 0), selector]));
+    }
+    let mut direction_config = vec![TlvNode::u16(1, index)];
+    direction_config.extend(config);
+
+    TlvNode::complex(
+        58,
+        vec![
+            TlvNode::complex(1 + direction, direction_config),
+            TlvNode::complex(3, common),
+        ],
+    )
+}
+
+/// StaticPwStatus (59) of a pseudowire with the session the RPD selected.
+fn static_pw_status(direction: u8, index: u16, session_id: u32) -> TlvNode {
+    TlvNode::complex(
+        59,
+        vec![TlvNode::complex(
+            1,
+            vec![
+                TlvNode::u8(1, direction),
+                TlvNode::u16(2, index),
+                TlvNode::u32(4, session_id),
+            ],
+        )],
+    )
+}
+
+#[test]
+fn pseudowires_join_the_config_with_the_status() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let group: IpAddr = "232.1.1.1".parse().unwrap();
+    let config = eds(
+        1,
+        false,
+        Operation::Write,
+        vec![
+            static_pw_config(
+                0,
+                1,
+                vec![TlvNode::ip(3, group), TlvNode::ip(4, core().ip())],
+                0x100,
+            ),
+            static_pw_config(1, 2, vec![TlvNode::ip(3, core().ip())], 0x200),
+        ],
+    );
+    // The return pseudowire selected another session, the third one is not configured.
+    let status = eds(
+        2,
+        true,
+        Operation::ReadResponse,
+        vec![
+            static_pw_status(0, 1, 0x100),
+            static_pw_status(1, 2, 0x201),
+            static_pw_status(0, 3, 0x300),
+        ],
+    );
+    let pcap = CaptureBuilder::new()
+        .send(&mut flow, Direction::FromServer, &config)
+        .send(&mut flow, Direction::FromClient, &status)
+        .build();
+    let capture = TempCapture::new("pseudowires", &pcap);
+
+    let rows = rows(&pseudowires(&[&capture.0]).unwrap().table());
+    let pw = |direction: &str, index: u16| {
+        rows.iter()
+            .find(|r| r["direction"] == direction && r["index"] == index)
+            .unwrap()
+    };
+    assert_eq!(rows.len(), 3);
+    assert_eq!(pw("forward", 1)["group_address"], "232.1.1.1");
+    assert_eq!(pw("forward", 1)["source_address"], "10.0.0.1");
+    assert_eq!(pw("forward", 1)["channels"], "0/DS-SCQAM/5");
+    assert_eq!(pw("forward", 1)["status"], "ok");
+    assert_eq!(pw("return", 2)["dest_address"], "10.0.0.1");
+    assert_eq!(pw("return", 2)["session_id"], 0x200);
+    assert_eq!(pw("return", 2)["selected_session_id"], 0x201);
+    assert_eq!(pw("return", 2)["status"], "SESSION MISMATCH");
+    assert_eq!(pw("forward", 3)["status"], "no config");
+}
+
+/// Ethernet/IPv4 frame from `src` to `dst` carrying `payload` over the IP `protocol`.
+fn ipv4_frame(src: IpAddr, dst: IpAddr, protocol: u8, payload: &[u8]) -> Vec<u8> {
+    let (src, dst) = match (src, dst) {
+        (IpAddr::V4(src), IpAddr::V4(dst)) => (src, dst),
+        _ => panic!("not IPv4 addresses"),
+    };
+    let mut frame = vec![
+        2,
+        0,
+        0,
+        0,
+        0,
+        dst.octets()[3],
+        2,
+        0,
+        0,
+        0,
+        0,
+        src.octets()[3],
+    ];
+    frame.extend_from_slice(&[0x08, 0x00, 0x45, 0]);
+    frame.extend_from_slice(&(20 + payload.len() as u16).to_be_bytes());
+    frame.extend_from_slice(&[0, 0, 0, 0, 64, protocol, 0, 0]);
+    frame.extend_from_slice(&src.octets());
+    frame.extend_from_slice(&dst.octets());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// L2TPv3 AVP with the M bit set.
+fn avp(vendor_id: u16, typ: u16, value: &[u8]) -> Vec<u8> {
+    let mut avp = (0x8000 | (6 + value.len() as u16)).to_be_bytes().to_vec();
+    avp.extend_from_slice(&vendor_id.to_be_bytes());
+    avp.extend_from_slice(&typ.to_be_bytes());
+    avp.extend_from_slice(value);
+    avp
+}
+
+/// L2TPv3 control message over IP, of this Message Type and with these AVPs.
+fn l2tp_control(message_type: u16, avps: &[Vec<u8>]) -> Vec<u8> {
+    let mut avps: Vec<u8> = avps.concat();
+    avps.splice(0..0, avp(0, 0, &message_type.to_be_bytes()));
+    let mut message = vec![0, 0, 0, 0, 0xc8, 0x03];
+    message.extend_from_slice(&(12 + avps.len() as u16).to_be_bytes());
+    message.extend_from_slice(&[0; 8]);
+    message.extend(avps);
+    message
+}
+
+/// Builds the frames of an L2TPv3 control connection and of one session between the Core
+/// and the RPD, over IP, and closes the session with a CDN.
+fn l2tp_session(builder: CaptureBuilder) -> CaptureBuilder {
+    let (core, rpd) = (core().ip(), rpd().ip());
+    let u32_avp = |typ: u16, n: u32| avp(0, typ, &n.to_be_bytes());
+    let frames = [
+        (
+            core,
+            rpd,
+            l2tp_control(1, &[u32_avp(61, 100), avp(0, 7, b"core-a")]),
+        ),
+        (
+            rpd,
+            core,
+            l2tp_control(2, &[u32_avp(61, 200), avp(0, 7, b"rpd-1")]),
+        ),
+        (core, rpd, l2tp_control(3, &[])),
+        (
+            core,
+            rpd,
+            l2tp_control(
+                10,
+                &[
+                    u32_avp(63, 0x1000),
+                    avp(0, 68, &[0, 12]),
+                    avp(4491, 16, &[0, 1]),
+                ],
+            ),
+        ),
+        (
+            rpd,
+            core,
+            l2tp_control(11, &[u32_avp(63, 0x2000), u32_avp(64, 0x1000)]),
+        ),
+        (
+            core,
+            rpd,
+            l2tp_control(12, &[u32_avp(63, 0x1000), u32_avp(64, 0x2000)]),
+        ),
+    ];
+
+    frames.iter().fold(builder, |builder, (src, dst, message)| {
+        builder.frame(ipv4_frame(*src, *dst, 115, message))
+    })
+}
+
+#[test]
+fn l2tp_follows_the_connection_and_session_setup() {
+    let cdn = l2tp_control(
+        14,
+        &[avp(0, 1, &[0, 3]), avp(0, 63, &0x2000u32.to_be_bytes())],
+    );
+    let pcap = l2tp_session(CaptureBuilder::new().step(10_000))
+        .frame(ipv4_frame(rpd().ip(), core().ip(), 115, &cdn))
+        .build();
+    let capture = TempCapture::new("l2tp", &pcap);
+
+    let sessions = l2tp_sessions(&[&capture.0]).unwrap();
+    let connections = rows(&sessions.connections());
+    assert_eq!(connections.len(), 1);
+    assert_eq!(connections[0]["initiator"], "10.0.0.1");
+    assert_eq!(connections[0]["connection_ids"], "100/200");
+    assert_eq!(connections[0]["initiator_host"], "core-a");
+    assert_eq!(connections[0]["responder_host"], "rpd-1");
+    assert_eq!(connections[0]["state"], "established");
+    assert_eq!(connections[0]["sessions"], 1);
+
+    let sessions = rows(&sessions.sessions());
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["local_session_id"], 0x1000);
+    assert_eq!(sessions[0]["remote_session_id"], 0x2000);
+    assert_eq!(sessions[0]["pw_type"], "MPTPW, MPT Pseudowire Type");
+    assert_eq!(
+        sessions[0]["depi_pw_subtype"],
+        "MPT-DEPI-PW, MPT DEPI Pseudowire Subtype"
+    );
+    // From the ICRQ of frame 4 to the ICCN of frame 6.
+    assert_eq!(sessions[0]["setup_ms"], 20.0);
+    assert_eq!(sessions[0]["state"], "closed");
+    assert_eq!(sessions[0]["result"], "Result 3");
+}
+
+/// DEPI data packet over IP: Session ID, then the sublayer header with this sequence
+/// number and the payload.
+fn depi_data(session_id: u32, sequence: u16, payload: &[u8]) -> Vec<u8> {
+    let mut packet = session_id.to_be_bytes().to_vec();
+    packet.extend_from_slice(&[0x40, 0]);
+    packet.extend_from_slice(&sequence.to_be_bytes());
+    packet.extend_from_slice(payload);
+    packet
+}
+
+#[test]
+fn depi_counts_the_sequence_gaps_of_each_session() {
+    let mut flow = TcpFlow::new(rpd(), core());
+    let group: IpAddr = "232.1.1.1".parse().unwrap();
+    let config = eds(
+        1,
+        false,
+        Operation::Write,
+        vec![
+            static_pw_config(
+                0,
+                1,
+                vec![TlvNode::ip(3, group), TlvNode::ip(4, core().ip())],
+                0x100,
+            ),
+            static_pw_config(1, 2, vec![TlvNode::ip(3, core().ip())], 0x200),
+        ],
+    );
+    let mut ts = vec![0; 2 * 188];
+    ts[0] = 0x47;
+    ts[188] = 0x47;
+    // Sequence 5 skips 2 to 4, then 2 comes late.
+    let mut builder = CaptureBuilder::new().send(&mut flow, Direction::FromServer, &config);
+    for sequence in [0, 1, 5, 2, 6] {
+        let packet = depi_data(0x100, sequence, &ts);
+        builder = builder.frame(ipv4_frame(core().ip(), group, 115, &packet));
+    }
+    let stray = depi_data(0x300, 0, &[0; 64]);
+    let pcap = builder
+        .frame(ipv4_frame(core().ip(), rpd().ip(), 115, &stray))
+        .build();
+    let capture = TempCapture::new("depi", &pcap);
+
+    let rows = rows(&depi_traffic(&[&capture.0]).unwrap().table());
+    let session = |id: u32| rows.iter().find(|r| r["session_id"] == id).unwrap();
+    assert_eq!(rows.len(), 3);
+    let forward = session(0x100);
+    assert_eq!(forward["dst"], "232.1.1.1");
+    assert_eq!(forward["session_id"], 0x100);
+    assert_eq!(
+        forward["configured_by"],
+        "StaticPwConfig 10.0.0.2 forward 1"
+    );
+    assert_eq!(forward["payload"], "MPT");
+    assert_eq!(forward["packets"], 5);
+    assert_eq!(forward["bytes"], 5 * (4 + 2 * 188));
+    assert_eq!(forward["ts_packets"], 10);
+    assert_eq!(forward["seq_gaps"], 1);
+    assert_eq!(forward["lost"], 3);
+    assert_eq!(forward["out_of_order"], 1);
+    assert_eq!(forward["status"], "LOSS");
+
+    let stray = session(0x300);
+    assert_eq!(stray["payload"], "PSP");
+    assert_eq!(stray["seq_gaps"], 0);
+    assert_eq!(stray["status"], "not configured");
+
+    let idle = session(0x200);
+    assert_eq!(idle["src"], "10.0.0.2");
+    assert_eq!(idle["dst"], "10.0.0.1");
+    assert_eq!(idle["configured_by"], "StaticPwConfig 10.0.0.2 return 2");
+    assert_eq!(idle["status"], "no traffic");
+}
+
+const GRANDMASTER: [u8; 8] = [0x00, 0x11, 0x22, 0xff, 0xfe, 0x00, 0x00, 0x01];
+const RPD_CLOCK: [u8; 8] = [0x00, 0x11, 0x22, 0xff, 0xfe, 0x00, 0x00, 0x02];
+
+/// PTPv2 message from port 1 of `clock`, with a log message interval of -3, in a UDP
+/// datagram to the event port.
+fn ptp_udp(message_type: u8, clock: [u8; 8], sequence_id: u16, body: &[u8]) -> Vec<u8> {
+    let mut ptp = vec![message_type, 2];
+    ptp.extend_from_slice(&(34 + body.len() as u16).to_be_bytes());
+    ptp.extend_from_slice(&[0; 16]);
+    ptp.extend_from_slice(&clock);
+    ptp.extend_from_slice(&[0, 1]);
+    ptp.extend_from_slice(&sequence_id.to_be_bytes());
+    ptp.extend_from_slice(&[0, 0xfd]);
+    ptp.extend_from_slice(body);
+
+    let mut udp = [319u16.to_be_bytes(), 319u16.to_be_bytes()].concat();
+    udp.extend_from_slice(&(8 + ptp.len() as u16).to_be_bytes());
+    udp.extend_from_slice(&[0, 0]);
+    udp.extend(ptp);
+    udp
+}
+
+#[test]
+fn ptp_measures_the_sync_intervals_and_delay_responses() {
+    let mut announce = vec![0; 13];
+    announce.extend_from_slice(&[128, 6, 0x21, 0x4e, 0x5d, 128]);
+    announce.extend_from_slice(&GRANDMASTER);
+    announce.extend_from_slice(&[0, 0, 0x20]);
+    let delay_resp = [vec![0; 10], RPD_CLOCK.to_vec(), vec![0, 1]].concat();
+
+    let to_rpd = |usec: u32, udp| (usec, core().ip(), rpd().ip(), udp);
+    let to_core = |usec: u32, udp| (usec, rpd().ip(), core().ip(), udp);
+    // Sync 3 is missing, the second Delay_Req is not answered.
+    let frames = [
+        to_rpd(0, ptp_udp(0xb, GRANDMASTER, 1, &announce)),
+        to_rpd(0, ptp_udp(0x0, GRANDMASTER, 1, &[0; 10])),
+        to_core(100_000, ptp_udp(0x1, RPD_CLOCK, 7, &[0; 10])),
+        to_rpd(102_000, ptp_udp(0x9, GRANDMASTER, 7, &delay_resp)),
+        to_rpd(125_000, ptp_udp(0x0, GRANDMASTER, 2, &[0; 10])),
+        to_rpd(250_000, ptp_udp(0x0, GRANDMASTER, 4, &[0; 10])),
+        to_core(400_000, ptp_udp(0x1, RPD_CLOCK, 8, &[0; 10])),
+        to_rpd(500_000, ptp_udp(0x0, GRANDMASTER, 5, &[0; 10])),
+    ];
+    let builder = frames
+        .into_iter()
+        .fold(CaptureBuilder::new(), |builder, (usec, src, dst, udp)| {
+            builder.at(1, usec).frame(ipv4_frame(src, dst, 17, &udp))
+        });
+    // PtpResultNotification of port 0 with PtpResult 2.
+    let result = TlvNode::complex(
+        86,
+        vec![TlvNode::u8(1, 3), TlvNode::u8(4, 0), TlvNode::u8(5, 2)],
+    );
+    let mut flow = TcpFlow::new(rpd(), core());
+    let pcap = builder
+        .at(1, 600_000)
+        .send(&mut flow, Direction::FromClient, &notify(1, vec![result]))
+        .build();
+    let capture = TempCapture::new("ptp", &pcap);
+
+    let timing = ptp_timing(&[&capture.0]).unwrap();
+    let clocks = rows(&timing.clocks());
+    assert_eq!(clocks.len(), 2);
+    let master = &clocks[0];
+    assert_eq!(master["clock"], "001122.fffe.000001-1");
+    assert_eq!(master["transport"], "UDP");
+    assert_eq!(master["role"], "master");
+    assert_eq!(master["sync"], 4);
+    assert_eq!(master["delay_resp"], 1);
+    assert_eq!(master["log_sync_interval"], -3);
+    // 125, 125 and 250 ms between the Sync.
+    assert_eq!(master["sync_interval_ms"], 166.666);
+    assert_eq!(master["max_sync_interval_ms"], 250.0);
+    assert_eq!(master["sync_seq_gaps"], 1);
+    assert_eq!(master["grandmaster"], "001122.fffe.000001");
+    assert_eq!(master["clock_class"], 6);
+    assert_eq!(master["time_source"], "GPS");
+
+    let slave = &clocks[1];
+    assert_eq!(slave["src"], "10.0.0.2");
+    assert_eq!(slave["role"], "slave");
+    assert_eq!(slave["delay_req"], 2);
+    assert_eq!(slave["delay_resp_ms"], 2.0);
+    assert_eq!(slave["unanswered"], 1);
+
+    let results = rows(&timing.results());
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ptp_result"], 2);
+    assert_eq!(results[0]["rpd_clock"], "001122.fffe.000002-1");
+    assert_eq!(results[0]["master_clock"], "001122.fffe.000001-1");
+    assert_eq!(results[0]["grandmaster"], "001122.fffe.000001");
+    assert_eq!(results[0]["sync"], 4);
+    assert_eq!(results[0]["max_sync_gap_ms"], 250.0);
+    assert_eq!(results[0]["last_sync_ms"], 100.0);
+    assert_eq!(results[0]["status"], "NOT SYNCHRONIZED");
+}